@@ -0,0 +1,216 @@
+use crate::deck::{Stack, Card};
+use crate::game::Game;
+use text_io::read;
+use std::fmt;
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+// Piles a card can jump over: its neighbour, or the pile three to the left
+const JUMPS: [usize; 2] = [1, 3];
+// How many piles of the row are drawn before it wraps onto the next line
+const ROW_WIDTH: usize = 13;
+
+pub struct Accordion
+{
+    deck: Stack,
+    row: Vec<Vec<Card>>,
+}
+
+impl Accordion
+{
+    fn new() -> Accordion
+    {
+        let mut deck = Stack::new_deck(false);
+
+        deck.shuffle();
+
+        Accordion { deck, row: vec![] }
+    }
+
+    pub fn play()
+    {
+        let mut game = Accordion::new();
+
+        while !game.win()
+        {
+            game.handle_input();
+        }
+
+        println!("You won 😀");
+    }
+
+    // Moves the pile at `from` onto the pile `jump` places to its left, when
+    // their top cards share a suit or a rank
+    fn make_move(&mut self, from: usize, jump: usize) -> Result<(), String>
+    {
+        if !JUMPS.contains(&jump) || from < jump || from >= self.row.len()
+        {
+            return Err(String::from("Unable to make that move"))
+        }
+
+        let to = from - jump;
+        let move_card = self.row[from].last().unwrap();
+        let to_card = self.row[to].last().unwrap();
+        if move_card.suit != to_card.suit && move_card.rank != to_card.rank
+        {
+            return Err(String::from("Unable to make that move"))
+        }
+
+        let pile = self.row.remove(from);
+        self.row[to].extend(pile);
+
+        Ok(())
+    }
+
+    fn handle_move(&mut self) -> Result<(), String>
+    {
+        println!("Select a pile between 1 and {}", self.row.len());
+        let from: usize = read!("{}\n");
+
+        let jumps = ["Onto its neighbour", "Three to the left", "Back"];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&jumps)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(0) => self.make_move(from.wrapping_sub(1), 1),
+            Some(1) => self.make_move(from.wrapping_sub(1), 3),
+            _ => Err(String::from("Going back"))
+        }
+    }
+
+    fn handle_deal(&mut self) -> Result<(), String>
+    {
+        if self.deck.size() == 0
+        {
+            return Err(String::from("The stock is empty"))
+        }
+
+        self.row.push(vec![self.deck.draw()]);
+
+        Ok(())
+    }
+
+    fn can_move(&self) -> bool
+    {
+        (0..self.row.len()).any(|from| JUMPS.iter().any(|&jump| {
+            from >= jump && {
+                let move_card = self.row[from].last().unwrap();
+                let to_card = self.row[from - jump].last().unwrap();
+                move_card.suit == to_card.suit || move_card.rank == to_card.rank
+            }
+        }))
+    }
+}
+
+impl fmt::Display for Accordion {
+    // The row is laid out left to right with each pile's number above its top card,
+    // wrapping onto a new line every `ROW_WIDTH` piles
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![
+            format!("🃏 : {} Cards remaining", self.deck.size()),
+            "+ ------------------- +".to_string()
+            ];
+        for (line, piles) in self.row.chunks(ROW_WIDTH).enumerate()
+        {
+            let numbers: Vec<String> = (0..piles.len()).map(|i| format!("{:>3}", line * ROW_WIDTH + i + 1)).collect();
+            let tops: Vec<String> = piles.iter().map(|p| p.last().unwrap().to_string()).collect();
+            let sizes: Vec<String> = piles.iter().map(|p| if p.len() > 1 { format!("x{:<2}", p.len()) } else { "   ".to_string() }).collect();
+            lines.push(numbers.join(" | "));
+            lines.push(tops.join(" | "));
+            lines.push(sizes.join(" | "));
+        };
+        if !self.can_move() && self.deck.size() > 0
+        {
+            lines.push("No moves, deal another card".to_string());
+        }
+
+        lines.push("+ ------------------- +".to_string());
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Accordion
+{
+    fn win(&self) -> bool
+    {
+        self.deck.size() == 0 && self.row.len() == 1
+    }
+
+    fn handle_input(&mut self)
+    {
+        let commands = ["Display", "Move", "Deal"];
+        println!("Select a command: ");
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&commands)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) =>
+            {
+                println!("+ -------------------- + ");
+                match commands[index] {
+                    "Display" => println!("{}", self),
+                    "Move" => self.handle_move().unwrap_or_else(|err| println!("{}", err)),
+                    "Deal" => self.handle_deal().unwrap_or_else(|err| println!("{}", err)),
+                    _ => {
+                        println!("Invalid selection");
+                        self.handle_input();
+                    }
+                }
+            }
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::Suit;
+
+    fn card(suit: Suit, rank: u8) -> Card
+    {
+        Card { suit, rank, visible: true }
+    }
+
+    #[test]
+    fn moves_onto_matching_piles()
+    {
+        let mut game = Accordion::new();
+        game.row = vec![
+            vec![card(Suit::Heart, 4)],
+            vec![card(Suit::Club, 9)],
+            vec![card(Suit::Spade, 2)],
+            vec![card(Suit::Diamond, 4)],
+        ];
+
+        assert!(game.make_move(3, 1).is_err());
+        assert!(game.make_move(3, 3).is_ok());
+        assert_eq!(game.row.len(), 3);
+        assert_eq!(game.row[0].last().unwrap().suit, Suit::Diamond);
+
+        game.row[1] = vec![card(Suit::Diamond, 9)];
+        assert!(game.make_move(1, 1).is_ok());
+        assert_eq!(game.row[0].len(), 3);
+    }
+
+    #[test]
+    fn deal_extends_row()
+    {
+        let mut game = Accordion::new();
+
+        while game.deck.size() > 0 { game.handle_deal().unwrap() }
+
+        assert_eq!(game.row.len(), 52);
+        assert!(game.handle_deal().is_err());
+        assert_eq!(format!("{}", game).lines().count(), 2 + 4 * 3 + 1);
+    }
+}
@@ -1,22 +1,59 @@
-#[path = "../utils/deck.rs"] mod deck;
-#[path = "../games/game.rs"] mod game;
-
-use game::Game;
-use deck::{Stack, Card};
+use crate::game::Game;
+use crate::deck::{Stack, Card};
 use std::collections::HashMap;
 use text_io::read;
 use std::fmt;
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AcesUpRules
+{
+    pub columns: usize,
+    // A top card may also be moved onto a card one rank above it, whatever its suit
+    pub build_down: bool,
+    // A single card may be set aside in a reserve and brought back later
+    pub reserve: bool,
+    // Aces showing on a column may be discarded for a point
+    pub discard_aces: bool,
+}
+
+impl Default for AcesUpRules
+{
+    fn default() -> AcesUpRules
+    {
+        AcesUpRules { columns: 4, build_down: false, reserve: false, discard_aces: false }
+    }
+}
 
+const VARIANTS: [(&str, AcesUpRules); 5] = [
+    ("Aces Up", AcesUpRules { columns: 4, build_down: false, reserve: false, discard_aces: false }),
+    ("Idiot's Delight", AcesUpRules { columns: 4, build_down: true, reserve: false, discard_aces: false }),
+    ("Firing Squad", AcesUpRules { columns: 4, build_down: false, reserve: true, discard_aces: false }),
+    ("Five Columns", AcesUpRules { columns: 5, build_down: false, reserve: false, discard_aces: false }),
+    ("Aces to Score", AcesUpRules { columns: 4, build_down: false, reserve: false, discard_aces: true }),
+];
 
 pub struct AcesUpGame 
 {
+    rules: AcesUpRules,
     deck: Stack,
-    columns: Vec<Vec<Card>>
+    columns: Vec<Vec<Card>>,
+    reserve: Option<Card>,
+    score: usize,
 }
 
 impl AcesUpGame 
 {
     pub fn new() -> AcesUpGame
+    {
+        AcesUpGame::with_rules(AcesUpRules::default())
+    }
+
+    pub fn with_rules(rules: AcesUpRules) -> AcesUpGame
     {
         let mut deck = Stack::new_deck_reverse(false);
 
@@ -24,20 +61,36 @@ impl AcesUpGame
 
         AcesUpGame
         {
+            rules,
             deck,
-            columns: vec![vec![],vec![],vec![],vec![]]
+            columns: (0..rules.columns).map(|_| vec![]).collect(),
+            reserve: None,
+            score: 0,
         }
     }
 
     pub fn play()
     {
-        let mut game = AcesUpGame::new();
+        let names: Vec<&str> = VARIANTS.iter().map(|(name, _)| *name).collect();
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select a variant")
+            .items(&names)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        let mut game = match selection { Some(index) => AcesUpGame::with_rules(VARIANTS[index].1), None => AcesUpGame::new() };
 
         while game.deck.size() > 0
         {
             game.turn()
         }
 
+        if game.rules.discard_aces
+        {
+            println!("You scored {}", game.score);
+        }
+
         if game.win() 
         { 
             println!("You won 😀");
@@ -50,14 +103,20 @@ impl AcesUpGame
 
     pub fn turn(&mut self)
     {
-        for i in 0..4
+        self.deal_row();
+        self.handle_input();
+    }
+
+    // Deals a card onto each column, or onto as many as the deck still covers
+    fn deal_row(&mut self)
+    {
+        let count = self.columns.len().min(self.deck.size());
+        for column in self.columns.iter_mut().take(count)
         {
             let mut card = self.deck.draw();
             card.set_visible(true);
-            self.columns[i].push(card);
+            column.push(card);
         };
-
-        self.handle_input();
     }
 
     fn clean(&mut self)
@@ -68,14 +127,13 @@ impl AcesUpGame
             let mut to_remove = vec![];
             for (i, top) in self.columns.iter().enumerate().filter_map(|(i,c)| 
                 {
-                    if c.len() > 0 { return Some((i,c.last().unwrap())) }
-                    else { return None }
+                    c.last().map(|top| (i, top))
                 })
             {
                 let entry = tops.entry(top.suit).or_insert((top.rank, i));
                 if entry.0 < top.rank
                 {
-                    to_remove.push(entry.1.clone());
+                    to_remove.push(entry.1);
                     *entry = (top.rank, i);
                 }
                 else if entry.0 > top.rank
@@ -83,7 +141,7 @@ impl AcesUpGame
                     to_remove.push(i)
                 }
             }
-            if to_remove.len() == 0
+            if to_remove.is_empty()
             {
                 break;
             }
@@ -94,77 +152,91 @@ impl AcesUpGame
         }
     }
 
-    fn try_move_aces(&mut self)
+    fn discard_ace(&mut self, column: usize) -> Result<(), String>
     {
-        let mut last_move_dest: isize = -1;
-        loop
+        if !self.rules.discard_aces
         {
-            let (last_open, last_ace) = self.can_move_positions();
+            return Err(String::from("Aces cannot be discarded in this variant"))
+        }
 
-            if last_move_dest == last_ace
-            {
-                break
-            }
-            else if last_open >= 0 && last_ace >= 0
-            {
-                let ace = self.columns[last_ace as usize].pop().unwrap();
-                self.columns[last_open as usize].push(ace);
-                last_move_dest = last_open;
-            }
-            else
-            {
-                break
-            }
+        match self.columns.get(column).and_then(|c| c.last())
+        {
+            Some(c) if c.is_ace() => {
+                self.columns[column].pop();
+                self.score += 1;
+                Ok(())
+            },
+            _ => Err(String::from("There is no ace on that column"))
         }
     }
 
+    // The reserve, when there is one, is numbered one past the last column
+    fn reserve_index(&self) -> Option<usize>
+    {
+        if self.rules.reserve { Some(self.columns.len()) } else { None }
+    }
+
     // set toggle to true if getting a "from", set to false if getting a "to"
     fn get_move_input(&self, toggle: bool) -> usize
     {
         let from_to_str = if toggle { "from" } else { "to" };
-        println!("Input a column to move {}: (1-4,L-R) ", from_to_str);
+        let reserve_str = match self.reserve_index() { Some(i) => format!(", {}: reserve", i + 1), None => String::new() };
+        println!("Input a column to move {}: (1-{},L-R{}) ", from_to_str, self.columns.len(), reserve_str);
         let command: usize = read!("{}\n");
-        let comm_index = command - 1;
-        if comm_index <= 3
-        && (( 
-                toggle && self.columns[comm_index].len() >= 1 
-            ) || (
-                !toggle && self.columns[comm_index].len() == 0)
-            )
+        let comm_index = command.wrapping_sub(1);
+        if (toggle && self.moving_card(comm_index).is_some())
+            || (!toggle && (comm_index < self.columns.len() || Some(comm_index) == self.reserve_index()))
         {
             return comm_index
         }
         
         println!("Invalid input, try again");
-        return self.get_move_input(toggle)
+        self.get_move_input(toggle)
     }
 
-    fn handle_move(&mut self, from: usize, to: usize)
+    fn moving_card(&self, from: usize) -> Option<&Card>
     {
-        let card = self.columns[from].pop().unwrap();
-        self.columns[to].push(card);
+        if Some(from) == self.reserve_index() { return self.reserve.as_ref() }
+
+        self.columns.get(from)?.last()
     }
 
-    fn can_move_positions(&self) -> (isize, isize)
+    fn is_move_valid(&self, from: usize, to: usize) -> bool
     {
-        let mut last_open: isize = -1;
-        let mut last_ace: isize = -1;
-        
-        self.columns.iter().map(|c| c.last()).enumerate().for_each(|(i,c)| {
-            match c {
-                Some(s) => if s.is_ace() { last_ace = i as isize; }
-                None => last_open = i as isize
-            }
-        });
+        if from == to { return false }
+        let move_card = match self.moving_card(from) { Some(c) => c, None => return false };
+
+        if Some(to) == self.reserve_index() { return self.reserve.is_none() }
+
+        match self.columns.get(to)
+        {
+            Some(column) => match column.last() {
+                Some(c) => self.rules.build_down && move_card.rank + 1 == c.rank,
+                None => true
+            },
+            None => false
+        }
+    }
+
+    fn handle_move(&mut self, from: usize, to: usize) -> Result<(), String>
+    {
+        if !self.is_move_valid(from, to)
+        {
+            return Err(String::from("Unable to make that move"))
+        }
+
+        let card = if Some(from) == self.reserve_index() { self.reserve.take().unwrap() } else { self.columns[from].pop().unwrap() };
+
+        if Some(to) == self.reserve_index() { self.reserve = Some(card) } else { self.columns[to].push(card) }
 
-        (last_open, last_ace)
+        Ok(())
     }
 
     fn can_move(&self) -> bool
     {
-        let (last_open, last_ace) = self.can_move_positions();
-        println!("{}, {}", last_open, last_ace);
-        last_open > -1 && last_ace > -1
+        let positions = self.columns.len() + self.reserve_index().map_or(0, |_| 1);
+
+        (0..positions).any(|from| (0..positions).any(|to| self.is_move_valid(from, to)))
     }
 }
 
@@ -175,38 +247,51 @@ impl fmt::Display for AcesUpGame
         let mut lines = vec![
             format!("🃏 : {} Cards remaining", self.deck.size()),
             format!("+ ------------------- +")];
+        if self.rules.reserve
+        {
+            let reserve_str = match self.reserve { Some(c) => c.to_string(), None => String::from("---") };
+            lines.push(format!("+ --- Reserve: {} --- +", reserve_str));
+        }
+        if self.rules.discard_aces
+        {
+            lines.push(format!("+ --- Score: {} --- +", self.score));
+        }
         for i in 0..max_column
         {
             let strings: Vec<String> = self.columns.iter().map(|c| c.get(i)).map(|c| {
                 match c {
                     Some(m) => format!("{}", m),
-                    None => format!("   ")
+                    None => "   ".to_string()
                 }
             }).collect();
             lines.push(strings.join(" | "));
         };
 
-        lines.push(format!("+ ------------------- +"));
+        lines.push("+ ------------------- +".to_string());
         write!(f, "{}", lines.join("\n"))
     }
 }
 
-impl game::Game for AcesUpGame 
+impl Game for AcesUpGame 
 {
     fn win(&self) -> bool
     {
-        for i in 0..4 
+        // With aces discarded for points, the whole deck has to go
+        if self.rules.discard_aces
         {
-            if !self.columns[i][0].is_ace() { return false }
-            if self.columns[i].len() > 1 { return false }
+            return self.reserve.is_none() && self.columns.iter().all(|c| c.is_empty())
         }
 
-        return true
+        // Otherwise the four aces are all that is left, one to a column
+        self.reserve.is_none()
+            && self.columns.iter().filter(|c| !c.is_empty()).count() == 4
+            && self.columns.iter().all(|c| c.len() <= 1 && c.iter().all(|card| card.is_ace()))
     }
 
     fn handle_input(&mut self)
     {
-        println!("Input a command: (D: display board, M: move, C: clean, N: next) ");
+        let score_str = if self.rules.discard_aces { ", S: score an ace" } else { "" };
+        println!("Input a command: (D: display board, M: move, C: clean{}, N: next) ", score_str);
         let command: String = read!("{}\n");
         let command = command.as_str();
         match command {
@@ -218,21 +303,29 @@ impl game::Game for AcesUpGame
                 if !self.can_move()
                 {
                     println!("Sorry, you cannot move on the current board.");
-                    self.handle_input();
                 }
-                let from = self.get_move_input(true);
-                let to = self.get_move_input(false);
-                self.handle_move(from, to);
+                else
+                {
+                    let from = self.get_move_input(true);
+                    let to = self.get_move_input(false);
+                    self.handle_move(from, to).unwrap_or_else(|err| println!("{}", err));
+                }
                 self.handle_input();
             },
             "C" => {
                 self.clean();
                 self.handle_input();
             }
-            "N" => return,
+            "S" if self.rules.discard_aces => {
+                println!("Input a column to score: (1-{},L-R) ", self.columns.len());
+                let column: usize = read!("{}\n");
+                self.discard_ace(column.wrapping_sub(1)).unwrap_or_else(|err| println!("{}", err));
+                self.handle_input();
+            }
+            "N" => (),
             _ => {
                 println!("Invalid command, try again");
-                return self.handle_input()
+                self.handle_input()
             }
         }
     }
@@ -242,7 +335,7 @@ impl game::Game for AcesUpGame
 mod test 
 {
     use super::*;
-    use deck::{Suit};
+    use crate::deck::Suit;
 
     #[test]
     fn clean()
@@ -278,6 +371,132 @@ mod test
             vec![Card { suit: Suit::Spade, rank: 6, visible: false }, Card { suit: Suit::Spade, rank: 14, visible: false }]
         ];
 
-        assert_eq!(game.win(), false)
+        assert!(!game.win())
+    }
+
+    #[test]
+    fn win()
+    {
+        let mut game = AcesUpGame::new();
+
+        game.columns = vec![
+            vec![Card { suit: Suit::Club, rank: 14, visible: true }],
+            vec![Card { suit: Suit::Heart, rank: 14, visible: true }],
+            vec![Card { suit: Suit::Spade, rank: 14, visible: true }],
+            vec![Card { suit: Suit::Diamond, rank: 14, visible: true }]
+        ];
+
+        assert!(game.win())
+    }
+
+    #[test]
+    fn win_needs_one_ace_per_column()
+    {
+        let mut game = AcesUpGame::new();
+
+        game.columns = vec![
+            vec![Card { suit: Suit::Club, rank: 14, visible: true }, Card { suit: Suit::Heart, rank: 14, visible: true }],
+            vec![],
+            vec![Card { suit: Suit::Spade, rank: 14, visible: true }],
+            vec![Card { suit: Suit::Diamond, rank: 14, visible: true }]
+        ];
+        assert!(!game.win());
+
+        let mut game = AcesUpGame::with_rules(VARIANTS[3].1);
+
+        game.columns = vec![
+            vec![Card { suit: Suit::Club, rank: 14, visible: true }],
+            vec![Card { suit: Suit::Heart, rank: 14, visible: true }],
+            vec![],
+            vec![Card { suit: Suit::Spade, rank: 14, visible: true }],
+            vec![Card { suit: Suit::Diamond, rank: 14, visible: true }]
+        ];
+        assert!(game.win());
+    }
+
+    #[test]
+    fn five_columns()
+    {
+        let mut game = AcesUpGame::with_rules(VARIANTS[3].1);
+
+        while game.deck.size() > 0
+        {
+            game.deal_row()
+        }
+
+        assert_eq!(game.columns.len(), 5);
+        assert_eq!(game.columns[0].len(), 11);
+        assert_eq!(game.columns[4].len(), 10);
+    }
+
+    #[test]
+    fn idiots_delight_builds_down()
+    {
+        let mut game = AcesUpGame::with_rules(VARIANTS[1].1);
+
+        game.columns = vec![
+            vec![Card { suit: Suit::Club, rank: 9, visible: true }],
+            vec![Card { suit: Suit::Heart, rank: 8, visible: true }],
+            vec![Card { suit: Suit::Spade, rank: 7, visible: true }],
+            vec![Card { suit: Suit::Diamond, rank: 3, visible: true }]
+        ];
+
+        assert!(game.handle_move(1, 0).is_ok());
+        assert!(game.handle_move(2, 3).is_err());
+        assert!(!AcesUpGame::new().is_move_valid(1, 0));
+    }
+
+    #[test]
+    fn firing_squad_reserve()
+    {
+        let mut game = AcesUpGame::with_rules(VARIANTS[2].1);
+
+        game.columns = vec![
+            vec![Card { suit: Suit::Club, rank: 9, visible: true }],
+            vec![Card { suit: Suit::Heart, rank: 8, visible: true }],
+            vec![],
+            vec![]
+        ];
+
+        assert!(game.handle_move(0, 4).is_ok());
+        assert!(game.handle_move(1, 4).is_err());
+        assert!(game.handle_move(4, 2).is_ok());
+        assert_eq!(game.reserve, None);
+    }
+
+    #[test]
+    fn aces_to_score()
+    {
+        let mut game = AcesUpGame::with_rules(VARIANTS[4].1);
+
+        game.columns = vec![
+            vec![Card { suit: Suit::Club, rank: 14, visible: true }],
+            vec![],
+            vec![],
+            vec![]
+        ];
+
+        assert!(!game.win());
+        assert!(game.discard_ace(0).is_ok());
+        assert_eq!(game.score, 1);
+        assert!(game.win());
+        assert!(AcesUpGame::new().discard_ace(0).is_err());
+    }
+
+    #[test]
+    fn cleaning_scores_nothing()
+    {
+        let mut game = AcesUpGame::with_rules(VARIANTS[4].1);
+
+        game.columns = vec![
+            vec![Card { suit: Suit::Club, rank: 13, visible: true }],
+            vec![Card { suit: Suit::Club, rank: 12, visible: true }],
+            vec![Card { suit: Suit::Club, rank: 11, visible: true }],
+            vec![]
+        ];
+
+        game.clean();
+        assert_eq!(game.columns[1], vec![]);
+        assert_eq!(game.score, 0);
     }
 }
\ No newline at end of file
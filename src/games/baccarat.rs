@@ -0,0 +1,540 @@
+use std::fmt;
+use std::collections::HashMap;
+use crate::deck::Card;
+use crate::shoe::Shoe;
+use crate::game::Game;
+use text_io::read;
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const DECKS: usize = 8;
+// The cut card goes in about 62 cards, a little over a deck, from the back
+const PENETRATION: f64 = 0.85;
+const STARTING_BANKROLL: f64 = 1000.0;
+const COMMISSION: f64 = 0.05;
+const ROWS: usize = 6;
+// How many columns of each scoreboard fit on screen
+const BEAD_COLUMNS: usize = 12;
+const ROAD_COLUMNS: usize = 24;
+
+// Aces count one, court cards and tens nothing
+pub fn card_value(card: &Card) -> u8
+{
+    match card.rank {
+        14 => 1,
+        10..=13 => 0,
+        rank => rank
+    }
+}
+
+// Only the last digit of a hand counts
+pub fn hand_total(cards: &[Card]) -> u8
+{
+    cards.iter().map(card_value).sum::<u8>() % 10
+}
+
+pub fn player_draws(player: u8) -> bool
+{
+    player <= 5
+}
+
+// The banker's side of the tableau, going on what the player's third card was, or
+// drawing like the player would when the player stood
+pub fn banker_draws(banker: u8, player_third: Option<u8>) -> bool
+{
+    match player_third {
+        None => banker <= 5,
+        Some(third) => match banker {
+            0..=2 => true,
+            3 => third != 8,
+            4 => (2..=7).contains(&third),
+            5 => (4..=7).contains(&third),
+            6 => (6..=7).contains(&third),
+            _ => false
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome
+{
+    Player,
+    Banker,
+    Tie,
+}
+
+impl Outcome
+{
+    fn letter(&self) -> &'static str
+    {
+        match self {
+            Outcome::Player => "P",
+            Outcome::Banker => "B",
+            Outcome::Tie => "T"
+        }
+    }
+}
+
+// One deal of baccarat, which plays itself out by the tableau
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coup
+{
+    pub player: Vec<Card>,
+    pub banker: Vec<Card>,
+}
+
+impl Coup
+{
+    // Two cards each, dealt alternately, then neither draws on a natural eight or
+    // nine, and otherwise the player and then the banker draw by the rules
+    pub fn deal(mut draw: impl FnMut() -> Card) -> Coup
+    {
+        let mut coup = Coup { player: vec![], banker: vec![] };
+        for _ in 0..2
+        {
+            coup.player.push(draw());
+            coup.banker.push(draw());
+        }
+        if coup.natural() { return coup }
+
+        let mut player_third = None;
+        if player_draws(hand_total(&coup.player))
+        {
+            let card = draw();
+            player_third = Some(card_value(&card));
+            coup.player.push(card);
+        }
+        if banker_draws(hand_total(&coup.banker), player_third)
+        {
+            coup.banker.push(draw());
+        }
+        coup
+    }
+
+    pub fn natural(&self) -> bool
+    {
+        hand_total(&self.player[..2]) >= 8 || hand_total(&self.banker[..2]) >= 8
+    }
+
+    pub fn outcome(&self) -> Outcome
+    {
+        let (player, banker) = (hand_total(&self.player), hand_total(&self.banker));
+
+        if player > banker { Outcome::Player } else if banker > player { Outcome::Banker } else { Outcome::Tie }
+    }
+
+    pub fn player_pair(&self) -> bool
+    {
+        self.player[0].rank == self.player[1].rank
+    }
+
+    pub fn banker_pair(&self) -> bool
+    {
+        self.banker[0].rank == self.banker[1].rank
+    }
+}
+
+impl fmt::Display for Coup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards = |cards: &[Card]| cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ");
+
+        write!(f, "Player: {} ({})\nBanker: {} ({})", cards(&self.player), hand_total(&self.player), cards(&self.banker), hand_total(&self.banker))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bet
+{
+    Player,
+    Banker,
+    Tie,
+    PlayerPair,
+    BankerPair,
+}
+
+const BETS: [Bet; 5] = [Bet::Player, Bet::Banker, Bet::Tie, Bet::PlayerPair, Bet::BankerPair];
+
+impl Bet
+{
+    // What comes back, stake included. Player and banker push on a tie, the banker
+    // pays even money less commission, a tie eight to one and a pair eleven to one.
+    pub fn returns(&self, coup: &Coup, stake: f64) -> f64
+    {
+        let outcome = coup.outcome();
+
+        match self {
+            Bet::Player | Bet::Banker if outcome == Outcome::Tie => stake,
+            Bet::Player if outcome == Outcome::Player => stake * 2.0,
+            Bet::Banker if outcome == Outcome::Banker => stake * (2.0 - COMMISSION),
+            Bet::Tie if outcome == Outcome::Tie => stake * 9.0,
+            Bet::PlayerPair if coup.player_pair() => stake * 12.0,
+            Bet::BankerPair if coup.banker_pair() => stake * 12.0,
+            _ => 0.0
+        }
+    }
+}
+
+impl fmt::Display for Bet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Bet::Player => "Player",
+            Bet::Banker => "Banker",
+            Bet::Tie => "Tie",
+            Bet::PlayerPair => "Player pair",
+            Bet::BankerPair => "Banker pair"
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Every result in the shoe, in columns of six read top to bottom
+pub fn bead_plate(history: &[Outcome]) -> String
+{
+    let columns = history.len().div_ceil(ROWS);
+    let first = columns.saturating_sub(BEAD_COLUMNS);
+
+    let rows: Vec<String> = (0..ROWS).map(|row| {
+        (first..columns).map(|col| history.get(col * ROWS + row).map_or(" ", |o| o.letter())).collect::<Vec<&str>>().join(" ")
+    }).collect();
+    rows.join("\n")
+}
+
+// Runs of player or banker wins, each in a column of its own. A run that reaches the
+// bottom, or meets an earlier run's tail, carries on to the right for the rest of
+// the run. Ties are marked with a slash on the result before them, or on the first
+// result when the shoe opens with a tie.
+pub fn big_road(history: &[Outcome]) -> String
+{
+    let mut grid: HashMap<(usize, usize), (Outcome, bool)> = HashMap::new();
+    let mut last: Option<(usize, usize, Outcome)> = None;
+    let mut start = 0;
+    let mut width = 0;
+    // Once a run has turned right it stays in that row
+    let mut tailing = false;
+    let mut opening_tie = false;
+
+    for outcome in history.iter()
+    {
+        let cell = match (*outcome, last) {
+            (Outcome::Tie, Some((row, col, _))) => {
+                grid.get_mut(&(row, col)).unwrap().1 = true;
+                continue
+            },
+            (Outcome::Tie, None) => {
+                opening_tie = true;
+                continue
+            },
+            (_, Some((row, col, previous))) if previous == *outcome => {
+                tailing = tailing || row + 1 == ROWS || grid.contains_key(&(row + 1, col));
+                if tailing { (row, col + 1) } else { (row + 1, col) }
+            },
+            (_, Some(_)) => {
+                start += 1;
+                tailing = false;
+                (0, start)
+            },
+            (_, None) => (0, 0)
+        };
+        grid.insert(cell, (*outcome, opening_tie));
+        opening_tie = false;
+        width = width.max(cell.1 + 1);
+        last = Some((cell.0, cell.1, *outcome));
+    }
+
+    let first = width.saturating_sub(ROAD_COLUMNS);
+    let rows: Vec<String> = (0..ROWS).map(|row| {
+        (first..width).map(|col| match grid.get(&(row, col)) {
+            Some((outcome, tie)) => format!("{}{}", outcome.letter(), if *tie { "/" } else { " " }),
+            None => "  ".to_string()
+        }).collect::<Vec<String>>().join("")
+    }).collect();
+    rows.join("\n")
+}
+
+pub struct Baccarat
+{
+    shoe: Shoe,
+    bankroll: f64,
+    bets: Vec<(Bet, f64)>,
+    history: Vec<Outcome>,
+    leave: bool,
+}
+
+impl Baccarat
+{
+    pub fn new() -> Result<Baccarat, String>
+    {
+        Ok(Baccarat { shoe: Shoe::new(DECKS, PENETRATION)?, bankroll: STARTING_BANKROLL, bets: vec![], history: vec![], leave: false })
+    }
+
+    pub fn play()
+    {
+        let mut game = match Baccarat::new() {
+            Ok(game) => game,
+            Err(err) => return println!("{}", err)
+        };
+
+        while !game.leave && (game.bankroll >= 1.0 || !game.bets.is_empty())
+        {
+            game.handle_input();
+        }
+
+        println!("You leave the table with {:.2}", game.bankroll);
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    pub fn place(&mut self, bet: Bet, stake: f64) -> Result<(), String>
+    {
+        if stake < 1.0 || stake > self.bankroll
+        {
+            return Err(format!("Bets must be between 1 and {:.2}", self.bankroll))
+        }
+
+        self.bankroll -= stake;
+        self.bets.push((bet, stake));
+        Ok(())
+    }
+
+    // Pays out the bets on a coup and returns what they won or lost between them
+    pub fn settle(&mut self, coup: &Coup) -> f64
+    {
+        let staked: f64 = self.bets.iter().map(|(_, stake)| stake).sum();
+        let returned: f64 = self.bets.iter().map(|(bet, stake)| bet.returns(coup, *stake)).sum();
+        self.bankroll += returned;
+        self.bets.clear();
+
+        returned - staked
+    }
+
+    // A fresh shoe starts a fresh scoreboard
+    pub fn deal(&mut self) -> Coup
+    {
+        if self.shoe.cut_card_reached()
+        {
+            println!("The cut card is out, reshuffling the shoe");
+            self.shoe.reshuffle();
+            self.history.clear();
+        }
+
+        let shoe = &mut self.shoe;
+        let coup = Coup::deal(|| shoe.draw());
+        self.history.push(coup.outcome());
+        coup
+    }
+
+    fn play_coup(&mut self)
+    {
+        let coup = self.deal();
+        println!("{}", coup);
+        let natural = if coup.natural() { " on a natural" } else { "" };
+        match coup.outcome() {
+            Outcome::Tie => println!("Tie{}", natural),
+            Outcome::Player => println!("Player wins{}", natural),
+            Outcome::Banker => println!("Banker wins{}", natural)
+        }
+
+        if self.bets.is_empty() { return }
+        let winnings = self.settle(&coup);
+        if winnings > 0.0 { println!("You won {:.2}", winnings) }
+        else if winnings < 0.0 { println!("You lost {:.2}", -winnings) }
+        else { println!("Push") }
+    }
+
+    fn get_stake(&self, bet: Bet) -> f64
+    {
+        println!("How much on {}? (1-{:.0})", bet, self.bankroll.floor());
+        let stake: u32 = read!("{}\n");
+
+        stake as f64
+    }
+}
+
+impl fmt::Display for Baccarat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bets: Vec<String> = self.bets.iter().map(|(bet, stake)| format!("{} {:.2}", bet, stake)).collect();
+        let lines = [
+            format!("🃏 : {} Cards remaining in the shoe", self.shoe.remaining()),
+            format!("+ --- Bankroll: {:.2} --- +", self.bankroll),
+            format!("Bets: {}", if bets.is_empty() { "none".to_string() } else { bets.join(", ") }),
+            "Bead plate:".to_string(),
+            bead_plate(&self.history),
+            "Big road:".to_string(),
+            big_road(&self.history),
+            "+ ------------------- +".to_string()
+        ];
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Baccarat
+{
+    fn win(&self) -> bool
+    {
+        self.bankroll > STARTING_BANKROLL
+    }
+
+    fn handle_input(&mut self)
+    {
+        let mut commands: Vec<String> = BETS.iter().map(|b| format!("Bet on {}", b)).collect();
+        commands.extend(["Deal", "Display", "Leave"].iter().map(|c| c.to_string()));
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select a command")
+            .items(&commands)
+            .default(BETS.len())
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) if index < BETS.len() => {
+                let stake = self.get_stake(BETS[index]);
+                self.place(BETS[index], stake).unwrap_or_else(|err| println!("{}", err));
+            },
+            Some(index) => match commands[index].as_str() {
+                "Deal" => self.play_coup(),
+                "Display" => println!("{}", self),
+                _ => self.leave = true
+            },
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    // Deals a coup from cards in the order they come out of the shoe
+    fn coup(cards: &str) -> Coup
+    {
+        let mut cards = parse_cards(cards).unwrap().into_iter();
+        Coup::deal(|| cards.next().unwrap())
+    }
+
+    #[test]
+    fn totals()
+    {
+        assert_eq!(hand_total(&parse_cards("9s8h").unwrap()), 7);
+        assert_eq!(hand_total(&parse_cards("KsTh").unwrap()), 0);
+        assert_eq!(hand_total(&parse_cards("As5h4d").unwrap()), 0);
+    }
+
+    #[test]
+    fn tableau()
+    {
+        assert!(player_draws(5));
+        assert!(!player_draws(6));
+
+        assert!(banker_draws(5, None));
+        assert!(!banker_draws(6, None));
+        assert!(banker_draws(2, Some(8)));
+        assert!(!banker_draws(3, Some(8)));
+        assert!(banker_draws(3, Some(9)));
+        assert!(banker_draws(4, Some(2)));
+        assert!(!banker_draws(4, Some(1)));
+        assert!(banker_draws(5, Some(4)));
+        assert!(!banker_draws(5, Some(8)));
+        assert!(banker_draws(6, Some(7)));
+        assert!(!banker_draws(6, Some(5)));
+        assert!(!banker_draws(7, Some(6)));
+    }
+
+    #[test]
+    fn coups()
+    {
+        // The banker's natural eight stops everything
+        let natural = coup("2s9hKc9d");
+        assert!(natural.natural());
+        assert_eq!((natural.player.len(), natural.outcome()), (2, Outcome::Banker));
+
+        // Player draws to 4 and gets a 5, so the banker on 5 draws as well
+        let both = coup("2s3h2c2d5c9h");
+        assert_eq!(both.player.len(), 3);
+        assert_eq!(both.banker.len(), 3);
+        assert_eq!(both.outcome(), Outcome::Player);
+        assert!(both.player_pair() && !both.banker_pair());
+
+        // Player stands on 7 and the banker on 6 stands too
+        let stands = coup("4s9d3c7h");
+        assert_eq!((stands.player.len(), stands.banker.len()), (2, 2));
+        assert_eq!(stands.outcome(), Outcome::Player);
+    }
+
+    #[test]
+    fn payouts()
+    {
+        let banker = coup("2s9hKc9d");
+        assert_eq!(Bet::Banker.returns(&banker, 100.0), 195.0);
+        assert_eq!(Bet::Player.returns(&banker, 100.0), 0.0);
+
+        let tie = coup("4s4h3c3d");
+        assert_eq!(Bet::Tie.returns(&tie, 10.0), 90.0);
+        assert_eq!(Bet::Player.returns(&tie, 10.0), 10.0);
+        assert_eq!(Bet::PlayerPair.returns(&tie, 10.0), 0.0);
+
+        let mut game = Baccarat::new().unwrap();
+        game.place(Bet::Player, 10.0).unwrap();
+        game.place(Bet::PlayerPair, 10.0).unwrap();
+        assert!(game.place(Bet::Tie, 5000.0).is_err());
+        assert!(game.place(Bet::Tie, 0.5).is_err());
+        assert_eq!(game.settle(&coup("4s4h4c3d")), 120.0);
+        assert_eq!(game.bankroll, STARTING_BANKROLL + 120.0);
+    }
+
+    #[test]
+    fn scoreboards()
+    {
+        use Outcome::*;
+        let history = [Banker, Banker, Tie, Player, Banker, Banker, Banker, Banker, Banker, Banker, Banker];
+
+        let bead = bead_plate(&history);
+        assert_eq!(bead.lines().next().unwrap(), "B B");
+        assert_eq!(bead.lines().nth(2).unwrap(), "T B");
+
+        // The run of seven bankers turns right at the bottom
+        let road: Vec<String> = big_road(&history).lines().map(|l| l.to_string()).collect();
+        assert_eq!(road[0], "B P B   ");
+        assert_eq!(road[1], "B/  B   ");
+        assert_eq!(road[5], "    B B ");
+    }
+
+    #[test]
+    fn dragon_tails_stay_in_their_row()
+    {
+        use Outcome::*;
+        let mut history = vec![Tie];
+        history.extend([Banker; 8]);
+        history.extend([Player; 8]);
+
+        // The players' run meets the bankers' tail and keeps right from there
+        let road: Vec<String> = big_road(&history).lines().map(|l| l.to_string()).collect();
+        assert_eq!(road[0], "B/P       ");
+        assert_eq!(road[4], "B P P P P ");
+        assert_eq!(road[5], "B B B     ");
+    }
+
+    #[test]
+    fn a_whole_shoe()
+    {
+        let mut game = Baccarat::new().unwrap();
+
+        while !game.shoe.cut_card_reached()
+        {
+            let coup = game.deal();
+            assert!(coup.player.len() <= 3 && coup.banker.len() <= 3);
+        }
+        assert!(game.history.len() > 60);
+    }
+}
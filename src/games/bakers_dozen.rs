@@ -0,0 +1,273 @@
+use crate::deck::{Stack, Card, Suit};
+use crate::game::Game;
+use std::collections::HashMap;
+use text_io::read;
+use std::fmt;
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const COLUMNS: usize = 13;
+const COLUMN_DEPTH: usize = 4;
+const FOUNDATION_SIZE: usize = 13;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Position
+{
+    Columns,
+    Foundation
+}
+
+#[derive(Copy, Clone, Debug)]
+struct MoveCommand
+{
+    location: Position,
+    i: usize
+}
+
+pub struct BakersDozen
+{
+    columns: Vec<Vec<Card>>,
+    foundations: HashMap<Suit, Vec<Card>>,
+}
+
+impl BakersDozen
+{
+    fn new() -> BakersDozen
+    {
+        let mut deck = Stack::new_deck(false);
+
+        deck.shuffle();
+
+        let columns = (0..COLUMNS).map(|_| {
+            let mut column = deck.deal(COLUMN_DEPTH);
+            // Kings can never be moved, so they are tucked under the rest of their column
+            column.sort_by_key(|c| c.rank != 13);
+            column
+        }).collect();
+
+        let mut foundations = HashMap::new();
+        foundations.insert(Suit::Club, vec![]);
+        foundations.insert(Suit::Spade, vec![]);
+        foundations.insert(Suit::Diamond, vec![]);
+        foundations.insert(Suit::Heart, vec![]);
+
+        BakersDozen { columns, foundations }
+    }
+
+    pub fn play()
+    {
+        let mut game = BakersDozen::new();
+
+        while !game.win()
+        {
+            game.handle_input();
+        }
+
+        println!("You won 😀");
+    }
+
+    fn get_move_input(&self) -> Option<MoveCommand>
+    {
+        let get_move_position = |min, max| {
+            println!("Select a number between {} and {}", min, max);
+            let position_i: usize = read!("{}\n");
+
+            position_i - 1
+        };
+
+        let positions = ["Columns", "Foundation", "Back"];
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&positions)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) =>
+            {
+                println!("+ -------------------- + ");
+                match positions[index] {
+                    "Columns" => Some(MoveCommand { location: Position::Columns, i: get_move_position(1, COLUMNS) }),
+                    "Foundation" => Some(MoveCommand { location: Position::Foundation, i: 0 }),
+                    "Back" => None,
+                    _ => {
+                        println!("Invalid selection");
+                        self.get_move_input()
+                    }
+                }
+            },
+            None => {
+                println!("Invalid selection");
+                self.get_move_input()
+            }
+        }
+    }
+
+    fn is_move_valid(&self, from: MoveCommand, to: MoveCommand) -> bool
+    {
+        if from.location != Position::Columns { return false }
+        let move_card = match self.columns.get(from.i).and_then(|c| c.last()) { Some(c) => c, None => return false };
+
+        match to.location
+        {
+            Position::Columns => {
+                if from.i == to.i { return false }
+                // Spaces are never filled in Baker's Dozen
+                match self.columns.get(to.i).and_then(|c| c.last())
+                {
+                    Some(c) => move_card.low_rank() + 1 == c.low_rank(),
+                    None => false
+                }
+            },
+            Position::Foundation => {
+                match self.foundations.get(&move_card.suit).unwrap().last()
+                {
+                    Some(c) => c.low_rank() + 1 == move_card.low_rank(),
+                    None => move_card.is_ace()
+                }
+            }
+        }
+    }
+
+    fn make_move(&mut self, from: MoveCommand, to: MoveCommand) -> Result<(), String>
+    {
+        if !self.is_move_valid(from, to)
+        {
+            return Err(String::from("Unable to make that move"))
+        }
+
+        let move_card = self.columns[from.i].pop().unwrap();
+
+        match to.location
+        {
+            Position::Columns => self.columns[to.i].push(move_card),
+            Position::Foundation => self.foundations.get_mut(&move_card.suit).unwrap().push(move_card)
+        };
+
+        Ok(())
+    }
+
+    fn handle_move(&mut self) -> Result<(), String>
+    {
+        let from = match self.get_move_input() { Some(e) => e, None => return Err(String::from("Going back"))};
+        let to = match self.get_move_input() { Some(e) => e, None => return Err(String::from("Going back"))};
+
+        self.make_move(from, to)
+    }
+}
+
+impl fmt::Display for BakersDozen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let max_column = self.columns.iter().map(|c| c.len()).max().unwrap();
+        let top_str = |suit: Suit| match self.foundations.get(&suit).unwrap().last() { Some(e) => e.to_string(), None => String::from("---") };
+
+        let mut lines = vec![
+            format!(
+                "+ TOP: ---{}---{}---{}---{}--- +",
+                top_str(Suit::Spade), top_str(Suit::Club), top_str(Suit::Diamond), top_str(Suit::Heart)
+                )
+            ];
+        for i in 0..max_column
+        {
+            let strings: Vec<String> = self.columns.iter().map(|c| c.get(i)).map(|c| {
+                match c {
+                    Some(m) => format!("{}", m),
+                    None => "   ".to_string()
+                }
+            }).collect();
+            lines.push(strings.join(" | "));
+        };
+
+        lines.push("+ ------------------- +".to_string());
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for BakersDozen
+{
+    fn win(&self) -> bool
+    {
+        self.foundations.values().all(|f| f.len() == FOUNDATION_SIZE)
+    }
+
+    fn handle_input(&mut self)
+    {
+        let commands = ["Display", "Move"];
+        println!("Select a command: ");
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&commands)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) =>
+            {
+                println!("+ -------------------- + ");
+                match commands[index] {
+                    "Display" => println!("{}", self),
+                    "Move" => self.handle_move().unwrap_or_else(|err| println!("{}", err)),
+                    _ => {
+                        println!("Invalid selection");
+                        self.handle_input();
+                    }
+                }
+            }
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    fn card(suit: Suit, rank: u8) -> Card
+    {
+        Card { suit, rank, visible: true }
+    }
+
+    #[test]
+    fn kings_start_at_the_bottom()
+    {
+        let game = BakersDozen::new();
+
+        for column in &game.columns
+        {
+            let kings = column.iter().filter(|c| c.rank == 13).count();
+            assert!(column[..kings].iter().all(|c| c.rank == 13));
+        }
+    }
+
+    #[test]
+    fn builds_down_regardless_of_suit()
+    {
+        let mut game = BakersDozen::new();
+        game.columns[0] = vec![card(Suit::Heart, 9)];
+        game.columns[1] = vec![card(Suit::Club, 8)];
+
+        let from = MoveCommand { location: Position::Columns, i: 1 };
+        let to = MoveCommand { location: Position::Columns, i: 0 };
+        assert!(game.make_move(from, to).is_ok());
+
+        // Spaces stay empty
+        assert!(!game.is_move_valid(to, from));
+    }
+
+    #[test]
+    fn foundations_build_up_from_ace()
+    {
+        let mut game = BakersDozen::new();
+        game.columns[0] = vec![card(Suit::Diamond, 2), card(Suit::Diamond, 14)];
+
+        let from = MoveCommand { location: Position::Columns, i: 0 };
+        let to = MoveCommand { location: Position::Foundation, i: 0 };
+        assert!(game.make_move(from, to).is_ok());
+        assert!(game.make_move(from, to).is_ok());
+        assert_eq!(game.foundations.get(&Suit::Diamond).unwrap().len(), 2);
+    }
+}
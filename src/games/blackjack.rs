@@ -0,0 +1,684 @@
+use crate::deck::Card;
+use crate::shoe::Shoe;
+use crate::blackjack_trainer::{basic_strategy, CountDrill};
+use crate::game::Game;
+use text_io::read;
+use std::fmt;
+use dialoguer::{
+    Select,
+    MultiSelect,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const STARTING_BANKROLL: f64 = 1000.0;
+const PENETRATION: f64 = 0.75;
+// A hand may be split until the player is holding this many
+const MAX_HANDS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Payout
+{
+    ThreeToTwo,
+    SixToFive
+}
+
+impl Payout
+{
+    fn multiplier(&self) -> f64
+    {
+        match self
+        {
+            Payout::ThreeToTwo => 1.5,
+            Payout::SixToFive => 1.2
+        }
+    }
+}
+
+impl fmt::Display for Payout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self
+        {
+            Payout::ThreeToTwo => write!(f, "3:2"),
+            Payout::SixToFive => write!(f, "6:5")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlackjackRules
+{
+    pub decks: usize,
+    pub dealer_hits_soft_17: bool,
+    pub double_after_split: bool,
+    pub resplit_aces: bool,
+    pub surrender: bool,
+    pub blackjack_payout: Payout,
+}
+
+impl Default for BlackjackRules
+{
+    fn default() -> BlackjackRules
+    {
+        BlackjackRules
+        {
+            decks: 6,
+            dealer_hits_soft_17: false,
+            double_after_split: true,
+            resplit_aces: false,
+            surrender: false,
+            blackjack_payout: Payout::ThreeToTwo
+        }
+    }
+}
+
+impl fmt::Display for BlackjackRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rules = vec![
+            format!("{} decks", self.decks),
+            String::from(if self.dealer_hits_soft_17 { "H17" } else { "S17" }),
+            format!("Blackjack pays {}", self.blackjack_payout)
+        ];
+        if self.double_after_split { rules.push(String::from("DAS")) }
+        if self.resplit_aces { rules.push(String::from("RSA")) }
+        if self.surrender { rules.push(String::from("Surrender")) }
+        write!(f, "{}", rules.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action
+{
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action_str = match self
+        {
+            Action::Hit => "Hit",
+            Action::Stand => "Stand",
+            Action::Double => "Double",
+            Action::Split => "Split",
+            Action::Surrender => "Surrender"
+        };
+        write!(f, "{}", action_str)
+    }
+}
+
+pub fn card_value(card: &Card) -> u32
+{
+    match card.rank
+    {
+        14 => 11,
+        11..=13 => 10,
+        rank => rank as u32
+    }
+}
+
+// The best total for a hand, and whether it is soft (an ace is still counting as eleven)
+pub fn hand_value(cards: &[Card]) -> (u32, bool)
+{
+    let mut total: u32 = cards.iter().map(card_value).sum();
+    let mut aces = cards.iter().filter(|c| c.is_ace()).count();
+
+    while total > 21 && aces > 0
+    {
+        total -= 10;
+        aces -= 1;
+    }
+
+    (total, aces > 0)
+}
+
+pub fn is_blackjack(cards: &[Card]) -> bool
+{
+    cards.len() == 2 && hand_value(cards).0 == 21
+}
+
+pub fn dealer_should_hit(cards: &[Card], rules: &BlackjackRules) -> bool
+{
+    let (total, soft) = hand_value(cards);
+
+    total < 17 || (total == 17 && soft && rules.dealer_hits_soft_17)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerHand
+{
+    pub cards: Vec<Card>,
+    pub bet: f64,
+    // Split hands can't be a blackjack, and may not be doubled without DAS
+    pub split: bool,
+    pub split_aces: bool,
+    pub surrendered: bool,
+    pub done: bool,
+}
+
+impl PlayerHand
+{
+    fn new(cards: Vec<Card>, bet: f64) -> PlayerHand
+    {
+        PlayerHand { cards, bet, split: false, split_aces: false, surrendered: false, done: false }
+    }
+
+    fn is_bust(&self) -> bool
+    {
+        hand_value(&self.cards).0 > 21
+    }
+
+    // How much comes back to the player, stake included, against the dealer's final hand
+    fn returns(&self, dealer: &[Card], rules: &BlackjackRules) -> f64
+    {
+        let blackjack = !self.split && is_blackjack(&self.cards);
+
+        if self.surrendered { return self.bet / 2.0 }
+        if self.is_bust() { return 0.0 }
+        if is_blackjack(dealer) { return if blackjack { self.bet } else { 0.0 } }
+        if blackjack { return self.bet + self.bet * rules.blackjack_payout.multiplier() }
+
+        let player_total = hand_value(&self.cards).0;
+        let dealer_total = hand_value(dealer).0;
+        if dealer_total > 21 || player_total > dealer_total { self.bet * 2.0 }
+        else if player_total == dealer_total { self.bet }
+        else { 0.0 }
+    }
+}
+
+pub struct Blackjack
+{
+    pub rules: BlackjackRules,
+    pub shoe: Shoe,
+    pub bankroll: f64,
+    pub dealer: Vec<Card>,
+    pub hands: Vec<PlayerHand>,
+    leave: bool,
+    // In training mode every decision is checked against basic strategy
+    trainer: bool,
+    decisions: usize,
+    deviations: usize,
+}
+
+impl Blackjack
+{
+    pub fn new(rules: BlackjackRules) -> Result<Blackjack, String>
+    {
+        let shoe = Shoe::new(rules.decks, PENETRATION)?;
+
+        Ok(Blackjack
+        {
+            rules,
+            shoe,
+            bankroll: STARTING_BANKROLL,
+            dealer: vec![],
+            hands: vec![],
+            leave: false,
+            trainer: false,
+            decisions: 0,
+            deviations: 0
+        })
+    }
+
+    pub fn play()
+    {
+        let modes = ["Play", "Basic strategy trainer", "Hi-Lo count drill"];
+        let mode = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select a mode")
+            .items(&modes)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        if mode == Some(2)
+        {
+            return CountDrill::play()
+        }
+
+        let mut game = match Blackjack::new(Blackjack::configure_rules()) {
+            Ok(game) => game,
+            Err(err) => return println!("{}", err)
+        };
+        game.trainer = mode == Some(1);
+        println!("Playing {}", game.rules);
+
+        while !game.leave && game.bankroll >= 1.0
+        {
+            game.handle_input();
+        }
+
+        println!("You leave the table with {:.2}", game.bankroll);
+        if game.trainer
+        {
+            println!("You followed basic strategy on {} of {} decisions", game.decisions - game.deviations, game.decisions);
+        }
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    pub fn configure_rules() -> BlackjackRules
+    {
+        let mut rules = BlackjackRules::default();
+
+        println!("How many decks in the shoe? (1-8)");
+        rules.decks = read!("{}\n");
+
+        let options = [
+            ("Dealer hits soft 17", rules.dealer_hits_soft_17),
+            ("Double after split", rules.double_after_split),
+            ("Re-split aces", rules.resplit_aces),
+            ("Late surrender", rules.surrender)
+        ];
+        let chosen = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("House rules (space to toggle)")
+            .items_checked(&options)
+            .interact_on(&Term::stderr()).unwrap();
+        rules.dealer_hits_soft_17 = chosen.contains(&0);
+        rules.double_after_split = chosen.contains(&1);
+        rules.resplit_aces = chosen.contains(&2);
+        rules.surrender = chosen.contains(&3);
+
+        let payouts = [Payout::ThreeToTwo, Payout::SixToFive];
+        let payout = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Blackjack pays")
+            .items(&payouts)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+        rules.blackjack_payout = payouts[payout.unwrap_or(0)];
+
+        rules
+    }
+
+    pub fn deal(&mut self, bet: f64) -> Result<(), String>
+    {
+        if bet < 1.0 || bet > self.bankroll
+        {
+            return Err(format!("Bets must be between 1 and {:.2}", self.bankroll))
+        }
+
+        self.bankroll -= bet;
+        let cards = vec![self.shoe.draw(), self.shoe.draw()];
+        self.hands = vec![PlayerHand::new(cards, bet)];
+        self.dealer = vec![self.shoe.draw(), self.shoe.draw().hide()];
+
+        Ok(())
+    }
+
+    // The dealer checks under an ace or a ten for blackjack before anyone acts
+    pub fn dealer_peek(&self) -> bool
+    {
+        card_value(&self.dealer[0]) >= 10 && is_blackjack(&self.dealer)
+    }
+
+    pub fn legal_actions(&self, hand_i: usize) -> Vec<Action>
+    {
+        let hand = match self.hands.get(hand_i) { Some(h) if !h.done => h, _ => return vec![] };
+        let first_action = hand.cards.len() == 2;
+        let can_afford = self.bankroll >= hand.bet;
+        let pair = first_action && card_value(&hand.cards[0]) == card_value(&hand.cards[1]);
+
+        let mut actions = vec![];
+        if !hand.split_aces { actions.push(Action::Hit) }
+        actions.push(Action::Stand);
+        if first_action && can_afford && !hand.split_aces && (!hand.split || self.rules.double_after_split)
+        {
+            actions.push(Action::Double)
+        }
+        if pair && can_afford && self.hands.len() < MAX_HANDS && (!hand.split_aces || self.rules.resplit_aces)
+        {
+            actions.push(Action::Split)
+        }
+        if first_action && self.rules.surrender && self.hands.len() == 1
+        {
+            actions.push(Action::Surrender)
+        }
+
+        actions
+    }
+
+    pub fn apply(&mut self, hand_i: usize, action: Action) -> Result<(), String>
+    {
+        if !self.legal_actions(hand_i).contains(&action)
+        {
+            return Err(format!("You cannot {} this hand", action.to_string().to_lowercase()))
+        }
+
+        match action
+        {
+            Action::Hit => {
+                let card = self.shoe.draw();
+                let hand = &mut self.hands[hand_i];
+                hand.cards.push(card);
+                hand.done = hand_value(&hand.cards).0 >= 21;
+            },
+            Action::Stand => self.hands[hand_i].done = true,
+            Action::Double => {
+                let card = self.shoe.draw();
+                let hand = &mut self.hands[hand_i];
+                self.bankroll -= hand.bet;
+                hand.bet *= 2.0;
+                hand.cards.push(card);
+                hand.done = true;
+            },
+            Action::Split => {
+                let bet = self.hands[hand_i].bet;
+                let aces = self.hands[hand_i].cards[0].is_ace();
+                let second = self.hands[hand_i].cards.pop().unwrap();
+                self.bankroll -= bet;
+
+                let mut new_hand = PlayerHand::new(vec![second], bet);
+                for hand in [&mut self.hands[hand_i], &mut new_hand]
+                {
+                    hand.cards.push(self.shoe.draw());
+                    hand.split = true;
+                    hand.split_aces = aces;
+                    // Split aces only get the one card, unless it's another ace to re-split
+                    hand.done = aces && !(self.rules.resplit_aces && hand.cards[1].is_ace());
+                }
+                self.hands.insert(hand_i + 1, new_hand);
+            },
+            Action::Surrender => {
+                let hand = &mut self.hands[hand_i];
+                hand.surrendered = true;
+                hand.done = true;
+            }
+        };
+
+        Ok(())
+    }
+
+    fn coach(&mut self, hand_i: usize, action: Action)
+    {
+        let best = basic_strategy(&self.hands[hand_i].cards, &self.dealer[0], &self.rules, &self.legal_actions(hand_i));
+
+        self.decisions += 1;
+        if action != best
+        {
+            self.deviations += 1;
+            println!("Basic strategy says {} here", best.to_string().to_lowercase());
+        }
+    }
+
+    pub fn reveal(&mut self)
+    {
+        for card in self.dealer.iter_mut() { card.set_visible(true) }
+    }
+
+    pub fn dealer_play(&mut self)
+    {
+        self.reveal();
+        // No need to draw when every hand is already settled
+        let live = self.hands.iter().any(|h| !h.surrendered && !h.is_bust() && (h.split || !is_blackjack(&h.cards)));
+
+        while live && dealer_should_hit(&self.dealer, &self.rules)
+        {
+            self.dealer.push(self.shoe.draw());
+        }
+    }
+
+    // Pays out every hand and returns the player's winnings for the round
+    pub fn settle(&mut self) -> f64
+    {
+        let staked: f64 = self.hands.iter().map(|h| h.bet).sum();
+        let returned: f64 = self.hands.iter().map(|h| h.returns(&self.dealer, &self.rules)).sum();
+        self.bankroll += returned;
+
+        returned - staked
+    }
+
+    fn get_bet(&self) -> f64
+    {
+        println!("Place a bet between 1 and {:.0}", self.bankroll.floor());
+        let bet: u32 = read!("{}\n");
+
+        bet as f64
+    }
+
+    fn get_action(&self, hand_i: usize) -> Action
+    {
+        let actions = self.legal_actions(hand_i);
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Hand {}", hand_i + 1))
+            .items(&actions)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) => actions[index],
+            None => Action::Stand
+        }
+    }
+
+    pub fn play_round(&mut self)
+    {
+        if self.shoe.cut_card_reached()
+        {
+            println!("The cut card is out, reshuffling the shoe");
+            self.shoe.reshuffle();
+        }
+
+        let bet = self.get_bet();
+        if let Err(err) = self.deal(bet)
+        {
+            return println!("{}", err)
+        }
+        println!("{}", self);
+
+        if !self.dealer_peek() && !is_blackjack(&self.hands[0].cards)
+        {
+            let mut hand_i = 0;
+            while hand_i < self.hands.len()
+            {
+                while !self.hands[hand_i].done
+                {
+                    let action = self.get_action(hand_i);
+                    if self.trainer { self.coach(hand_i, action) }
+                    self.apply(hand_i, action).unwrap_or_else(|err| println!("{}", err));
+                    println!("{}", self);
+                }
+                hand_i += 1;
+            }
+        }
+
+        self.dealer_play();
+        println!("{}", self);
+
+        let winnings = self.settle();
+        if winnings > 0.0 { println!("You won {:.2}", winnings) }
+        else if winnings < 0.0 { println!("You lost {:.2}", -winnings) }
+        else { println!("Push") }
+    }
+}
+
+impl fmt::Display for Blackjack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards_str = |cards: &[Card]| cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ");
+        let dealer_value = if self.dealer.iter().all(|c| c.visible) { format!("({})", hand_value(&self.dealer).0) } else { String::new() };
+
+        let mut lines = vec![
+            format!("🃏 : {} Cards remaining in the shoe", self.shoe.remaining()),
+            format!("+ --- Bankroll: {:.2} --- +", self.bankroll),
+            format!("Dealer: {} {}", cards_str(&self.dealer), dealer_value)
+            ];
+        for (i, hand) in self.hands.iter().enumerate()
+        {
+            let (total, soft) = hand_value(&hand.cards);
+            let status = if hand.surrendered { " surrendered" } else if hand.is_bust() { " bust" } else { "" };
+            lines.push(format!(
+                "Hand {}: {} ({}{}) bet {:.2}{}",
+                i + 1, cards_str(&hand.cards), if soft { "soft " } else { "" }, total, hand.bet, status
+                ));
+        }
+
+        lines.push("+ ------------------- +".to_string());
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Blackjack
+{
+    fn win(&self) -> bool
+    {
+        self.bankroll > STARTING_BANKROLL
+    }
+
+    fn handle_input(&mut self)
+    {
+        let commands = ["Deal", "Display", "Leave"];
+        println!("Select a command: ");
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&commands)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) =>
+            {
+                println!("+ -------------------- + ");
+                match commands[index] {
+                    "Deal" => self.play_round(),
+                    "Display" => println!("{}", self),
+                    "Leave" => self.leave = true,
+                    _ => {
+                        println!("Invalid selection");
+                        self.handle_input();
+                    }
+                }
+            }
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::Suit;
+
+    fn card(rank: u8) -> Card
+    {
+        Card { suit: Suit::Spade, rank, visible: true }
+    }
+
+    fn table(rules: BlackjackRules, player: &[u8], dealer: &[u8]) -> Blackjack
+    {
+        let mut game = Blackjack::new(rules).unwrap();
+        game.bankroll -= 10.0;
+        game.hands = vec![PlayerHand::new(player.iter().map(|r| card(*r)).collect(), 10.0)];
+        game.dealer = dealer.iter().map(|r| card(*r)).collect();
+        game
+    }
+
+    #[test]
+    fn hand_values()
+    {
+        assert_eq!(hand_value(&[card(14), card(6)]), (17, true));
+        assert_eq!(hand_value(&[card(14), card(6), card(10)]), (17, false));
+        assert_eq!(hand_value(&[card(14), card(14), card(14)]), (13, true));
+        assert!(is_blackjack(&[card(13), card(14)]));
+        assert!(!is_blackjack(&[card(7), card(4), card(14)]));
+    }
+
+    #[test]
+    fn soft_17()
+    {
+        let soft_17 = [card(14), card(6)];
+        let h17 = BlackjackRules { dealer_hits_soft_17: true, ..BlackjackRules::default() };
+
+        assert!(!dealer_should_hit(&soft_17, &BlackjackRules::default()));
+        assert!(dealer_should_hit(&soft_17, &h17));
+        assert!(!dealer_should_hit(&[card(10), card(7)], &h17));
+    }
+
+    #[test]
+    fn blackjack_payouts()
+    {
+        let six_to_five = BlackjackRules { blackjack_payout: Payout::SixToFive, ..BlackjackRules::default() };
+
+        assert_eq!(table(BlackjackRules::default(), &[14, 13], &[10, 9]).settle(), 15.0);
+        assert_eq!(table(six_to_five, &[14, 13], &[10, 9]).settle(), 12.0);
+        assert_eq!(table(BlackjackRules::default(), &[14, 13], &[14, 12]).settle(), 0.0);
+        assert_eq!(table(BlackjackRules::default(), &[10, 9], &[14, 12]).settle(), -10.0);
+    }
+
+    #[test]
+    fn settle_hands()
+    {
+        assert_eq!(table(BlackjackRules::default(), &[10, 9], &[10, 8]).settle(), 10.0);
+        assert_eq!(table(BlackjackRules::default(), &[10, 8], &[10, 8]).settle(), 0.0);
+        assert_eq!(table(BlackjackRules::default(), &[10, 8, 5], &[10, 6, 10]).settle(), -10.0);
+        assert_eq!(table(BlackjackRules::default(), &[10, 2], &[10, 6, 10]).settle(), 10.0);
+    }
+
+    #[test]
+    fn bet_limits()
+    {
+        let mut game = Blackjack::new(BlackjackRules::default()).unwrap();
+
+        assert!(game.deal(0.5).is_err());
+        assert!(game.deal(STARTING_BANKROLL + 1.0).is_err());
+    }
+
+    #[test]
+    fn surrender()
+    {
+        let rules = BlackjackRules { surrender: true, ..BlackjackRules::default() };
+
+        assert!(!table(BlackjackRules::default(), &[10, 6], &[10, 9]).legal_actions(0).contains(&Action::Surrender));
+
+        let mut game = table(rules, &[10, 6], &[10, 9]);
+        assert!(game.apply(0, Action::Surrender).is_ok());
+        assert_eq!(game.settle(), -5.0);
+    }
+
+    #[test]
+    fn split_and_double_after_split()
+    {
+        let no_das = BlackjackRules { double_after_split: false, ..BlackjackRules::default() };
+
+        let mut game = table(BlackjackRules::default(), &[8, 8], &[10, 9]);
+        assert!(game.apply(0, Action::Split).is_ok());
+        assert_eq!(game.hands.len(), 2);
+        assert_eq!(game.bankroll, STARTING_BANKROLL - 20.0);
+        assert!(game.legal_actions(0).contains(&Action::Double));
+
+        let mut game = table(no_das, &[8, 8], &[10, 9]);
+        game.apply(0, Action::Split).unwrap();
+        assert!(!game.legal_actions(0).contains(&Action::Double));
+        assert!(!game.legal_actions(1).contains(&Action::Double));
+    }
+
+    #[test]
+    fn split_aces()
+    {
+        let rsa = BlackjackRules { resplit_aces: true, ..BlackjackRules::default() };
+
+        let mut game = table(BlackjackRules::default(), &[14, 14], &[10, 9]);
+        game.apply(0, Action::Split).unwrap();
+        game.hands[0].cards = vec![card(14), card(14)];
+        game.hands[0].done = false;
+        assert_eq!(game.legal_actions(0), vec![Action::Stand]);
+
+        let mut game = table(rsa, &[14, 14], &[10, 9]);
+        game.apply(0, Action::Split).unwrap();
+        game.hands[0].cards = vec![card(14), card(14)];
+        game.hands[0].done = false;
+        assert_eq!(game.legal_actions(0), vec![Action::Stand, Action::Split]);
+
+        // Split aces making 21 don't count as a blackjack
+        game.hands[1].cards = vec![card(14), card(13)];
+        game.hands[0].done = true;
+        assert_eq!(game.hands[1].returns(&[card(10), card(9)], &game.rules), 20.0);
+    }
+}
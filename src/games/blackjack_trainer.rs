@@ -0,0 +1,348 @@
+use crate::deck::{Stack, Card};
+use crate::blackjack::{Action, BlackjackRules, card_value, hand_value};
+use crate::game::Game;
+use text_io::read;
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+// How many cards the count drill turns over at a time
+const DRILL_BATCH: usize = 4;
+// Every so many batches the player is asked for the running count, and every
+// other time the true count as well
+const DRILL_QUIZ_EVERY: usize = 3;
+// The drill stops once only this share of the shoe is left, like a cut card
+const DRILL_CUT: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Chart
+{
+    Hit,
+    Stand,
+    DoubleOrHit,
+    DoubleOrStand
+}
+
+// Multi-deck basic strategy, adjusted for the dealer hitting soft 17, double after
+// split and late surrender. `legal` is what the player is allowed to do with the hand,
+// and the returned action is always one of them.
+pub fn basic_strategy(cards: &[Card], dealer_up: &Card, rules: &BlackjackRules, legal: &[Action]) -> Action
+{
+    let up = card_value(dealer_up);
+    let (total, soft) = hand_value(cards);
+    let can = |action: Action| legal.contains(&action);
+    let pair = cards.len() == 2 && card_value(&cards[0]) == card_value(&cards[1]);
+
+    if can(Action::Surrender) && should_surrender(cards, total, soft, up, rules)
+    {
+        return Action::Surrender
+    }
+    if pair && can(Action::Split) && should_split(card_value(&cards[0]), up, rules)
+    {
+        return Action::Split
+    }
+
+    let chart = if soft { soft_total(total, up, rules) } else { hard_total(total, up, rules) };
+    let action = match chart
+    {
+        Chart::Hit => Action::Hit,
+        Chart::Stand => Action::Stand,
+        Chart::DoubleOrHit => if can(Action::Double) { Action::Double } else { Action::Hit },
+        Chart::DoubleOrStand => if can(Action::Double) { Action::Double } else { Action::Stand }
+    };
+
+    // Split aces can't take another card
+    if can(action) { action } else { Action::Stand }
+}
+
+fn should_surrender(cards: &[Card], total: u32, soft: bool, up: u32, rules: &BlackjackRules) -> bool
+{
+    let h17 = rules.dealer_hits_soft_17;
+    let eights = cards.len() == 2 && cards.iter().all(|c| card_value(c) == 8);
+
+    if soft { return false }
+    if eights { return h17 && up == 11 }
+
+    match total
+    {
+        15 => up == 10 || (h17 && up == 11),
+        16 => up >= 9,
+        17 => h17 && up == 11,
+        _ => false
+    }
+}
+
+fn should_split(value: u32, up: u32, rules: &BlackjackRules) -> bool
+{
+    let das = rules.double_after_split;
+
+    match value
+    {
+        11 | 8 => true,
+        9 => matches!(up, 2..=6 | 8 | 9),
+        7 => up <= 7,
+        6 => if das { up <= 6 } else { (3..=6).contains(&up) },
+        4 => das && (5..=6).contains(&up),
+        2 | 3 => if das { up <= 7 } else { (4..=7).contains(&up) },
+        _ => false
+    }
+}
+
+fn soft_total(total: u32, up: u32, rules: &BlackjackRules) -> Chart
+{
+    let h17 = rules.dealer_hits_soft_17;
+
+    match total
+    {
+        20..=21 => Chart::Stand,
+        19 => if h17 && up == 6 { Chart::DoubleOrStand } else { Chart::Stand },
+        18 => match up {
+            2 => if h17 { Chart::DoubleOrStand } else { Chart::Stand },
+            3..=6 => Chart::DoubleOrStand,
+            7 | 8 => Chart::Stand,
+            _ => Chart::Hit
+        },
+        17 => if (3..=6).contains(&up) { Chart::DoubleOrHit } else { Chart::Hit },
+        15 | 16 => if (4..=6).contains(&up) { Chart::DoubleOrHit } else { Chart::Hit },
+        13 | 14 => if (5..=6).contains(&up) { Chart::DoubleOrHit } else { Chart::Hit },
+        _ => Chart::Hit
+    }
+}
+
+fn hard_total(total: u32, up: u32, rules: &BlackjackRules) -> Chart
+{
+    match total
+    {
+        17..=u32::MAX => Chart::Stand,
+        13..=16 => if up <= 6 { Chart::Stand } else { Chart::Hit },
+        12 => if (4..=6).contains(&up) { Chart::Stand } else { Chart::Hit },
+        11 => if up == 11 && !rules.dealer_hits_soft_17 { Chart::Hit } else { Chart::DoubleOrHit },
+        10 => if up <= 9 { Chart::DoubleOrHit } else { Chart::Hit },
+        9 => if (3..=6).contains(&up) { Chart::DoubleOrHit } else { Chart::Hit },
+        _ => Chart::Hit
+    }
+}
+
+pub fn hi_lo_value(card: &Card) -> i32
+{
+    match card_value(card)
+    {
+        2..=6 => 1,
+        7..=9 => 0,
+        _ => -1
+    }
+}
+
+// The running count spread over the decks left to be dealt, rounded toward zero
+pub fn true_count(running: i32, cards_remaining: usize) -> i32
+{
+    let decks_remaining = (cards_remaining as f64 / 52.0).max(0.5);
+
+    (running as f64 / decks_remaining).trunc() as i32
+}
+
+pub struct CountDrill
+{
+    deck: Stack,
+    // Cards left in the shoe when the drill stops
+    cut: usize,
+    running: i32,
+    batches: usize,
+    asked: usize,
+    correct: usize,
+    stop: bool,
+}
+
+impl CountDrill
+{
+    fn new(decks: usize) -> CountDrill
+    {
+        let mut deck = Stack::new_decks(decks, false);
+
+        deck.shuffle();
+
+        let cut = deck.size() / DRILL_CUT;
+
+        CountDrill { deck, cut, running: 0, batches: 0, asked: 0, correct: 0, stop: false }
+    }
+
+    pub fn play()
+    {
+        println!("How many decks to count down? (1-8)");
+        let decks: usize = read!("{}\n");
+        let mut drill = CountDrill::new(decks.clamp(1, 8));
+
+        while !drill.stop && !drill.cut_reached()
+        {
+            drill.handle_input();
+        }
+
+        println!("You answered {} of {} correctly, the running count was {}", drill.correct, drill.asked, drill.running);
+        if drill.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    fn cut_reached(&self) -> bool
+    {
+        self.deck.size() <= self.cut
+    }
+
+    fn deal_batch(&mut self) -> Vec<Card>
+    {
+        let cards = self.deck.deal(DRILL_BATCH);
+        self.running += cards.iter().map(hi_lo_value).sum::<i32>();
+        self.batches += 1;
+
+        cards
+    }
+
+    fn quiz(&mut self, question: &str, answer: i32)
+    {
+        println!("{}", question);
+        let guess: i32 = read!("{}\n");
+
+        self.asked += 1;
+        if guess == answer
+        {
+            self.correct += 1;
+            println!("Correct");
+        }
+        else
+        {
+            println!("Not quite, it was {}", answer);
+        }
+    }
+}
+
+impl Game for CountDrill
+{
+    // A passing drill gets at least nine in ten counts right
+    fn win(&self) -> bool
+    {
+        self.asked > 0 && self.correct * 10 >= self.asked * 9
+    }
+
+    fn handle_input(&mut self)
+    {
+        let cards = self.deal_batch();
+        println!("{}", cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" "));
+
+        if !self.batches.is_multiple_of(DRILL_QUIZ_EVERY) { return }
+
+        self.quiz("What is the running count?", self.running);
+        if self.batches.is_multiple_of(DRILL_QUIZ_EVERY * 2)
+        {
+            let answer = true_count(self.running, self.deck.size());
+            self.quiz(&format!("{} cards remain, what is the true count?", self.deck.size()), answer);
+        }
+
+        let options = ["Keep counting", "Stop"];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+        self.stop = selection == Some(1);
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::Suit;
+
+    fn card(rank: u8) -> Card
+    {
+        Card { suit: Suit::Heart, rank, visible: true }
+    }
+
+    const ALL: [Action; 5] = [Action::Hit, Action::Stand, Action::Double, Action::Split, Action::Surrender];
+    const NO_SURRENDER: [Action; 4] = [Action::Hit, Action::Stand, Action::Double, Action::Split];
+
+    #[test]
+    fn hard_totals()
+    {
+        let s17 = BlackjackRules::default();
+        let h17 = BlackjackRules { dealer_hits_soft_17: true, ..s17 };
+
+        assert_eq!(basic_strategy(&[card(10), card(6)], &card(10), &s17, &NO_SURRENDER), Action::Hit);
+        assert_eq!(basic_strategy(&[card(10), card(6)], &card(10), &s17, &ALL), Action::Surrender);
+        assert_eq!(basic_strategy(&[card(10), card(2)], &card(3), &s17, &ALL), Action::Hit);
+        assert_eq!(basic_strategy(&[card(7), card(4)], &card(14), &s17, &ALL), Action::Hit);
+        assert_eq!(basic_strategy(&[card(7), card(4)], &card(14), &h17, &ALL), Action::Double);
+        assert_eq!(basic_strategy(&[card(5), card(4), card(2)], &card(6), &s17, &[Action::Hit, Action::Stand]), Action::Hit);
+    }
+
+    #[test]
+    fn soft_totals()
+    {
+        let s17 = BlackjackRules::default();
+        let h17 = BlackjackRules { dealer_hits_soft_17: true, ..s17 };
+
+        assert_eq!(basic_strategy(&[card(14), card(7)], &card(2), &s17, &ALL), Action::Stand);
+        assert_eq!(basic_strategy(&[card(14), card(7)], &card(2), &h17, &ALL), Action::Double);
+        assert_eq!(basic_strategy(&[card(14), card(4), card(3)], &card(4), &s17, &[Action::Hit, Action::Stand]), Action::Stand);
+        assert_eq!(basic_strategy(&[card(14), card(7)], &card(10), &s17, &ALL), Action::Hit);
+    }
+
+    #[test]
+    fn pairs()
+    {
+        let das = BlackjackRules::default();
+        let no_das = BlackjackRules { double_after_split: false, ..das };
+
+        assert_eq!(basic_strategy(&[card(6), card(6)], &card(2), &das, &ALL), Action::Split);
+        assert_eq!(basic_strategy(&[card(6), card(6)], &card(2), &no_das, &ALL), Action::Hit);
+        assert_eq!(basic_strategy(&[card(13), card(12)], &card(6), &das, &ALL), Action::Stand);
+        assert_eq!(basic_strategy(&[card(5), card(5)], &card(6), &das, &ALL), Action::Double);
+        assert_eq!(basic_strategy(&[card(14), card(14)], &card(14), &das, &[Action::Stand]), Action::Stand);
+    }
+
+    #[test]
+    fn counting()
+    {
+        let cards = [card(2), card(6), card(7), card(9), card(10), card(13), card(14), card(5)];
+
+        assert_eq!(cards.iter().map(hi_lo_value).sum::<i32>(), 0);
+        assert_eq!(true_count(6, 156), 2);
+        assert_eq!(true_count(-5, 104), -2);
+        assert_eq!(true_count(3, 10), 6);
+    }
+
+    #[test]
+    fn drill_keeps_running_count()
+    {
+        let mut drill = CountDrill::new(1);
+
+        let dealt: Vec<Card> = (0..13).flat_map(|_| drill.deal_batch()).collect();
+
+        assert_eq!(drill.running, dealt.iter().map(hi_lo_value).sum::<i32>());
+        assert_eq!(drill.running, 0);
+    }
+
+    #[test]
+    fn one_deck_drill_deals()
+    {
+        let mut drill = CountDrill::new(1);
+        assert!(!drill.cut_reached());
+
+        drill.deck = Stack::from_cards((2..=10).map(card).collect());
+        drill.deal_batch();
+        assert_eq!(drill.running, 4);
+
+        let mut drill = CountDrill::new(1);
+        while !drill.cut_reached()
+        {
+            drill.deal_batch();
+        }
+        assert_eq!((drill.batches, drill.deck.size()), (10, 12));
+    }
+}
@@ -0,0 +1,831 @@
+use std::fmt;
+use crate::deck::{Stack, Card, Suit};
+use crate::trick::{StandardRules, Trick, TrickRound, sort_hand};
+use crate::game::{Game, HUMAN};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const PLAYERS: usize = 4;
+// The human sits South, partnered with North against East and West
+const NAMES: [&str; PLAYERS] = ["South", "West", "North", "East"];
+const TRICKS: usize = 13;
+// Four boards cover every combination of vulnerability
+const BOARDS: usize = 4;
+const NO_TRUMP: StandardRules = StandardRules { trump: None };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Strain
+{
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+    NoTrump
+}
+
+impl Strain
+{
+    fn trump(&self) -> Option<Suit>
+    {
+        match self {
+            Strain::Clubs => Some(Suit::Club),
+            Strain::Diamonds => Some(Suit::Diamond),
+            Strain::Hearts => Some(Suit::Heart),
+            Strain::Spades => Some(Suit::Spade),
+            Strain::NoTrump => None
+        }
+    }
+
+    fn is_major(&self) -> bool
+    {
+        matches!(self, Strain::Hearts | Strain::Spades)
+    }
+}
+
+impl fmt::Display for Strain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.trump() {
+            Some(suit) => write!(f, "{}", suit),
+            None => write!(f, "NT")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Call
+{
+    Pass,
+    Double,
+    Redouble,
+    Bid(u8, Strain)
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Call::Pass => write!(f, "Pass"),
+            Call::Double => write!(f, "Double"),
+            Call::Redouble => write!(f, "Redouble"),
+            Call::Bid(level, strain) => write!(f, "{}{}", level, strain)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contract
+{
+    pub level: u8,
+    pub strain: Strain,
+    // 0 undoubled, 1 doubled, 2 redoubled
+    pub doubled: u8,
+    pub declarer: usize,
+}
+
+impl fmt::Display for Contract {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let doubled = ["", "X", "XX"][self.doubled as usize];
+
+        write!(f, "{}{}{} by {}", self.level, self.strain, doubled, NAMES[self.declarer])
+    }
+}
+
+fn side(seat: usize) -> usize
+{
+    seat % 2
+}
+
+// Every call in order, starting with the dealer
+pub struct Auction
+{
+    pub dealer: usize,
+    pub calls: Vec<Call>,
+}
+
+impl Auction
+{
+    pub fn new(dealer: usize) -> Auction
+    {
+        Auction { dealer, calls: vec![] }
+    }
+
+    pub fn to_call(&self) -> usize
+    {
+        (self.dealer + self.calls.len()) % PLAYERS
+    }
+
+    fn seat_of(&self, index: usize) -> usize
+    {
+        (self.dealer + index) % PLAYERS
+    }
+
+    // The last call other than a pass, with the seat that made it
+    fn last_action(&self) -> Option<(usize, Call)>
+    {
+        self.calls.iter().enumerate().rev()
+            .find(|(_, call)| **call != Call::Pass)
+            .map(|(i, call)| (self.seat_of(i), *call))
+    }
+
+    fn last_bid(&self) -> Option<(usize, u8, Strain)>
+    {
+        self.calls.iter().enumerate().rev().find_map(|(i, call)| match call {
+            Call::Bid(level, strain) => Some((self.seat_of(i), *level, *strain)),
+            _ => None
+        })
+    }
+
+    fn bids_by(&self, seat: usize) -> Vec<(u8, Strain)>
+    {
+        self.calls.iter().enumerate()
+            .filter(|(i, _)| self.seat_of(*i) == seat)
+            .filter_map(|(_, call)| match call {
+                Call::Bid(level, strain) => Some((*level, *strain)),
+                _ => None
+            })
+            .collect()
+    }
+
+    // The lowest level `strain` can be bid at now
+    fn cheapest_level(&self, strain: Strain) -> u8
+    {
+        match self.last_bid() {
+            Some((_, level, last)) if strain > last => level,
+            Some((_, level, _)) => level + 1,
+            None => 1
+        }
+    }
+
+    // Bids must be higher than the last one. Only an opponent's bid can be doubled,
+    // and only a double of your own side's bid can be redoubled.
+    pub fn check(&self, call: Call) -> Result<(), String>
+    {
+        if self.finished()
+        {
+            return Err(String::from("The auction is over"))
+        }
+
+        let seat = self.to_call();
+        let last = self.last_action();
+        match call {
+            Call::Pass => Ok(()),
+            Call::Bid(level, strain) if !(1..=7).contains(&level) => Err(format!("Unable to bid {}{}, bids are from 1 to 7", level, strain)),
+            Call::Bid(level, strain) => match self.last_bid() {
+                Some((_, last_level, last_strain)) if (level, strain) <= (last_level, last_strain) => Err(format!("{} is not higher than {}{}", call, last_level, last_strain)),
+                _ => Ok(())
+            },
+            Call::Double => match last {
+                Some((bidder, Call::Bid(_, _))) if side(bidder) != side(seat) => Ok(()),
+                _ => Err(String::from("Only an opponent's bid can be doubled"))
+            },
+            Call::Redouble => match last {
+                Some((doubler, Call::Double)) if side(doubler) != side(seat) => Ok(()),
+                _ => Err(String::from("Only an opponent's double can be redoubled"))
+            }
+        }
+    }
+
+    pub fn call(&mut self, call: Call) -> Result<(), String>
+    {
+        self.check(call)?;
+        self.calls.push(call);
+
+        Ok(())
+    }
+
+    pub fn legal_calls(&self) -> Vec<Call>
+    {
+        let mut calls = vec![Call::Pass, Call::Double, Call::Redouble];
+        for level in 1..=7
+        {
+            for strain in [Strain::Clubs, Strain::Diamonds, Strain::Hearts, Strain::Spades, Strain::NoTrump].iter()
+            {
+                calls.push(Call::Bid(level, *strain));
+            }
+        }
+
+        calls.into_iter().filter(|call| self.check(*call).is_ok()).collect()
+    }
+
+    // Four passes to start, or three after a bid
+    pub fn finished(&self) -> bool
+    {
+        let passes = self.calls.iter().rev().take_while(|call| **call == Call::Pass).count();
+
+        passes == 4 || (passes == 3 && self.last_bid().is_some())
+    }
+
+    // The final bid, played by whoever on that side first named its strain.
+    // Nothing if the board was passed out.
+    pub fn contract(&self) -> Option<Contract>
+    {
+        if !self.finished() { return None }
+
+        let (bidder, level, strain) = self.last_bid()?;
+        let declarer = (0..self.calls.len())
+            .find(|i| side(self.seat_of(*i)) == side(bidder) && matches!(self.calls[*i], Call::Bid(_, s) if s == strain))
+            .map(|i| self.seat_of(i))
+            .unwrap();
+        let doubled = match self.last_action() {
+            Some((_, Call::Double)) => 1,
+            Some((_, Call::Redouble)) => 2,
+            _ => 0
+        };
+
+        Some(Contract { level, strain, doubled, declarer })
+    }
+}
+
+// The dealer for a board, rotating clockwise from North on board one
+pub fn dealer(board: usize) -> usize
+{
+    (2 + board - 1) % PLAYERS
+}
+
+// The standard sixteen board cycle: none, North-South, East-West or both vulnerable
+pub fn vulnerable(board: usize, seat: usize) -> bool
+{
+    const CYCLE: [u8; 16] = [0, 1, 2, 3, 1, 2, 3, 0, 2, 3, 0, 1, 3, 0, 1, 2];
+    let vulnerability = CYCLE[(board - 1) % 16];
+
+    vulnerability == 3 || vulnerability == 1 + side(seat) as u8
+}
+
+// Duplicate score for the declaring side, negative when the contract goes down
+pub fn score(contract: &Contract, tricks: usize, vulnerable: bool) -> i32
+{
+    let needed = contract.level as usize + 6;
+    let multiplier = 1 << contract.doubled;
+
+    if tricks < needed
+    {
+        let under = needed - tricks;
+        if contract.doubled == 0
+        {
+            return -((under * if vulnerable { 100 } else { 50 }) as i32)
+        }
+
+        let doubled: usize = (1..=under).map(|i| match (vulnerable, i) {
+            (true, 1) => 200,
+            (true, _) => 300,
+            (false, 1) => 100,
+            (false, 2) | (false, 3) => 200,
+            _ => 300
+        }).sum();
+        return -((doubled * multiplier / 2) as i32)
+    }
+
+    let per_trick = match contract.strain { Strain::Clubs | Strain::Diamonds => 20, _ => 30 };
+    let first_trick = if contract.strain == Strain::NoTrump { 10 } else { 0 };
+    let trick_points = (per_trick * contract.level as usize + first_trick) * multiplier;
+
+    let game = if trick_points >= 100 { if vulnerable { 500 } else { 300 } } else { 50 };
+    let slam = match contract.level {
+        6 => if vulnerable { 750 } else { 500 },
+        7 => if vulnerable { 1500 } else { 1000 },
+        _ => 0
+    };
+    let insult = 50 * contract.doubled as usize;
+    let overtrick = if contract.doubled == 0 { per_trick } else { (if vulnerable { 200 } else { 100 }) * multiplier / 2 };
+
+    (trick_points + game + slam + insult + (tricks - needed) * overtrick) as i32
+}
+
+// Four for an ace, three for a king, two for a queen and one for a jack
+fn high_card_points(hand: &[Card]) -> u32
+{
+    hand.iter().map(|c| c.rank.saturating_sub(10) as u32).sum()
+}
+
+fn length(hand: &[Card], strain: Strain) -> usize
+{
+    hand.iter().filter(|c| Some(c.suit) == strain.trump()).count()
+}
+
+// No singletons or voids and at most one doubleton
+fn balanced(hand: &[Card]) -> bool
+{
+    let lengths: Vec<usize> = [Strain::Clubs, Strain::Diamonds, Strain::Hearts, Strain::Spades].iter().map(|s| length(hand, *s)).collect();
+
+    lengths.iter().all(|l| *l >= 2) && lengths.iter().filter(|l| **l == 2).count() <= 1
+}
+
+fn longest_suit(hand: &[Card]) -> Strain
+{
+    [Strain::Clubs, Strain::Diamonds, Strain::Hearts, Strain::Spades].iter().copied()
+        .max_by_key(|s| (length(hand, *s), *s))
+        .unwrap()
+}
+
+// Partner's suit with three to support it, then a five card major, then no trump
+fn fit(hand: &[Card], partner: Strain) -> Strain
+{
+    if partner != Strain::NoTrump && length(hand, partner) >= 3 { return partner }
+
+    let major = longest_suit(hand);
+    if major.is_major() && length(hand, major) >= 5 { major } else { Strain::NoTrump }
+}
+
+// How high a partnership holding `points` between them should go in `strain`
+fn target_level(points: u32, strain: Strain) -> u8
+{
+    match points {
+        37..=40 => 7,
+        33..=36 => 6,
+        25..=32 => match strain { Strain::NoTrump => 3, s if s.is_major() => 4, _ => 5 },
+        23..=24 => if strain == Strain::NoTrump { 2 } else { 3 },
+        _ => if strain == Strain::NoTrump { 1 } else { 2 }
+    }
+}
+
+// A simple bidding bot: open on twelve points, with a no trump on a balanced 15 to
+// 17, answer partner's opening by adding up the partnership's points, double a
+// high contract holding a good hand and otherwise pass
+fn bot_call(auction: &Auction, hand: &[Card]) -> Call
+{
+    let seat = auction.to_call();
+    let points = high_card_points(hand);
+
+    if let Some((bidder, level, _)) = auction.last_bid()
+    {
+        if side(bidder) != side(seat) && level >= 4 && points >= 15 && auction.check(Call::Double).is_ok()
+        {
+            return Call::Double
+        }
+    }
+
+    let (strain, target, combined) = match (auction.bids_by(seat).first(), auction.bids_by((seat + 2) % PLAYERS).first()) {
+        (None, None) if points >= 12 => {
+            let strain = if balanced(hand) && (15..=17).contains(&points) { Strain::NoTrump } else { longest_suit(hand) };
+            (strain, 1, points)
+        },
+        (None, Some((_, partner))) if points >= 6 => {
+            let shown = if *partner == Strain::NoTrump { 16 } else { 12 };
+            let strain = fit(hand, *partner);
+            (strain, target_level(points + shown, strain), points + shown)
+        },
+        _ => return Call::Pass
+    };
+
+    let cheapest = auction.cheapest_level(strain);
+    if cheapest > target { return Call::Pass }
+
+    Call::Bid(if combined >= 25 { target } else { cheapest }, strain)
+}
+
+// Leads aces, lets a partner's winning card stand and otherwise takes the trick
+// as cheaply as possible
+fn bot_play(hand: &[Card], legal: &[usize], trick: &Trick, seat: usize, rules: &StandardRules) -> usize
+{
+    let cheapest = |i: &&usize| (Some(hand[**i].suit) == rules.trump, hand[**i].rank);
+    let lowest = *legal.iter().min_by_key(cheapest).unwrap();
+
+    if trick.plays.is_empty()
+    {
+        return legal.iter().find(|i| hand[**i].rank == 14).copied().unwrap_or(lowest)
+    }
+    if trick.winner(rules) == Some((seat + 2) % PLAYERS)
+    {
+        return lowest
+    }
+
+    legal.iter().filter(|i| trick.would_win(seat, hand[**i], rules)).min_by_key(cheapest).copied().unwrap_or(lowest)
+}
+
+pub struct Bridge
+{
+    board: usize,
+    // Duplicate scores by side, North-South first
+    scores: Vec<i32>,
+    auction: Auction,
+    contract: Option<Contract>,
+    round: TrickRound,
+    leave: bool,
+}
+
+impl Bridge
+{
+    pub fn new() -> Bridge
+    {
+        let mut game = Bridge
+        {
+            board: 1,
+            scores: vec![0; 2],
+            auction: Auction::new(dealer(1)),
+            contract: None,
+            round: TrickRound::new(vec![vec![]; PLAYERS], 0),
+            leave: false
+        };
+
+        game.deal();
+        game
+    }
+
+    pub fn play()
+    {
+        let mut game = Bridge::new();
+
+        while !game.leave && game.board <= BOARDS
+        {
+            game.handle_input();
+        }
+
+        println!("North-South {}, East-West {}", game.scores[0], game.scores[1]);
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    pub fn deal(&mut self)
+    {
+        let mut deck = Stack::new_deck(false);
+        deck.shuffle();
+
+        let hands = (0..PLAYERS).map(|_| {
+            let mut hand = deck.deal(TRICKS);
+            sort_hand(&mut hand, &NO_TRUMP);
+            hand
+        }).collect();
+
+        self.round = TrickRound::new(hands, 0);
+        self.auction = Auction::new(dealer(self.board));
+        self.contract = None;
+    }
+
+    fn rules(&self) -> StandardRules
+    {
+        StandardRules { trump: self.contract.and_then(|c| c.strain.trump()) }
+    }
+
+    pub fn make_call(&mut self, call: Call) -> Result<(), String>
+    {
+        let seat = self.auction.to_call();
+        self.auction.call(call)?;
+        println!("{}: {}", NAMES[seat], call);
+
+        if let Some(contract) = self.auction.contract()
+        {
+            self.start_play(contract);
+        }
+
+        Ok(())
+    }
+
+    // Declarer's left hand opponent leads
+    fn start_play(&mut self, contract: Contract)
+    {
+        println!("The contract is {}", contract);
+        self.contract = Some(contract);
+
+        let rules = self.rules();
+        let mut hands = std::mem::take(&mut self.round.hands);
+        for hand in hands.iter_mut()
+        {
+            sort_hand(hand, &rules);
+        }
+        self.round = TrickRound::new(hands, (contract.declarer + 1) % PLAYERS);
+    }
+
+    fn dummy(&self) -> Option<usize>
+    {
+        self.contract.map(|c| (c.declarer + 2) % PLAYERS)
+    }
+
+    // Declarer plays dummy's cards as well as their own
+    fn controller(&self, seat: usize) -> usize
+    {
+        match self.contract {
+            Some(contract) if Some(seat) == self.dummy() => contract.declarer,
+            _ => seat
+        }
+    }
+
+    // Dummy is laid face up once the opening lead is made
+    fn dummy_exposed(&self) -> bool
+    {
+        self.contract.is_some() && (self.round.tricks_played() > 0 || !self.round.trick.plays.is_empty())
+    }
+
+    pub fn play_card(&mut self, index: usize) -> Result<Option<usize>, String>
+    {
+        let seat = self.round.to_play();
+        let card = *self.round.hands[seat].get(index).ok_or_else(|| String::from("Unable to play that card"))?;
+        let winner = self.round.play(index, &self.rules())?;
+
+        println!("{}: {}", NAMES[seat], card);
+        if let Some(winner) = winner
+        {
+            println!("{} took the trick", NAMES[winner]);
+        }
+
+        Ok(winner)
+    }
+
+    fn side_tricks(&self, seat: usize) -> usize
+    {
+        self.round.tricks_won[side(seat)] + self.round.tricks_won[side(seat) + 2]
+    }
+
+    // Scores the board for the declaring side, or the defenders if it went down,
+    // then moves on to the next board. Returns the declaring side's score.
+    pub fn score_board(&mut self) -> i32
+    {
+        let points = match self.contract {
+            Some(contract) => {
+                let points = score(&contract, self.side_tricks(contract.declarer), vulnerable(self.board, contract.declarer));
+                let scorer = if points > 0 { side(contract.declarer) } else { 1 - side(contract.declarer) };
+                self.scores[scorer] += points.abs();
+                points
+            },
+            None => 0
+        };
+
+        self.board += 1;
+        if self.board <= BOARDS
+        {
+            self.deal();
+        }
+
+        points
+    }
+
+    fn get_call(&self) -> Option<Call>
+    {
+        println!("{}", self);
+        let calls = self.auction.legal_calls();
+        let mut options: Vec<String> = calls.iter().map(|c| c.to_string()).collect();
+        options.push("Leave".to_string());
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Your call")
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) if index == calls.len() => None,
+            Some(index) => Some(calls[index]),
+            None => Some(Call::Pass)
+        }
+    }
+
+    fn bidding(&mut self)
+    {
+        while !self.auction.finished()
+        {
+            let seat = self.auction.to_call();
+            let call = if seat == HUMAN { self.get_call() } else { Some(bot_call(&self.auction, &self.round.hands[seat])) };
+            match call {
+                Some(call) => self.make_call(call).unwrap_or_else(|err| println!("{}", err)),
+                None => {
+                    self.leave = true;
+                    return
+                }
+            }
+        }
+    }
+
+    fn play_bots(&mut self)
+    {
+        let rules = self.rules();
+
+        while !self.round.finished() && self.controller(self.round.to_play()) != HUMAN
+        {
+            let seat = self.round.to_play();
+            let index = bot_play(&self.round.hands[seat], &self.round.legal(&rules), &self.round.trick, seat, &rules);
+            self.play_card(index).unwrap();
+        }
+    }
+
+    fn get_play(&mut self)
+    {
+        let seat = self.round.to_play();
+        let legal = self.round.legal(&self.rules());
+        let mut options: Vec<String> = legal.iter().map(|i| self.round.hands[seat][*i].to_string()).collect();
+        options.push("Leave".to_string());
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Play a card from {}", if seat == HUMAN { "your hand" } else { "dummy" }))
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) if index == legal.len() => self.leave = true,
+            Some(index) => { self.play_card(legal[index]).unwrap_or_else(|err| { println!("{}", err); None }); },
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+impl fmt::Display for Bridge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards = |seat: usize| -> String { self.round.hands[seat].iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ") };
+        let vulnerability = match (vulnerable(self.board, 0), vulnerable(self.board, 1)) {
+            (true, true) => "Both",
+            (true, false) => "North-South",
+            (false, true) => "East-West",
+            (false, false) => "None"
+        };
+
+        let mut lines = vec![format!("+ --- Board {} --- +", self.board)];
+        lines.push(format!("North-South {}, East-West {}", self.scores[0], self.scores[1]));
+        lines.push(format!("Dealer: {}, Vulnerable: {}", NAMES[self.auction.dealer], vulnerability));
+        let calls: Vec<String> = self.auction.calls.iter().enumerate().map(|(i, call)| format!("{} {}", NAMES[self.auction.seat_of(i)], call)).collect();
+        lines.push(format!("Auction: {}", calls.join(", ")));
+
+        if let Some(contract) = self.contract
+        {
+            lines.push(format!("Contract: {}", contract));
+            lines.push(format!("Tricks: North-South {}, East-West {}", self.side_tricks(0), self.side_tricks(1)));
+            if let Some(dummy) = self.dummy().filter(|_| self.dummy_exposed())
+            {
+                lines.push(format!("Dummy ({}): {}", NAMES[dummy], cards(dummy)));
+            }
+            let trick: Vec<String> = self.round.trick.plays.iter().map(|(seat, card)| format!("{}: {}", NAMES[*seat], card)).collect();
+            lines.push(format!("Trick: {}", trick.join(" | ")));
+        }
+        lines.push(format!("Your hand: {}", cards(HUMAN)));
+        lines.push("+ ------------------- +".to_string());
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Bridge
+{
+    fn win(&self) -> bool
+    {
+        self.board > BOARDS && self.scores[0] > self.scores[1]
+    }
+
+    fn handle_input(&mut self)
+    {
+        if !self.auction.finished()
+        {
+            self.bidding();
+            if self.auction.finished() && self.contract.is_none()
+            {
+                println!("Passed out");
+                self.score_board();
+            }
+            return
+        }
+
+        self.play_bots();
+        if !self.round.finished()
+        {
+            println!("{}", self);
+            self.get_play();
+            self.play_bots();
+        }
+
+        if self.round.finished()
+        {
+            let contract = self.contract.unwrap();
+            let points = self.score_board();
+            if points > 0
+            {
+                println!("{} made for {}", contract, points);
+            }
+            else
+            {
+                println!("{} went down for {}", contract, -points);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    fn contract(level: u8, strain: Strain, doubled: u8) -> Contract
+    {
+        Contract { level, strain, doubled, declarer: 0 }
+    }
+
+    #[test]
+    fn auction_rules()
+    {
+        let mut auction = Auction::new(0);
+
+        assert!(auction.call(Call::Double).is_err());
+        auction.call(Call::Bid(1, Strain::Hearts)).unwrap();
+        assert!(auction.call(Call::Bid(1, Strain::Diamonds)).is_err());
+        assert!(auction.call(Call::Redouble).is_err());
+        auction.call(Call::Bid(1, Strain::Spades)).unwrap();
+        // Partner's bid can't be doubled
+        auction.call(Call::Pass).unwrap();
+        assert!(auction.call(Call::Double).is_err());
+        auction.call(Call::Pass).unwrap();
+        auction.call(Call::Double).unwrap();
+        assert!(auction.call(Call::Double).is_err());
+        auction.call(Call::Redouble).unwrap();
+        assert!(auction.call(Call::Bid(8, Strain::Clubs)).is_err());
+        assert_eq!(auction.cheapest_level(Strain::Hearts), 2);
+        assert_eq!(auction.cheapest_level(Strain::NoTrump), 1);
+    }
+
+    #[test]
+    fn declarer()
+    {
+        // West opens hearts and East, who first named spades, plays it doubled
+        let mut auction = Auction::new(1);
+        for call in [
+            Call::Bid(1, Strain::Hearts), Call::Pass, Call::Bid(1, Strain::Spades), Call::Pass,
+            Call::Bid(2, Strain::Spades), Call::Pass, Call::Bid(4, Strain::Spades), Call::Double,
+            Call::Pass, Call::Pass, Call::Pass
+        ].iter()
+        {
+            assert!(auction.contract().is_none());
+            auction.call(*call).unwrap();
+        }
+
+        assert!(auction.finished());
+        assert_eq!(auction.contract(), Some(Contract { level: 4, strain: Strain::Spades, doubled: 1, declarer: 3 }));
+        assert!(auction.call(Call::Pass).is_err());
+
+        let mut passed_out = Auction::new(0);
+        for _ in 0..4 { passed_out.call(Call::Pass).unwrap() }
+        assert!(passed_out.finished());
+        assert_eq!(passed_out.contract(), None);
+    }
+
+    #[test]
+    fn duplicate_scoring()
+    {
+        assert_eq!(score(&contract(4, Strain::Spades, 0), 10, false), 420);
+        assert_eq!(score(&contract(3, Strain::NoTrump, 0), 10, true), 630);
+        assert_eq!(score(&contract(1, Strain::NoTrump, 0), 7, false), 90);
+        assert_eq!(score(&contract(1, Strain::NoTrump, 1), 8, false), 280);
+        assert_eq!(score(&contract(2, Strain::Hearts, 1), 8, false), 470);
+        assert_eq!(score(&contract(6, Strain::Clubs, 0), 12, true), 1370);
+        assert_eq!(score(&contract(7, Strain::NoTrump, 2), 13, true), 2980);
+        assert_eq!(score(&contract(4, Strain::Hearts, 0), 8, true), -200);
+        assert_eq!(score(&contract(3, Strain::Spades, 1), 6, false), -500);
+        assert_eq!(score(&contract(1, Strain::NoTrump, 2), 6, true), -400);
+    }
+
+    #[test]
+    fn boards()
+    {
+        assert_eq!(dealer(1), 2);
+        assert_eq!(dealer(2), 3);
+        assert_eq!(dealer(3), HUMAN);
+        assert!(!vulnerable(1, 0) && !vulnerable(1, 1));
+        assert!(vulnerable(2, 2) && !vulnerable(2, 3));
+        assert!(!vulnerable(3, 0) && vulnerable(3, 1));
+        assert!(vulnerable(4, 0) && vulnerable(4, 1));
+    }
+
+    #[test]
+    fn bot_bidding()
+    {
+        let opener = crate::deck::parse_cards("AsKs7s4s2sKhQh3h2hAd5c4c2c").unwrap();
+        let notrump = crate::deck::parse_cards("AsKs7s4sKhQh3hAd8d5c4c3c2c").unwrap();
+        assert_eq!(bot_call(&Auction::new(0), &opener), Call::Bid(1, Strain::Spades));
+        assert_eq!(bot_call(&Auction::new(0), &notrump), Call::Bid(1, Strain::NoTrump));
+
+        // Partner opened a spade and this hand raises straight to game
+        let mut auction = Auction::new(2);
+        auction.call(Call::Bid(1, Strain::Spades)).unwrap();
+        auction.call(Call::Pass).unwrap();
+        assert_eq!(bot_call(&auction, &opener), Call::Bid(4, Strain::Spades));
+    }
+
+    #[test]
+    fn bots_play_a_board()
+    {
+        let mut game = Bridge::new();
+        for call in [Call::Bid(3, Strain::NoTrump), Call::Pass, Call::Pass, Call::Pass].iter()
+        {
+            game.make_call(*call).unwrap();
+        }
+        let declarer = dealer(1);
+        assert_eq!(game.controller((declarer + 2) % PLAYERS), declarer);
+        assert!(!game.dummy_exposed());
+
+        let rules = game.rules();
+        while !game.round.finished()
+        {
+            let seat = game.round.to_play();
+            let index = bot_play(&game.round.hands[seat], &game.round.legal(&rules), &game.round.trick, seat, &rules);
+            game.play_card(index).unwrap();
+            assert!(game.dummy_exposed());
+        }
+
+        assert_eq!(game.side_tricks(0) + game.side_tricks(1), TRICKS);
+        game.score_board();
+        assert_eq!(game.board, 2);
+        assert_eq!(game.scores.iter().filter(|s| **s > 0).count(), 1);
+    }
+}
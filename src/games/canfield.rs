@@ -0,0 +1,403 @@
+use crate::deck::{Stack, Card, Suit, rank_name};
+use crate::game::Game;
+use std::collections::HashMap;
+use text_io::read;
+use std::fmt;
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const COLUMNS: usize = 4;
+const RESERVE_SIZE: usize = 13;
+const DRAW_COUNT: usize = 3;
+const FOUNDATION_SIZE: usize = 13;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Position
+{
+    Reserve,
+    Waste,
+    Columns,
+    Foundation
+}
+
+#[derive(Copy, Clone, Debug)]
+struct MoveCommand
+{
+    location: Position,
+    i: usize
+}
+
+pub struct Canfield
+{
+    deck: Stack,
+    waste: Vec<Card>,
+    reserve: Vec<Card>,
+    columns: Vec<Vec<Card>>,
+    foundations: HashMap<Suit, Vec<Card>>,
+    // Every foundation starts on the rank of the first card dealt to them
+    base_rank: u8,
+}
+
+impl Canfield
+{
+    fn new() -> Canfield
+    {
+        let mut deck = Stack::new_deck(false);
+
+        deck.shuffle();
+
+        let mut reserve: Vec<Card> = deck.deal(RESERVE_SIZE).into_iter().map(Card::hide).collect();
+        if let Some(top) = reserve.last_mut() { top.set_visible(true) }
+
+        let first = deck.draw();
+        let mut foundations = HashMap::new();
+        foundations.insert(Suit::Club, vec![]);
+        foundations.insert(Suit::Spade, vec![]);
+        foundations.insert(Suit::Diamond, vec![]);
+        foundations.insert(Suit::Heart, vec![]);
+        foundations.get_mut(&first.suit).unwrap().push(first);
+
+        let columns = (0..COLUMNS).map(|_| vec![deck.draw()]).collect();
+
+        Canfield { deck, waste: vec![], reserve, columns, foundations, base_rank: first.rank }
+    }
+
+    pub fn play()
+    {
+        let mut game = Canfield::new();
+
+        while !game.win()
+        {
+            game.handle_input();
+        }
+
+        println!("You won 😀");
+    }
+
+    fn get_move_input(&self) -> Option<MoveCommand>
+    {
+        let get_move_position = |min, max| {
+            println!("Select a number between {} and {}", min, max);
+            let position_i: usize = read!("{}\n");
+
+            position_i - 1
+        };
+
+        let positions = ["Reserve", "Waste", "Columns", "Foundation", "Back"];
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&positions)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) =>
+            {
+                println!("+ -------------------- + ");
+                match positions[index] {
+                    "Reserve" => Some(MoveCommand { location: Position::Reserve, i: 0 }),
+                    "Waste" => Some(MoveCommand { location: Position::Waste, i: 0 }),
+                    "Columns" => Some(MoveCommand { location: Position::Columns, i: get_move_position(1, COLUMNS) }),
+                    "Foundation" => Some(MoveCommand { location: Position::Foundation, i: 0 }),
+                    "Back" => None,
+                    _ => {
+                        println!("Invalid selection");
+                        self.get_move_input()
+                    }
+                }
+            },
+            None => {
+                println!("Invalid selection");
+                self.get_move_input()
+            }
+        }
+    }
+
+    // The cards that would be picked up by a move from `from`. Whole columns
+    // move as a unit onto other columns, but only their top card can go up
+    // to a foundation.
+    fn moving_cards(&self, from: MoveCommand, to: MoveCommand) -> Option<&[Card]>
+    {
+        let pile = match from.location
+        {
+            Position::Reserve => &self.reserve[self.reserve.len().saturating_sub(1)..],
+            Position::Waste => &self.waste[self.waste.len().saturating_sub(1)..],
+            Position::Columns => {
+                let column = self.columns.get(from.i)?;
+                match to.location
+                {
+                    Position::Foundation => &column[column.len().saturating_sub(1)..],
+                    _ => &column[..]
+                }
+            },
+            Position::Foundation => return None
+        };
+
+        if pile.is_empty() { None } else { Some(pile) }
+    }
+
+    fn is_move_valid(&self, from: MoveCommand, to: MoveCommand) -> bool
+    {
+        let moving = match self.moving_cards(from, to) { Some(m) => m, None => return false };
+        let move_card = moving[0];
+
+        match to.location
+        {
+            Position::Reserve | Position::Waste => false,
+            Position::Columns => {
+                if from.location == Position::Columns && from.i == to.i { return false }
+                match self.columns.get(to.i)
+                {
+                    Some(column) => match column.last() {
+                        Some(c) => move_card.color() != c.color() && move_card.next_rank() == c.rank,
+                        // Empty columns are refilled from the reserve, so they only
+                        // take other cards once the reserve has run out
+                        None => self.reserve.is_empty()
+                    },
+                    None => false
+                }
+            },
+            Position::Foundation => {
+                match self.foundations.get(&move_card.suit).unwrap().last()
+                {
+                    Some(c) => c.next_rank() == move_card.rank,
+                    None => move_card.rank == self.base_rank
+                }
+            }
+        }
+    }
+
+    fn make_move(&mut self, from: MoveCommand, to: MoveCommand) -> Result<(), String>
+    {
+        if !self.is_move_valid(from, to)
+        {
+            return Err(String::from("Unable to make that move"))
+        }
+
+        let moving = match from.location
+        {
+            Position::Reserve => {
+                let card = self.reserve.pop().unwrap();
+                if let Some(next) = self.reserve.last_mut() { next.set_visible(true) }
+                vec![card]
+            },
+            Position::Waste => vec![self.waste.pop().unwrap()],
+            Position::Columns => {
+                let column = &mut self.columns[from.i];
+                let split = if to.location == Position::Foundation { column.len() - 1 } else { 0 };
+                column.split_off(split)
+            },
+            Position::Foundation => return Err(String::from("Unable to make that move"))
+        };
+
+        match to.location
+        {
+            Position::Columns => self.columns[to.i].extend(moving),
+            Position::Foundation => self.foundations.get_mut(&moving[0].suit).unwrap().extend(moving),
+            _ => return Err(String::from("Unable to make that move"))
+        };
+
+        self.fill_from_reserve();
+
+        Ok(())
+    }
+
+    fn fill_from_reserve(&mut self)
+    {
+        for column in self.columns.iter_mut().filter(|c| c.is_empty())
+        {
+            if let Some(card) = self.reserve.pop()
+            {
+                column.push(card.see());
+                if let Some(next) = self.reserve.last_mut() { next.set_visible(true) }
+            }
+        }
+    }
+
+    fn handle_move(&mut self) -> Result<(), String>
+    {
+        let from = match self.get_move_input() { Some(e) => e, None => return Err(String::from("Going back"))};
+        let to = match self.get_move_input() { Some(e) => e, None => return Err(String::from("Going back"))};
+
+        self.make_move(from, to)
+    }
+
+    fn handle_draw(&mut self)
+    {
+        if self.deck.size() == 0
+        {
+            self.deck = Stack::from_cards(std::mem::take(&mut self.waste));
+            return
+        }
+
+        let count = DRAW_COUNT.min(self.deck.size());
+        self.waste.extend(self.deck.deal(count));
+    }
+}
+
+impl fmt::Display for Canfield {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let max_column = self.columns.iter().map(|c| c.len()).max().unwrap();
+        let top_str = |suit: Suit| match self.foundations.get(&suit).unwrap().last() { Some(e) => e.to_string(), None => String::from("---") };
+        let reserve_str = match self.reserve.last() { Some(e) => e.to_string(), None => String::from("---") };
+        let waste_str = match self.waste.last() { Some(e) => e.to_string(), None => String::from("---") };
+
+        let mut lines = vec![
+            format!("🃏 : {} Cards remaining", self.deck.size()),
+            format!(
+                "+ FOUNDATIONS ({}): ---{}---{}---{}---{}--- +",
+                rank_name(self.base_rank).trim(),
+                top_str(Suit::Spade), top_str(Suit::Club), top_str(Suit::Diamond), top_str(Suit::Heart)
+                ),
+            format!("+ --- Reserve ({}): {} --- Waste: {} --- +", self.reserve.len(), reserve_str, waste_str)
+            ];
+        for i in 0..max_column
+        {
+            let strings: Vec<String> = self.columns.iter().map(|c| c.get(i)).map(|c| {
+                match c {
+                    Some(m) => format!("{}", m),
+                    None => "   ".to_string()
+                }
+            }).collect();
+            lines.push(strings.join(" | "));
+        };
+
+        lines.push("+ ------------------- +".to_string());
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Canfield
+{
+    fn win(&self) -> bool
+    {
+        self.foundations.values().all(|f| f.len() == FOUNDATION_SIZE)
+    }
+
+    fn handle_input(&mut self)
+    {
+        let commands = ["Display", "Move", "Draw"];
+        println!("Select a command: ");
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&commands)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) =>
+            {
+                println!("+ -------------------- + ");
+                match commands[index] {
+                    "Display" => println!("{}", self),
+                    "Move" => self.handle_move().unwrap_or_else(|err| println!("{}", err)),
+                    "Draw" => self.handle_draw(),
+                    _ => {
+                        println!("Invalid selection");
+                        self.handle_input();
+                    }
+                }
+            }
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    fn card(suit: Suit, rank: u8) -> Card
+    {
+        Card { suit, rank, visible: true }
+    }
+
+    fn game_with(base_rank: u8) -> Canfield
+    {
+        let mut game = Canfield::new();
+        for foundation in game.foundations.values_mut() { foundation.clear() }
+        game.base_rank = base_rank;
+        game
+    }
+
+    const FOUNDATION: MoveCommand = MoveCommand { location: Position::Foundation, i: 0 };
+
+    #[test]
+    fn deal()
+    {
+        let game = Canfield::new();
+
+        assert_eq!(game.reserve.len(), RESERVE_SIZE);
+        assert!(game.reserve.last().unwrap().visible);
+        assert!(!game.reserve[0].visible);
+        assert_eq!(game.foundations.values().map(|f| f.len()).sum::<usize>(), 1);
+        assert_eq!(game.deck.size(), 52 - RESERVE_SIZE - 1 - COLUMNS);
+    }
+
+    #[test]
+    fn foundation_wraps_from_king_to_ace()
+    {
+        let mut game = game_with(12);
+        game.foundations.insert(Suit::Heart, vec![card(Suit::Heart, 12), card(Suit::Heart, 13)]);
+        game.waste = vec![card(Suit::Heart, 14)];
+
+        let from = MoveCommand { location: Position::Waste, i: 0 };
+        assert!(game.make_move(from, FOUNDATION).is_ok());
+
+        game.waste = vec![card(Suit::Heart, 2)];
+        assert!(game.make_move(from, FOUNDATION).is_ok());
+        assert_eq!(game.foundations.get(&Suit::Heart).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn foundation_starts_at_base_rank()
+    {
+        let mut game = game_with(7);
+        let from = MoveCommand { location: Position::Waste, i: 0 };
+
+        game.waste = vec![card(Suit::Club, 14)];
+        assert!(!game.is_move_valid(from, FOUNDATION));
+
+        game.waste = vec![card(Suit::Club, 7)];
+        assert!(game.is_move_valid(from, FOUNDATION));
+    }
+
+    #[test]
+    fn columns_move_as_a_unit_and_refill()
+    {
+        let mut game = game_with(5);
+        game.columns = vec![
+            vec![card(Suit::Spade, 2), card(Suit::Heart, 14)],
+            vec![card(Suit::Diamond, 3)],
+            vec![card(Suit::Club, 9)],
+            vec![card(Suit::Club, 10)],
+        ];
+        let reserve_top = *game.reserve.last().unwrap();
+
+        let from = MoveCommand { location: Position::Columns, i: 0 };
+        let to = MoveCommand { location: Position::Columns, i: 1 };
+        assert!(game.make_move(from, to).is_ok());
+
+        assert_eq!(game.columns[1].len(), 3);
+        assert_eq!(game.columns[0], vec![reserve_top]);
+        assert!(game.reserve.last().unwrap().visible);
+    }
+
+    #[test]
+    fn redeal_waste()
+    {
+        let mut game = Canfield::new();
+        let stock = game.deck.size();
+
+        while game.deck.size() > 0 { game.handle_draw() }
+        assert_eq!(game.waste.len(), stock);
+
+        game.handle_draw();
+        assert_eq!(game.deck.size(), stock);
+        assert!(game.waste.is_empty());
+    }
+}
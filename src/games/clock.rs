@@ -0,0 +1,182 @@
+use crate::deck::{Stack, Card};
+use crate::game::Game;
+use std::fmt;
+
+const PILES: usize = 13;
+const PILE_SIZE: usize = 4;
+// Kings go in the middle of the clock, the rest sit on their hour
+const KING_PILE: usize = 12;
+
+pub struct Clock
+{
+    // Face up cards are tucked under the bottom of a pile, so each pile reads
+    // face up cards first and the next face down card last
+    piles: Vec<Vec<Card>>,
+    in_hand: Option<Card>,
+}
+
+impl Clock
+{
+    fn new() -> Clock
+    {
+        let mut deck = Stack::new_deck(false);
+
+        deck.shuffle();
+
+        let piles = (0..PILES).map(|_| deck.deal(PILE_SIZE).into_iter().map(Card::hide).collect()).collect();
+
+        Clock { piles, in_hand: None }
+    }
+
+    pub fn play()
+    {
+        let mut game = Clock::new();
+
+        while !game.finished()
+        {
+            game.handle_input();
+        }
+
+        println!("{}", game);
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    fn pile_for(card: &Card) -> usize
+    {
+        card.low_rank() as usize - 1
+    }
+
+    fn finished(&self) -> bool
+    {
+        self.in_hand.is_none() && !self.piles[KING_PILE].iter().any(|c| !c.visible)
+    }
+
+    // Places the card in hand under its hour and picks up the next face down card
+    // from that same pile. The first turn starts from the kings in the middle.
+    fn turn(&mut self)
+    {
+        let pile = match self.in_hand.take()
+        {
+            Some(card) => {
+                let pile = Clock::pile_for(&card);
+                self.piles[pile].insert(0, card.see());
+                pile
+            },
+            None => KING_PILE
+        };
+
+        if self.piles[pile].last().is_some_and(|c| !c.visible)
+        {
+            self.in_hand = self.piles[pile].pop();
+        }
+    }
+}
+
+impl fmt::Display for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pile_str = |i: usize| {
+            let pile: &Vec<Card> = &self.piles[i];
+            let up = pile.iter().filter(|c| c.visible).count();
+            match pile.first() {
+                Some(c) if c.visible => format!("{}x{}", c, up),
+                _ => format!("{}x{}", "? ?", pile.len()),
+            }
+        };
+        let in_hand_str = match self.in_hand { Some(c) => c.see().to_string(), None => String::from("---") };
+
+        let lines = vec![
+            format!("🃏 : In hand {}", in_hand_str),
+            "+ ------------------- +".to_string(),
+            format!("{:^44}", pile_str(11)),
+            format!("{:^20}{:^24}", pile_str(10), pile_str(0)),
+            format!("{:<16}{:^12}{:>16}", pile_str(9), "", pile_str(1)),
+            format!("{:<16}{:^12}{:>16}", pile_str(8), pile_str(KING_PILE), pile_str(2)),
+            format!("{:<16}{:^12}{:>16}", pile_str(7), "", pile_str(3)),
+            format!("{:^20}{:^24}", pile_str(6), pile_str(4)),
+            format!("{:^44}", pile_str(5)),
+            "+ ------------------- +".to_string(),
+        ];
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Clock
+{
+    fn win(&self) -> bool
+    {
+        self.piles.iter().flatten().all(|c| c.visible)
+    }
+
+    // Clock has no decisions to make, so every turn is played automatically
+    fn handle_input(&mut self)
+    {
+        self.turn();
+        if let Some(card) = self.in_hand
+        {
+            println!("Turned {}", card.see());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::Suit;
+
+    fn card(suit: Suit, rank: u8) -> Card
+    {
+        Card { suit, rank, visible: false }
+    }
+
+    #[test]
+    fn plays_to_completion()
+    {
+        let mut game = Clock::new();
+
+        while !game.finished() { game.turn() }
+
+        assert_eq!(game.piles[KING_PILE].iter().filter(|c| c.visible).count(), PILE_SIZE);
+        assert_eq!(game.piles.iter().map(|p| p.len()).sum::<usize>(), 52);
+    }
+
+    #[test]
+    fn turns_onto_hour()
+    {
+        let mut game = Clock::new();
+        game.piles[KING_PILE] = vec![card(Suit::Club, 13), card(Suit::Heart, 3)];
+        game.piles[2] = vec![card(Suit::Spade, 13)];
+
+        game.turn();
+        assert_eq!(game.in_hand.unwrap().rank, 3);
+
+        game.turn();
+        assert!(game.piles[2][0].visible);
+        assert_eq!(game.in_hand.unwrap().rank, 13);
+    }
+
+    #[test]
+    fn win()
+    {
+        let mut game = Clock::new();
+        let suits = [Suit::Heart, Suit::Spade, Suit::Club, Suit::Diamond];
+        // Every pile holds the rank of the next hour round, so play goes around
+        // the whole clock four times before the last king turns up
+        game.piles = (0..PILES).map(|i| {
+            let rank = match (i + 1) % PILES { 0 => 14, next => next as u8 + 1 };
+            suits.iter().map(|s| card(*s, rank)).collect()
+        }).collect();
+
+        while !game.finished() { game.turn() }
+
+        assert!(game.win());
+    }
+}
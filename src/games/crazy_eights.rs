@@ -0,0 +1,253 @@
+use std::fmt;
+use crate::deck::{Card, Suit};
+use crate::shedding::{Shedding, SheddingRules, Effect};
+use crate::game::{Game, HUMAN, player_names, read_players};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const MAX_PLAYERS: usize = 6;
+const SUITS: [Suit; 4] = [Suit::Spade, Suit::Heart, Suit::Club, Suit::Diamond];
+
+// Names whichever suit the hand holds most of
+fn bot_suit(hand: &[Card]) -> Suit
+{
+    *SUITS.iter().max_by_key(|s| hand.iter().filter(|c| c.suit == **s).count()).unwrap()
+}
+
+// Saves wild cards for when nothing else plays, and otherwise plays from the suit
+// it holds most of, action cards first
+fn bot_play(game: &Shedding, seat: usize) -> Option<(usize, Option<Suit>)>
+{
+    let hand = &game.hands[seat];
+    let count = |suit: Suit| hand.iter().filter(|c| c.suit == suit).count();
+
+    let index = *game.playable(seat).iter().max_by_key(|i| {
+        let effect = game.rules.effect(&hand[**i]);
+        (effect != Some(Effect::Wild), effect.is_some(), count(hand[**i].suit))
+    })?;
+    let wild = game.rules.effect(&hand[index]) == Some(Effect::Wild);
+    let rest: Vec<Card> = hand.iter().enumerate().filter(|(i, _)| *i != index).map(|(_, c)| *c).collect();
+
+    Some((index, if wild { Some(bot_suit(&rest)) } else { None }))
+}
+
+pub struct CrazyEights
+{
+    names: Vec<String>,
+    game: Shedding,
+    leave: bool,
+}
+
+impl CrazyEights
+{
+    pub fn new(players: usize, rules: SheddingRules) -> Result<CrazyEights, String>
+    {
+        Ok(CrazyEights { names: player_names(players), game: Shedding::new(players, rules)?, leave: false })
+    }
+
+    pub fn play()
+    {
+        let players = read_players(MAX_PLAYERS);
+        let variants = ["Crazy Eights", "Crazy Eights with action cards"];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Which rules?")
+            .items(&variants)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+        let rules = if selection == Some(1) { SheddingRules::action_cards() } else { SheddingRules::crazy_eights() };
+
+        let mut game = match CrazyEights::new(players, rules) {
+            Ok(game) => game,
+            Err(err) => return println!("{}", err)
+        };
+
+        while !game.leave && game.game.winner().is_none()
+        {
+            game.handle_input();
+        }
+
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    fn report(&self, seat: usize, card: Card)
+    {
+        if self.game.rules.effect(&card) == Some(Effect::Wild)
+        {
+            println!("{}: {} and names {}", self.names[seat], card, self.game.suit);
+        }
+        else
+        {
+            println!("{}: {}", self.names[seat], card);
+        }
+    }
+
+    // Plays if it can, and otherwise draws one card and plays that if it fits
+    fn bot_turn(&mut self)
+    {
+        let seat = self.game.to_play;
+
+        if bot_play(&self.game, seat).is_none()
+        {
+            match self.game.draw(seat) {
+                Some(_) => println!("{} draws", self.names[seat]),
+                None => println!("{} can't draw", self.names[seat])
+            }
+        }
+        match bot_play(&self.game, seat) {
+            Some((index, named)) => {
+                let card = self.game.play(index, named).unwrap();
+                self.report(seat, card);
+            },
+            None => self.game.pass()
+        }
+    }
+
+    fn play_bots(&mut self)
+    {
+        while self.game.winner().is_none() && self.game.to_play != HUMAN
+        {
+            self.bot_turn();
+        }
+    }
+
+    fn select(&self, prompt: &str, options: &[String]) -> Option<usize>
+    {
+        Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap()
+    }
+
+    fn get_suit(&self) -> Suit
+    {
+        let options: Vec<String> = SUITS.iter().map(|s| s.to_string()).collect();
+
+        SUITS[self.select("Name a suit", &options).unwrap_or(0)]
+    }
+
+    fn play_human(&mut self, index: usize)
+    {
+        let card = self.game.hands[HUMAN][index];
+        let named = if self.game.rules.effect(&card) == Some(Effect::Wild) { Some(self.get_suit()) } else { None };
+
+        match self.game.play(index, named) {
+            Ok(card) => self.report(HUMAN, card),
+            Err(err) => println!("{}", err)
+        }
+    }
+
+    fn human_turn(&mut self)
+    {
+        println!("{}", self);
+        let playable = self.game.playable(HUMAN);
+        let mut options: Vec<String> = playable.iter().map(|i| self.game.hands[HUMAN][*i].to_string()).collect();
+        options.push("Draw".to_string());
+        options.push("Leave".to_string());
+
+        match self.select("Play a card", &options) {
+            Some(index) if index < playable.len() => self.play_human(playable[index]),
+            Some(index) if index == playable.len() => {
+                match self.game.draw(HUMAN) {
+                    Some(card) if self.game.can_play(&card) => {
+                        let options = vec![format!("Play {}", card), "Keep it".to_string()];
+                        if self.select(&format!("You drew {}", card), &options) == Some(0)
+                        {
+                            return self.play_human(self.game.hands[HUMAN].len() - 1)
+                        }
+                    },
+                    Some(card) => println!("You drew {}", card),
+                    None => println!("There are no cards left to draw")
+                }
+                self.game.pass();
+            },
+            Some(_) => self.leave = true,
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+impl fmt::Display for CrazyEights {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![format!("+ --- {} to follow on {} --- +", self.game.suit, self.game.top())];
+        for (seat, hand) in self.game.hands.iter().enumerate().filter(|(seat, _)| *seat != HUMAN)
+        {
+            lines.push(format!("{:<6} {} cards", self.names[seat], hand.len()));
+        }
+        lines.push(format!("Stock: {} cards, play goes {}", self.game.stock.size(), if self.game.clockwise { "left" } else { "right" }));
+        let hand: Vec<String> = self.game.hands[HUMAN].iter().map(|c| c.to_string()).collect();
+        lines.push(format!("Your hand: {}", hand.join(" ")));
+        lines.push("+ ------------------- +".to_string());
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for CrazyEights
+{
+    fn win(&self) -> bool
+    {
+        self.game.winner() == Some(HUMAN)
+    }
+
+    fn handle_input(&mut self)
+    {
+        self.play_bots();
+        if self.game.winner().is_none()
+        {
+            self.human_turn();
+            self.play_bots();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    #[test]
+    fn bot_choices()
+    {
+        let mut game = Shedding::new(2, SheddingRules::action_cards()).unwrap();
+        game.discards = parse_cards("5s").unwrap();
+        game.suit = Suit::Spade;
+
+        game.hands[1] = parse_cards("8h5h9s3h").unwrap();
+        assert_eq!(bot_play(&game, 1), Some((1, None)));
+        game.hands[1] = parse_cards("8h9d3h2h").unwrap();
+        assert_eq!(bot_play(&game, 1), Some((0, Some(Suit::Heart))));
+        game.hands[1] = parse_cards("9s3hQs").unwrap();
+        assert_eq!(bot_play(&game, 1), Some((2, None)));
+        game.hands[1] = parse_cards("9h").unwrap();
+        assert_eq!(bot_play(&game, 1), None);
+    }
+
+    #[test]
+    fn bots_play_a_game()
+    {
+        for rules in [SheddingRules::crazy_eights(), SheddingRules::action_cards()].iter()
+        {
+            let mut game = CrazyEights::new(5, rules.clone()).unwrap();
+
+            for _ in 0..2000
+            {
+                if game.game.winner().is_some() { break }
+                game.bot_turn();
+            }
+            let cards: usize = game.game.hands.iter().map(|h| h.len()).sum::<usize>() + game.game.stock.size() + game.game.discards.len();
+            assert_eq!(cards, 52);
+        }
+    }
+}
@@ -0,0 +1,493 @@
+use std::fmt;
+use crate::deck::{Stack, Card};
+use crate::cribbage::{HandCount, card_value, count_hand, peg_points};
+use crate::game::{Game, HUMAN};
+use dialoguer::{
+    Select,
+    MultiSelect,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const BOT: usize = 1;
+const DEAL: usize = 6;
+const DISCARDS: usize = 2;
+const GAME_OVER: u32 = 121;
+const MAX_COUNT: u32 = 31;
+// Each character of the peg board covers two holes, split into sections of twenty
+const HOLES_PER_MARK: u32 = 2;
+const MARKS_PER_SECTION: u32 = 10;
+
+fn cards_string(cards: &[Card]) -> String
+{
+    cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+// Every way of choosing `DISCARDS` cards from a hand of `n`
+fn discard_choices(n: usize) -> Vec<Vec<usize>>
+{
+    (0..n).flat_map(|a| (a + 1..n).map(move |b| vec![a, b])).collect()
+}
+
+// Keeps the four cards that score best on average over every possible starter,
+// counting what goes into the crib for the dealer and against everyone else
+fn bot_discards(hand: &[Card], dealer: bool) -> Vec<usize>
+{
+    let starters: Vec<Card> = Stack::new_deck(false).deal(52).into_iter().filter(|c| !hand.contains(c)).collect();
+    let value = |discards: &Vec<usize>| -> i32 {
+        let kept: Vec<Card> = (0..hand.len()).filter(|i| !discards.contains(i)).map(|i| hand[i]).collect();
+        let thrown: Vec<Card> = discards.iter().map(|i| hand[*i]).collect();
+        let kept_points: u32 = starters.iter().map(|s| count_hand(&kept, Some(*s), false).total()).sum();
+        let crib_points = count_hand(&thrown, None, true).total() as i32 * starters.len() as i32;
+
+        kept_points as i32 + if dealer { crib_points } else { -crib_points }
+    };
+
+    discard_choices(hand.len()).into_iter().max_by_key(value).unwrap()
+}
+
+// Takes the most points it can, avoids leaving a count of five or twenty one for the
+// other player to make fifteen or thirty one from, and otherwise gets rid of high cards
+fn bot_peg(hand: &[Card], playable: &[usize], played: &[Card]) -> usize
+{
+    let count: u32 = played.iter().map(card_value).sum();
+
+    *playable.iter().max_by_key(|i| {
+        let mut after = played.to_vec();
+        after.push(hand[**i]);
+        let total = count + card_value(&hand[**i]);
+        (peg_points(&after), total != 5 && total != 21, card_value(&hand[**i]))
+    }).unwrap()
+}
+
+pub struct Cribbage
+{
+    names: Vec<String>,
+    scores: Vec<u32>,
+    dealer: usize,
+    deck: Stack,
+    // The four cards each player keeps for the show
+    hands: Vec<Vec<Card>>,
+    // The cards each player still has to peg
+    pegging: Vec<Vec<Card>>,
+    crib: Vec<Card>,
+    starter: Option<Card>,
+    // Cards played since the count was last reset
+    played: Vec<Card>,
+    to_play: usize,
+    last_played: usize,
+    leave: bool,
+}
+
+impl Cribbage
+{
+    pub fn new() -> Cribbage
+    {
+        let mut game = Cribbage
+        {
+            names: vec!["You".to_string(), "Bot".to_string()],
+            scores: vec![0; 2],
+            dealer: BOT,
+            deck: Stack::new_deck(false),
+            hands: vec![vec![]; 2],
+            pegging: vec![vec![]; 2],
+            crib: vec![],
+            starter: None,
+            played: vec![],
+            to_play: HUMAN,
+            last_played: HUMAN,
+            leave: false
+        };
+
+        game.deal();
+        game
+    }
+
+    pub fn play()
+    {
+        let mut game = Cribbage::new();
+
+        while !game.leave && !game.game_over()
+        {
+            game.handle_input();
+        }
+
+        println!("{}", game.peg_board());
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    pub fn deal(&mut self)
+    {
+        self.deck = Stack::new_deck(false);
+        self.deck.shuffle();
+
+        for seat in 0..2
+        {
+            let mut hand = self.deck.deal(DEAL);
+            hand.sort_by_key(|c| (c.low_rank(), c.suit as u8));
+            self.hands[seat] = hand;
+            self.pegging[seat].clear();
+        }
+        self.crib.clear();
+        self.starter = None;
+        self.played.clear();
+        self.to_play = 1 - self.dealer;
+    }
+
+    fn game_over(&self) -> bool
+    {
+        self.scores.iter().any(|s| *s >= GAME_OVER)
+    }
+
+    fn peg(&mut self, seat: usize, points: u32, reason: &str)
+    {
+        if points == 0 || self.game_over() { return }
+
+        self.scores[seat] = (self.scores[seat] + points).min(GAME_OVER);
+        println!("{} pegs {} for {}", self.names[seat], points, reason);
+    }
+
+    // Puts two cards from a player's hand into the dealer's crib
+    pub fn discard(&mut self, seat: usize, discards: &[usize]) -> Result<(), String>
+    {
+        let mut discards = discards.to_vec();
+        discards.sort_unstable();
+        discards.dedup();
+        if discards.len() != DISCARDS || discards.iter().any(|i| *i >= self.hands[seat].len())
+        {
+            return Err(format!("Choose {} cards to put in the crib", DISCARDS))
+        }
+
+        for i in discards.iter().rev()
+        {
+            let card = self.hands[seat].remove(*i);
+            self.crib.push(card);
+        }
+        self.pegging[seat] = self.hands[seat].clone();
+
+        Ok(())
+    }
+
+    // Turns the starter once both players have discarded. A jack is two for the dealer.
+    pub fn cut(&mut self)
+    {
+        let starter = self.deck.draw();
+        println!("The starter is {}", starter);
+        self.starter = Some(starter);
+
+        if starter.rank == 11
+        {
+            self.peg(self.dealer, 2, "his heels");
+        }
+    }
+
+    fn count(&self) -> u32
+    {
+        self.played.iter().map(card_value).sum()
+    }
+
+    pub fn playable(&self, seat: usize) -> Vec<usize>
+    {
+        (0..self.pegging[seat].len()).filter(|i| self.count() + card_value(&self.pegging[seat][*i]) <= MAX_COUNT).collect()
+    }
+
+    pub fn pegging_over(&self) -> bool
+    {
+        self.pegging.iter().all(|hand| hand.is_empty())
+    }
+
+    // Plays a card for the player whose turn it is and pegs what it makes
+    pub fn play_card(&mut self, index: usize) -> Result<(), String>
+    {
+        let seat = self.to_play;
+        if !self.playable(seat).contains(&index)
+        {
+            return Err(format!("Unable to play that card, the count is {}", self.count()))
+        }
+
+        let card = self.pegging[seat].remove(index);
+        self.played.push(card);
+        println!("{}: {} for {}", self.names[seat], card, self.count());
+        self.peg(seat, peg_points(&self.played), "the play");
+        self.last_played = seat;
+        self.advance();
+
+        Ok(())
+    }
+
+    // Passes the play on. A player who can't play says go, and once neither can the
+    // last to play pegs one, unless they just made thirty one, and the count starts again.
+    fn advance(&mut self)
+    {
+        let seat = self.last_played;
+        let other = 1 - seat;
+
+        if self.count() == MAX_COUNT
+        {
+            self.played.clear();
+        }
+        if self.pegging_over()
+        {
+            if !self.played.is_empty() { self.peg(seat, 1, "last card") }
+            self.played.clear();
+            return
+        }
+
+        if !self.playable(other).is_empty()
+        {
+            self.to_play = other;
+        }
+        else if !self.playable(seat).is_empty()
+        {
+            if !self.pegging[other].is_empty() { println!("{}: go", self.names[other]) }
+            self.to_play = seat;
+        }
+        else
+        {
+            if !self.played.is_empty() { self.peg(seat, 1, "go") }
+            self.played.clear();
+            self.to_play = if self.pegging[other].is_empty() { seat } else { other };
+        }
+    }
+
+    // Counts the non-dealer's hand, then the dealer's and the crib, and passes the deal
+    pub fn show(&mut self) -> Vec<(String, HandCount)>
+    {
+        let pone = 1 - self.dealer;
+        let shows = [
+            (pone, format!("{}'s hand", self.names[pone]), self.hands[pone].clone(), false),
+            (self.dealer, format!("{}'s hand", self.names[self.dealer]), self.hands[self.dealer].clone(), false),
+            (self.dealer, format!("{}'s crib", self.names[self.dealer]), self.crib.clone(), true)
+        ];
+        let mut counts = vec![];
+
+        for (seat, name, cards, crib) in shows.iter()
+        {
+            let count = count_hand(cards, self.starter, *crib);
+            println!("{} {}: {}", name, cards_string(cards), count);
+            self.peg(*seat, count.total(), name);
+            counts.push((name.clone(), count));
+        }
+        self.dealer = 1 - self.dealer;
+
+        counts
+    }
+
+    fn peg_line(&self, seat: usize) -> String
+    {
+        let marks: String = (0..GAME_OVER / HOLES_PER_MARK).map(|mark| {
+            let filled = if self.scores[seat] > mark * HOLES_PER_MARK { '#' } else { '.' };
+            if mark % MARKS_PER_SECTION == 0 { format!("|{}", filled) } else { filled.to_string() }
+        }).collect();
+
+        format!("{:<4}{}| {}", self.names[seat], marks, self.scores[seat])
+    }
+
+    pub fn peg_board(&self) -> String
+    {
+        format!("{}\n{}", self.peg_line(HUMAN), self.peg_line(BOT))
+    }
+
+    fn get_discards(&self) -> Vec<usize>
+    {
+        let cards: Vec<String> = self.hands[HUMAN].iter().map(|c| c.to_string()).collect();
+        let owner = if self.dealer == HUMAN { "your" } else { "the bot's" };
+
+        MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Choose {} cards for {} crib (space to toggle)", DISCARDS, owner))
+            .items(&cards)
+            .interact_on(&Term::stderr()).unwrap()
+    }
+
+    fn discarding(&mut self)
+    {
+        println!("{}", self);
+        while self.hands[HUMAN].len() == DEAL
+        {
+            let discards = self.get_discards();
+            self.discard(HUMAN, &discards).unwrap_or_else(|err| println!("{}", err));
+        }
+
+        let discards = bot_discards(&self.hands[BOT], self.dealer == BOT);
+        self.discard(BOT, &discards).unwrap();
+        self.cut();
+    }
+
+    fn play_bot(&mut self)
+    {
+        while !self.pegging_over() && self.to_play == BOT && !self.game_over()
+        {
+            let playable = self.playable(BOT);
+            let index = bot_peg(&self.pegging[BOT], &playable, &self.played);
+            self.play_card(index).unwrap();
+        }
+    }
+
+    fn get_play(&mut self)
+    {
+        let playable = self.playable(HUMAN);
+        let mut options: Vec<String> = playable.iter().map(|i| self.pegging[HUMAN][*i].to_string()).collect();
+        options.push("Leave".to_string());
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Play a card, the count is {}", self.count()))
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) if index == playable.len() => self.leave = true,
+            Some(index) => self.play_card(playable[index]).unwrap_or_else(|err| println!("{}", err)),
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+impl fmt::Display for Cribbage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec!["+ --- Peg Board --- +".to_string(), self.peg_board()];
+        lines.push("+ ------------------- +".to_string());
+        lines.push(format!("Dealer: {}", self.names[self.dealer]));
+        if let Some(starter) = self.starter
+        {
+            lines.push(format!("Starter: {}", starter));
+            lines.push(format!("Count: {} ({})", self.count(), cards_string(&self.played)));
+            lines.push(format!("Your cards: {}", cards_string(&self.pegging[HUMAN])));
+        }
+        else
+        {
+            lines.push(format!("Your hand: {}", cards_string(&self.hands[HUMAN])));
+        }
+        lines.push("+ ------------------- +".to_string());
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Cribbage
+{
+    fn win(&self) -> bool
+    {
+        self.scores[HUMAN] >= GAME_OVER
+    }
+
+    fn handle_input(&mut self)
+    {
+        if self.starter.is_none()
+        {
+            return self.discarding()
+        }
+
+        self.play_bot();
+        if !self.pegging_over() && !self.game_over()
+        {
+            println!("{}", self);
+            self.get_play();
+            self.play_bot();
+        }
+
+        if self.pegging_over() && !self.game_over()
+        {
+            self.show();
+            println!("{}", self.peg_board());
+            if !self.game_over()
+            {
+                self.deal();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    #[test]
+    fn discarding()
+    {
+        let mut game = Cribbage::new();
+
+        assert!(game.discard(HUMAN, &[0]).is_err());
+        assert!(game.discard(HUMAN, &[0, 0]).is_err());
+        assert!(game.discard(HUMAN, &[0, 6]).is_err());
+        game.discard(HUMAN, &[0, 5]).unwrap();
+        assert_eq!(game.hands[HUMAN].len(), 4);
+        assert_eq!(game.crib.len(), 2);
+
+        // Holding on to the double run and the fifteens
+        let hand = parse_cards("4h5h5c6dKsQc").unwrap();
+        assert_eq!(bot_discards(&hand, false), vec![4, 5]);
+    }
+
+    #[test]
+    fn go_and_last_card()
+    {
+        let mut game = Cribbage::new();
+        game.dealer = BOT;
+        game.scores = vec![0, 0];
+        game.pegging = vec![parse_cards("KhQh5c").unwrap(), parse_cards("Th9d").unwrap()];
+        game.to_play = HUMAN;
+
+        game.play_card(0).unwrap();
+        game.play_card(0).unwrap();
+        // 20, and with nothing for the bot to play after the queen the human gets a go
+        game.play_card(0).unwrap();
+        assert_eq!(game.scores, vec![1, 0]);
+        assert!(game.played.is_empty());
+        assert_eq!(game.to_play, BOT);
+
+        game.play_card(0).unwrap();
+        game.play_card(0).unwrap();
+        assert!(game.pegging_over());
+        assert_eq!(game.scores, vec![2, 0]);
+    }
+
+    #[test]
+    fn thirty_one()
+    {
+        let mut game = Cribbage::new();
+        game.pegging = vec![parse_cards("KhAc5d").unwrap(), parse_cards("QsTc").unwrap()];
+        game.to_play = HUMAN;
+
+        for _ in 0..4 { game.play_card(0).unwrap() }
+        assert_eq!(game.scores, vec![0, 2]);
+        assert!(game.played.is_empty());
+        assert_eq!(game.to_play, HUMAN);
+        assert!(game.play_card(1).is_err());
+    }
+
+    #[test]
+    fn bots_play_a_hand()
+    {
+        let mut game = Cribbage::new();
+        for seat in 0..2
+        {
+            let discards = bot_discards(&game.hands[seat], game.dealer == seat);
+            game.discard(seat, &discards).unwrap();
+        }
+        game.cut();
+
+        while !game.pegging_over()
+        {
+            assert!(game.count() <= MAX_COUNT);
+            let seat = game.to_play;
+            let index = bot_peg(&game.pegging[seat], &game.playable(seat), &game.played);
+            game.play_card(index).unwrap();
+        }
+        let pegged: u32 = game.scores.iter().sum();
+        let shown: u32 = game.show().iter().map(|(_, count)| count.total()).sum();
+
+        assert!(pegged >= 1);
+        assert_eq!(game.scores.iter().sum::<u32>(), pegged + shown);
+        assert_eq!(game.peg_board().lines().count(), 2);
+    }
+}
@@ -0,0 +1,502 @@
+use std::fmt;
+use crate::deck::{Stack, Card, Suit};
+use crate::game::{Game, HUMAN, player_names, read_players};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const MAX_PLAYERS: usize = 6;
+const HAND_SIZE: usize = 6;
+// No bout has more attacking cards than this
+const MAX_ATTACKS: usize = 6;
+
+// A higher card of the same suit beats an attack, and any trump beats a card that isn't one
+pub fn beats(attack: &Card, defence: &Card, trump: Suit) -> bool
+{
+    if defence.suit == attack.suit { defence.rank > attack.rank } else { defence.suit == trump }
+}
+
+pub struct Durak
+{
+    names: Vec<String>,
+    hands: Vec<Vec<Card>>,
+    stock: Stack,
+    // Turned up under the stock and drawn last
+    trump: Card,
+    attacker: usize,
+    defender: usize,
+    // Attacking cards, each with the card that beat it once it has been
+    table: Vec<(Card, Option<Card>)>,
+    // How many cards this bout's attack can have, going on the defender's hand
+    limit: usize,
+    leave: bool,
+}
+
+impl Durak
+{
+    // Six cards each from a deck of sixes up, and the card at the bottom of the stock
+    // names trumps. Whoever holds the lowest trump attacks first.
+    pub fn new(players: usize) -> Result<Durak, String>
+    {
+        if !(2..=MAX_PLAYERS).contains(&players)
+        {
+            return Err(format!("Durak is played by 2 to {} players, {} provided", MAX_PLAYERS, players))
+        }
+
+        let mut stock = Stack::new_partial_deck(6..=14);
+        stock.shuffle();
+        let hands: Vec<Vec<Card>> = (0..players).map(|_| stock.deal(HAND_SIZE)).collect();
+        // Six players take the whole deck, so the last card dealt shows trumps instead
+        let trump = *stock.bottom_card().unwrap_or_else(|| hands[players - 1].last().unwrap());
+
+        let lowest_trump = (0..players).filter_map(|s| {
+            hands[s].iter().filter(|c| c.suit == trump.suit).map(|c| (c.rank, s)).min()
+        }).min();
+
+        let mut game = Durak { names: player_names(players), hands, stock, trump, attacker: 0, defender: 0, table: vec![], limit: 0, leave: false };
+        game.start_bout(lowest_trump.map_or(0, |(_, seat)| seat));
+        Ok(game)
+    }
+
+    pub fn play()
+    {
+        let mut game = match Durak::new(read_players(MAX_PLAYERS)) {
+            Ok(game) => game,
+            Err(err) => return println!("{}", err)
+        };
+
+        while !game.leave && !game.over()
+        {
+            game.handle_input();
+        }
+
+        match game.fool() {
+            Some(fool) if !game.leave => println!("{} {} the durak", game.names[fool], if fool == HUMAN { "are" } else { "is" }),
+            None if !game.leave => println!("Everyone got out together, nobody is the durak"),
+            _ => ()
+        }
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    // A player is out once their hand is empty and there is nothing left to draw
+    fn in_game(&self, seat: usize) -> bool
+    {
+        !self.hands[seat].is_empty() || self.stock.size() > 0
+    }
+
+    fn next_in_game(&self, seat: usize) -> usize
+    {
+        let n = self.hands.len();
+        (1..=n).map(|step| (seat + step) % n).find(|s| self.in_game(*s)).unwrap_or(seat)
+    }
+
+    pub fn over(&self) -> bool
+    {
+        (0..self.hands.len()).filter(|s| self.in_game(*s)).count() <= 1
+    }
+
+    // The last player left holding cards, if anyone is
+    pub fn fool(&self) -> Option<usize>
+    {
+        (0..self.hands.len()).find(|s| self.in_game(*s))
+    }
+
+    fn start_bout(&mut self, attacker: usize)
+    {
+        self.attacker = attacker;
+        self.defender = self.next_in_game(attacker);
+        self.table.clear();
+        self.limit = MAX_ATTACKS.min(self.hands[self.defender].len());
+    }
+
+    fn undefended(&self) -> usize
+    {
+        self.table.iter().filter(|(_, defence)| defence.is_none()).count()
+    }
+
+    // The attacker leads anything, then anyone but the defender can throw in cards
+    // of a rank already on the table, as long as the defender could still beat them all
+    pub fn can_attack(&self, seat: usize, card: &Card) -> bool
+    {
+        let matches = if self.table.is_empty()
+        {
+            seat == self.attacker
+        }
+        else
+        {
+            self.table.iter().any(|(attack, defence)| attack.rank == card.rank || defence.is_some_and(|d| d.rank == card.rank))
+        };
+
+        seat != self.defender && matches && self.table.len() < self.limit && self.undefended() < self.hands[self.defender].len()
+    }
+
+    pub fn attack(&mut self, seat: usize, index: usize) -> Result<Card, String>
+    {
+        let card = *self.hands[seat].get(index).ok_or_else(|| String::from("Unable to play that card"))?;
+        if !self.can_attack(seat, &card)
+        {
+            return Err(format!("{} can't be played into this attack", card))
+        }
+
+        self.hands[seat].remove(index);
+        self.table.push((card, None));
+        Ok(card)
+    }
+
+    // The defender beats the attacking card at `attack` with the card at `index`
+    pub fn defend(&mut self, attack: usize, index: usize) -> Result<Card, String>
+    {
+        let card = *self.hands[self.defender].get(index).ok_or_else(|| String::from("Unable to play that card"))?;
+        match self.table.get(attack) {
+            Some((attacking, None)) if beats(attacking, &card, self.trump.suit) => (),
+            Some((attacking, None)) => return Err(format!("{} doesn't beat {}", card, attacking)),
+            _ => return Err(String::from("That card has already been beaten"))
+        }
+
+        self.hands[self.defender].remove(index);
+        self.table[attack].1 = Some(card);
+        Ok(card)
+    }
+
+    // The attackers in the order they throw in, starting with whoever led
+    fn attackers(&self) -> Vec<usize>
+    {
+        let n = self.hands.len();
+        (0..n).map(|step| (self.attacker + step) % n).filter(|s| *s != self.defender && !self.hands[*s].is_empty()).collect()
+    }
+
+    // A defender who beat everything goes on to attack. One who took the cards loses
+    // their turn to attack. Either way everyone draws back up to six, the attackers
+    // first and the defender last.
+    pub fn end_bout(&mut self, taken: bool)
+    {
+        let table: Vec<Card> = self.table.drain(..).flat_map(|(attack, defence)| std::iter::once(attack).chain(defence)).collect();
+        if taken
+        {
+            self.hands[self.defender].extend(table);
+        }
+
+        let n = self.hands.len();
+        let mut order: Vec<usize> = (0..n).map(|step| (self.attacker + step) % n).filter(|s| *s != self.defender).collect();
+        order.push(self.defender);
+        for seat in order
+        {
+            while self.hands[seat].len() < HAND_SIZE && self.stock.size() > 0
+            {
+                let card = self.stock.draw();
+                self.hands[seat].push(card);
+            }
+        }
+
+        if self.over() { return }
+        let next = if !taken && self.in_game(self.defender) { self.defender } else { self.next_in_game(self.defender) };
+        self.start_bout(next);
+    }
+
+    fn trump_key(&self, card: &Card) -> (bool, u8)
+    {
+        (card.suit == self.trump.suit, card.rank)
+    }
+
+    // Leads its lowest card, saving trumps, and throws in whatever matches without
+    // spending trumps while there is still a stock to draw from
+    fn bot_attack(&self, seat: usize) -> Option<usize>
+    {
+        let hand = &self.hands[seat];
+        let index = (0..hand.len()).filter(|i| self.can_attack(seat, &hand[*i])).min_by_key(|i| self.trump_key(&hand[*i]))?;
+
+        if !self.table.is_empty() && hand[index].suit == self.trump.suit && self.stock.size() > 0
+        {
+            return None
+        }
+        Some(index)
+    }
+
+    // Beats an attack as cheaply as it can, or gives up and takes the cards
+    fn bot_defend(&self, attack: usize) -> Option<usize>
+    {
+        let hand = &self.hands[self.defender];
+        let attacking = self.table[attack].0;
+
+        (0..hand.len()).filter(|i| beats(&attacking, &hand[*i], self.trump.suit)).min_by_key(|i| self.trump_key(&hand[*i]))
+    }
+
+    fn select(&self, prompt: &str, options: &[String]) -> Option<usize>
+    {
+        Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap()
+    }
+
+    // The human picks a card to attack with, or to stop throwing in
+    fn human_attack(&mut self) -> Option<usize>
+    {
+        let playable: Vec<usize> = (0..self.hands[HUMAN].len()).filter(|i| self.can_attack(HUMAN, &self.hands[HUMAN][*i])).collect();
+        if playable.is_empty() { return None }
+
+        println!("{}", self);
+        let lead = self.table.is_empty();
+        let mut options: Vec<String> = playable.iter().map(|i| self.hands[HUMAN][*i].to_string()).collect();
+        options.push(if lead { "Leave" } else { "Done" }.to_string());
+
+        match self.select(if lead { "Lead a card" } else { "Throw in a card" }, &options) {
+            Some(index) if index < playable.len() => Some(playable[index]),
+            _ if lead => {
+                self.leave = true;
+                None
+            },
+            _ => None
+        }
+    }
+
+    fn human_defend(&mut self, attack: usize) -> Option<usize>
+    {
+        println!("{}", self);
+        let attacking = self.table[attack].0;
+        let playable: Vec<usize> = (0..self.hands[HUMAN].len()).filter(|i| beats(&attacking, &self.hands[HUMAN][*i], self.trump.suit)).collect();
+        let mut options: Vec<String> = playable.iter().map(|i| self.hands[HUMAN][*i].to_string()).collect();
+        options.push("Take".to_string());
+        options.push("Leave".to_string());
+
+        match self.select(&format!("Beat {}", attacking), &options) {
+            Some(index) if index < playable.len() => Some(playable[index]),
+            Some(index) if index > playable.len() => {
+                self.leave = true;
+                None
+            },
+            _ => None
+        }
+    }
+
+    fn choose_attack(&mut self, seat: usize) -> Option<usize>
+    {
+        if seat == HUMAN { self.human_attack() } else { self.bot_attack(seat) }
+    }
+
+    // Goes round the attackers once, each throwing in all they want to
+    fn throw_ins(&mut self) -> bool
+    {
+        let mut added = false;
+
+        for seat in self.attackers()
+        {
+            while let Some(index) = self.choose_attack(seat)
+            {
+                let card = self.attack(seat, index).unwrap();
+                println!("{} throws in {}", self.names[seat], card);
+                added = true;
+            }
+            if self.leave { break }
+        }
+        added
+    }
+
+    // One bout: the lead, then defending and throwing in until the defender has beaten
+    // everything or taken it all, and nobody adds any more
+    fn bout(&mut self)
+    {
+        let attacker = self.attacker;
+        let lead = match self.choose_attack(attacker) { Some(index) => index, None => return };
+        let card = self.attack(attacker, lead).unwrap();
+        println!("{} attacks {} with {}", self.names[attacker], self.names[self.defender], card);
+
+        let mut taken = false;
+        loop
+        {
+            while let (false, Some(attack)) = (taken, self.table.iter().position(|(_, defence)| defence.is_none()))
+            {
+                let choice = if self.defender == HUMAN { self.human_defend(attack) } else { self.bot_defend(attack) };
+                if self.leave { return }
+                match choice {
+                    Some(index) => {
+                        let card = self.defend(attack, index).unwrap();
+                        println!("{} beats {} with {}", self.names[self.defender], self.table[attack].0, card);
+                    },
+                    None => {
+                        println!("{} takes", self.names[self.defender]);
+                        taken = true;
+                    }
+                }
+            }
+            if !self.throw_ins() || self.leave { break }
+        }
+        if self.leave { return }
+
+        if !taken { println!("{} beat off the attack", self.names[self.defender]) }
+        self.end_bout(taken);
+    }
+}
+
+impl fmt::Display for Durak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![format!("+ --- Trumps: {} ({}), stock: {} --- +", self.trump.suit, self.trump, self.stock.size())];
+        for seat in (0..self.hands.len()).filter(|s| *s != HUMAN)
+        {
+            let role = if seat == self.defender { ", defending" } else if seat == self.attacker { ", attacking" } else { "" };
+            lines.push(format!("{:<6} {} cards{}", self.names[seat], self.hands[seat].len(), role));
+        }
+        let table: Vec<String> = self.table.iter().map(|(attack, defence)| match defence {
+            Some(defence) => format!("{}/{}", attack, defence),
+            None => attack.to_string()
+        }).collect();
+        lines.push(format!("Table: {}", table.join("  ")));
+
+        let mut hand = self.hands[HUMAN].clone();
+        hand.sort_by_key(|c| self.trump_key(c));
+        let hand: Vec<String> = hand.iter().map(|c| c.to_string()).collect();
+        lines.push(format!("Your hand: {}", hand.join(" ")));
+        lines.push("+ ------------------- +".to_string());
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Durak
+{
+    fn win(&self) -> bool
+    {
+        self.over() && self.fool() != Some(HUMAN)
+    }
+
+    fn handle_input(&mut self)
+    {
+        self.bout();
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    fn deal(hands: &[&str], stock: &str, trump: &str) -> Durak
+    {
+        let mut game = Durak::new(hands.len()).unwrap();
+        game.hands = hands.iter().map(|h| parse_cards(h).unwrap()).collect();
+        game.stock = Stack::from_cards(parse_cards(stock).unwrap());
+        game.trump = trump.parse().unwrap();
+        game.start_bout(0);
+        game
+    }
+
+    #[test]
+    fn beating()
+    {
+        let card = |s: &str| s.parse::<Card>().unwrap();
+
+        assert!(beats(&card("9s"), &card("Js"), Suit::Heart));
+        assert!(!beats(&card("9s"), &card("7s"), Suit::Heart));
+        assert!(beats(&card("As"), &card("6h"), Suit::Heart));
+        assert!(!beats(&card("6h"), &card("As"), Suit::Heart));
+        assert!(!beats(&card("9s"), &card("Jd"), Suit::Heart));
+    }
+
+    #[test]
+    fn dealing()
+    {
+        let game = Durak::new(3).unwrap();
+        assert_eq!(game.stock.size(), 18);
+        assert_eq!(Some(&game.trump), game.stock.bottom_card());
+        assert!(game.hands.iter().flatten().all(|c| c.rank >= 6));
+
+        // The attacker holds the lowest trump, if anyone holds one
+        let lowest = game.hands.iter().flatten().filter(|c| c.suit == game.trump.suit).map(|c| c.rank).min();
+        if let Some(rank) = lowest
+        {
+            assert!(game.hands[game.attacker].iter().any(|c| c.suit == game.trump.suit && c.rank == rank));
+        }
+
+        let full = Durak::new(6).unwrap();
+        assert_eq!(full.stock.size(), 0);
+        assert_eq!(full.trump, *full.hands[5].last().unwrap());
+    }
+
+    #[test]
+    fn attacking()
+    {
+        let mut game = deal(&["9s9dKc", "Ts6c", "9h7h"], "", "6h");
+
+        assert!(game.attack(1, 0).is_err());
+        assert!(game.attack(2, 0).is_err());
+        game.attack(0, 0).unwrap();
+        assert!(game.defend(0, 1).is_err());
+        game.defend(0, 0).unwrap();
+
+        // A nine matches, but then the defender has nothing left to beat another with
+        game.attack(0, 0).unwrap();
+        assert!(!game.can_attack(2, &"9h".parse().unwrap()));
+        assert_eq!(game.bot_attack(2), None);
+    }
+
+    #[test]
+    fn ending_bouts()
+    {
+        // Beaten off, so the defender draws last and attacks next
+        let mut game = deal(&["9s", "Ts"], "6c7c8c9cTcJcQc", "6h");
+        game.attack(0, 0).unwrap();
+        game.defend(0, 0).unwrap();
+        game.end_bout(false);
+        assert_eq!(game.hands[0].len(), 6);
+        assert_eq!(game.hands[1], parse_cards("Qc").unwrap());
+        assert_eq!((game.attacker, game.defender), (1, 0));
+
+        // Taken, so the defender picks up and the next player attacks
+        let mut game = deal(&["9s", "6c", "7h"], "", "6h");
+        game.attack(0, 0).unwrap();
+        game.end_bout(true);
+        assert_eq!(game.hands[1], parse_cards("6c9s").unwrap());
+        assert_eq!((game.attacker, game.defender), (2, 1));
+        assert!(!game.in_game(0));
+    }
+
+    #[test]
+    fn bots_play_a_game()
+    {
+        let mut game = Durak::new(4).unwrap();
+
+        for _ in 0..500
+        {
+            if game.over() { break }
+
+            let attacker = game.attacker;
+            let lead = game.bot_attack(attacker).unwrap();
+            game.attack(attacker, lead).unwrap();
+
+            let mut taken = false;
+            loop
+            {
+                while let (false, Some(attack)) = (taken, game.table.iter().position(|(_, d)| d.is_none()))
+                {
+                    match game.bot_defend(attack) {
+                        Some(index) => { game.defend(attack, index).unwrap(); },
+                        None => taken = true
+                    }
+                }
+                let mut added = false;
+                for seat in game.attackers()
+                {
+                    while let Some(index) = game.bot_attack(seat)
+                    {
+                        game.attack(seat, index).unwrap();
+                        added = true;
+                    }
+                }
+                if !added { break }
+            }
+            game.end_bout(taken);
+        }
+        assert!(game.over());
+    }
+}
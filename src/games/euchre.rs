@@ -0,0 +1,585 @@
+use std::fmt;
+use crate::deck::{Stack, Card, Suit};
+use crate::trick::{TrickRules, StandardRules, Trick, TrickRound, sort_hand};
+use crate::game::{Game, HUMAN};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const PLAYERS: usize = 4;
+const HAND_SIZE: usize = 5;
+const GAME_OVER: u32 = 10;
+const SUITS: [Suit; 4] = [Suit::Spade, Suit::Heart, Suit::Club, Suit::Diamond];
+// Hands are sorted without a trump until one is called
+const NO_TRUMP: StandardRules = StandardRules { trump: None };
+// How strong a hand a bot wants before calling trump, and before going alone
+const CALL: f64 = 6.0;
+const ALONE: f64 = 10.0;
+
+// The other suit of the same colour, whose jack becomes the left bower
+fn same_colour(suit: Suit) -> Suit
+{
+    match suit {
+        Suit::Spade => Suit::Club,
+        Suit::Club => Suit::Spade,
+        Suit::Heart => Suit::Diamond,
+        Suit::Diamond => Suit::Heart,
+        Suit::Joker => Suit::Joker
+    }
+}
+
+// The jack of trump is the highest card, then the jack of the same colour, which
+// counts as a trump rather than a card of its own suit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EuchreRules
+{
+    pub trump: Suit,
+}
+
+impl TrickRules for EuchreRules {
+    fn trump(&self) -> Option<Suit> {
+        Some(self.trump)
+    }
+
+    fn effective_suit(&self, card: &Card) -> Suit {
+        if card.rank == 11 && card.suit == same_colour(self.trump) { self.trump } else { card.suit }
+    }
+
+    fn card_order(&self, card: &Card) -> u8 {
+        match (card.rank, card.suit) {
+            (11, suit) if suit == self.trump => 16,
+            (11, suit) if suit == same_colour(self.trump) => 15,
+            (rank, _) => rank
+        }
+    }
+}
+
+// Whether the makers won the round and the points it's worth: one for three or four
+// tricks, two for all five or four when alone, and two to the defenders for a euchre
+pub fn round_points(tricks: usize, alone: bool) -> (bool, u32)
+{
+    match tricks {
+        5 if alone => (true, 4),
+        5 => (true, 2),
+        3 | 4 => (true, 1),
+        _ => (false, 2)
+    }
+}
+
+// Counts bowers and high trumps most, with something for each off-suit ace
+fn hand_strength(hand: &[Card], trump: Suit) -> f64
+{
+    let rules = EuchreRules { trump };
+
+    hand.iter().map(|card| {
+        if rules.effective_suit(card) == trump
+        {
+            match rules.card_order(card) { 16 => 3.0, 15 => 2.5, 14 => 2.0, 13 => 1.5, _ => 1.0 }
+        }
+        else if card.rank == 14 { 1.0 } else { 0.0 }
+    }).sum()
+}
+
+// Orders cheapest first, any off-suit card before a trump
+fn cheapest(card: &Card, rules: &EuchreRules) -> (bool, u8)
+{
+    (rules.effective_suit(card) == rules.trump, rules.card_order(card))
+}
+
+fn bot_discard(hand: &[Card], rules: &EuchreRules) -> usize
+{
+    (0..hand.len()).min_by_key(|i| cheapest(&hand[*i], rules)).unwrap()
+}
+
+// Whether a bot calls `trump`, and if so whether it goes alone
+fn bot_call(hand: &[Card], trump: Suit) -> Option<bool>
+{
+    let strength = hand_strength(hand, trump);
+
+    if strength >= CALL { Some(strength >= ALONE) } else { None }
+}
+
+// Leads the right bower or an off-suit ace, lets a partner's winning card stand and
+// otherwise takes the trick as cheaply as possible
+fn bot_play(hand: &[Card], legal: &[usize], trick: &Trick, seat: usize, rules: &EuchreRules) -> usize
+{
+    let lowest = *legal.iter().min_by_key(|i| cheapest(&hand[**i], rules)).unwrap();
+
+    if trick.plays.is_empty()
+    {
+        let right = legal.iter().find(|i| rules.card_order(&hand[**i]) == 16);
+        let ace = legal.iter().find(|i| hand[**i].rank == 14 && rules.effective_suit(&hand[**i]) != rules.trump);
+        return right.or(ace).copied().unwrap_or(lowest)
+    }
+    if trick.winner(rules) == Some((seat + 2) % PLAYERS)
+    {
+        return lowest
+    }
+
+    legal.iter()
+        .filter(|i| trick.would_win(seat, hand[**i], rules))
+        .min_by_key(|i| cheapest(&hand[**i], rules))
+        .copied()
+        .unwrap_or(lowest)
+}
+
+pub struct Euchre
+{
+    names: Vec<String>,
+    // Scores by team, with the human's team first
+    scores: Vec<u32>,
+    dealer: usize,
+    up_card: Card,
+    trump: Option<Suit>,
+    maker: usize,
+    alone: bool,
+    round: TrickRound,
+    leave: bool,
+}
+
+impl Euchre
+{
+    pub fn new() -> Euchre
+    {
+        let names = ["You", "Bot 1", "Partner", "Bot 3"].iter().map(|n| n.to_string()).collect();
+        let mut game = Euchre
+        {
+            names,
+            scores: vec![0; 2],
+            dealer: PLAYERS - 1,
+            up_card: Card::new_visible(Suit::Spade, 9).unwrap(),
+            trump: None,
+            maker: 0,
+            alone: false,
+            round: TrickRound::new(vec![vec![]; PLAYERS], 0),
+            leave: false
+        };
+
+        game.deal();
+        game
+    }
+
+    pub fn play()
+    {
+        let mut game = Euchre::new();
+
+        while !game.leave && !game.game_over()
+        {
+            game.handle_input();
+        }
+
+        println!("{}", game.score_lines().join("\n"));
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    // Five cards each with the next turned up for the first round of bidding
+    pub fn deal(&mut self)
+    {
+        let mut deck = Stack::new_partial_deck(9..=14);
+        deck.shuffle();
+
+        let hands = (0..PLAYERS).map(|_| {
+            let mut hand = deck.deal(HAND_SIZE);
+            sort_hand(&mut hand, &NO_TRUMP);
+            hand
+        }).collect();
+
+        self.round = TrickRound::new(hands, (self.dealer + 1) % PLAYERS);
+        self.up_card = deck.draw();
+        self.trump = None;
+        self.alone = false;
+    }
+
+    fn rules(&self) -> EuchreRules
+    {
+        EuchreRules { trump: self.trump.unwrap_or(self.up_card.suit) }
+    }
+
+    // Makes `seat` the maker with `trump`. Going alone sits their partner out.
+    pub fn call(&mut self, seat: usize, trump: Suit, alone: bool) -> Result<(), String>
+    {
+        if self.trump.is_some()
+        {
+            return Err(String::from("Trump has already been called"))
+        }
+
+        self.trump = Some(trump);
+        self.maker = seat;
+        self.alone = alone;
+        if alone
+        {
+            self.round.sit_out((seat + 2) % PLAYERS);
+        }
+
+        let rules = self.rules();
+        for hand in self.round.hands.iter_mut()
+        {
+            sort_hand(hand, &rules);
+        }
+
+        println!("{} calls {}{}", self.names[seat], trump, if alone { " and goes alone" } else { "" });
+        Ok(())
+    }
+
+    // The dealer takes the up card once it's ordered up, then discards a card from
+    // the six they hold. A dealer sitting out leaves it where it is.
+    pub fn pick_up(&mut self, discard: usize) -> Result<(), String>
+    {
+        if self.round.sitting_out[self.dealer] { return Ok(()) }
+        if discard > HAND_SIZE
+        {
+            return Err(String::from("Unable to discard that card"))
+        }
+
+        let rules = self.rules();
+        let hand = &mut self.round.hands[self.dealer];
+        hand.push(self.up_card);
+        hand.remove(discard);
+        sort_hand(hand, &rules);
+
+        Ok(())
+    }
+
+    pub fn legal(&self) -> Vec<usize>
+    {
+        self.round.legal(&self.rules())
+    }
+
+    pub fn play_card(&mut self, index: usize) -> Result<Option<usize>, String>
+    {
+        let seat = self.round.to_play();
+        let card = *self.round.hands[seat].get(index).ok_or_else(|| String::from("Unable to play that card"))?;
+        let winner = self.round.play(index, &self.rules())?;
+
+        println!("{}: {}", self.names[seat], card);
+        if let Some(winner) = winner
+        {
+            println!("{} took the trick", self.names[winner]);
+        }
+
+        Ok(winner)
+    }
+
+    // Scores the round and passes the deal, returning the team that scored and its points
+    pub fn score_round(&mut self) -> (usize, u32)
+    {
+        let makers = self.maker % 2;
+        let tricks = self.round.tricks_won[makers] + self.round.tricks_won[makers + 2];
+        let (made, points) = round_points(tricks, self.alone);
+        let team = if made { makers } else { 1 - makers };
+
+        self.scores[team] += points;
+        self.dealer = (self.dealer + 1) % PLAYERS;
+
+        (team, points)
+    }
+
+    pub fn game_over(&self) -> bool
+    {
+        self.scores.iter().any(|s| *s >= GAME_OVER)
+    }
+
+    fn select(&self, prompt: &str, options: &[String]) -> Option<usize>
+    {
+        Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap()
+    }
+
+    // The first round, where the up card's suit can be ordered up
+    fn get_order_up(&self) -> Option<bool>
+    {
+        println!("{}", self);
+        let suit = self.up_card.suit;
+        let options = vec!["Pass".to_string(), format!("Order up {}", suit), format!("Order up {} and go alone", suit)];
+
+        match self.select("Order up the turned card?", &options) {
+            Some(1) => Some(false),
+            Some(2) => Some(true),
+            _ => None
+        }
+    }
+
+    // The second round, where any other suit can be named
+    fn get_call(&self) -> Option<(Suit, bool)>
+    {
+        println!("{}", self);
+        let suits: Vec<Suit> = SUITS.iter().copied().filter(|s| *s != self.up_card.suit).collect();
+        let mut calls = vec![];
+        for alone in [false, true].iter()
+        {
+            calls.extend(suits.iter().map(|s| (*s, *alone)));
+        }
+        let mut options = vec!["Pass".to_string()];
+        options.extend(calls.iter().map(|(s, alone)| format!("Call {}{}", s, if *alone { " and go alone" } else { "" })));
+
+        match self.select("Name trump?", &options) {
+            Some(index) if index > 0 => Some(calls[index - 1]),
+            _ => None
+        }
+    }
+
+    fn get_discard(&self, hand: &[Card]) -> usize
+    {
+        let cards: Vec<String> = hand.iter().map(|c| c.to_string()).collect();
+
+        self.select("Discard a card", &cards).unwrap_or(HAND_SIZE)
+    }
+
+    fn dealer_pick_up(&mut self)
+    {
+        if self.round.sitting_out[self.dealer] { return }
+
+        let mut hand = self.round.hands[self.dealer].clone();
+        hand.push(self.up_card);
+        let discard = if self.dealer == HUMAN { self.get_discard(&hand) } else { bot_discard(&hand, &self.rules()) };
+        self.pick_up(discard).unwrap_or_else(|err| println!("{}", err));
+    }
+
+    // Two rounds of bidding from the dealer's left. Returns false if everyone passed.
+    fn bidding(&mut self) -> bool
+    {
+        let seats: Vec<usize> = (1..=PLAYERS).map(|i| (self.dealer + i) % PLAYERS).collect();
+        println!("Turned up: {}", self.up_card);
+
+        for seat in seats.iter().copied()
+        {
+            let order = if seat == HUMAN { self.get_order_up() } else {
+                // The dealer judges the hand they'd have after picking up
+                let mut hand = self.round.hands[seat].clone();
+                if seat == self.dealer
+                {
+                    hand.push(self.up_card);
+                    hand.remove(bot_discard(&hand, &EuchreRules { trump: self.up_card.suit }));
+                }
+                bot_call(&hand, self.up_card.suit)
+            };
+            if let Some(alone) = order
+            {
+                self.call(seat, self.up_card.suit, alone).unwrap();
+                self.dealer_pick_up();
+                return true
+            }
+            println!("{} passes", self.names[seat]);
+        }
+
+        for seat in seats
+        {
+            let call = if seat == HUMAN { self.get_call() } else {
+                let hand = &self.round.hands[seat];
+                let suit = SUITS.iter().copied()
+                    .filter(|s| *s != self.up_card.suit)
+                    .max_by(|a, b| hand_strength(hand, *a).partial_cmp(&hand_strength(hand, *b)).unwrap())
+                    .unwrap();
+                bot_call(hand, suit).map(|alone| (suit, alone))
+            };
+            if let Some((suit, alone)) = call
+            {
+                self.call(seat, suit, alone).unwrap();
+                return true
+            }
+            println!("{} passes", self.names[seat]);
+        }
+
+        false
+    }
+
+    fn play_bots(&mut self)
+    {
+        let rules = self.rules();
+
+        while !self.round.finished() && self.round.to_play() != HUMAN
+        {
+            let seat = self.round.to_play();
+            let index = bot_play(&self.round.hands[seat], &self.legal(), &self.round.trick, seat, &rules);
+            self.play_card(index).unwrap();
+        }
+    }
+
+    fn get_play(&mut self)
+    {
+        let legal = self.legal();
+        let mut options: Vec<String> = legal.iter().map(|i| self.round.hands[HUMAN][*i].to_string()).collect();
+        options.push("Leave".to_string());
+
+        match self.select("Play a card", &options) {
+            Some(index) if index == legal.len() => self.leave = true,
+            Some(index) => { self.play_card(legal[index]).unwrap_or_else(|err| { println!("{}", err); None }); },
+            None => println!("User did not select anything")
+        }
+    }
+
+    fn score_lines(&self) -> Vec<String>
+    {
+        vec![
+            format!("You and Partner {:>2}", self.scores[0]),
+            format!("Bots 1 and 3    {:>2}", self.scores[1])
+        ]
+    }
+}
+
+impl fmt::Display for Euchre {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let trick: Vec<String> = self.round.trick.plays.iter().map(|(seat, card)| format!("{}: {}", self.names[*seat], card)).collect();
+        let hand: Vec<String> = self.round.hands[HUMAN].iter().map(|c| c.to_string()).collect();
+
+        let mut lines = vec!["+ --- Scores --- +".to_string()];
+        lines.extend(self.score_lines());
+        lines.push("+ ------------------- +".to_string());
+        lines.push(format!("Dealer: {}", self.names[self.dealer]));
+        match self.trump {
+            Some(trump) => {
+                lines.push(format!("Trump: {} called by {}{}", trump, self.names[self.maker], if self.alone { " alone" } else { "" }));
+                for seat in 0..PLAYERS
+                {
+                    let took = if self.round.sitting_out[seat] { "sitting out".to_string() } else { format!("took {}", self.round.tricks_won[seat]) };
+                    lines.push(format!("{:<8} {}", self.names[seat], took));
+                }
+                lines.push(format!("Trick: {}", trick.join(" | ")));
+            },
+            None => lines.push(format!("Turned up: {}", self.up_card))
+        }
+        lines.push(format!("Your hand: {}", hand.join(" ")));
+        lines.push("+ ------------------- +".to_string());
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Euchre
+{
+    fn win(&self) -> bool
+    {
+        self.scores[0] >= GAME_OVER
+    }
+
+    fn handle_input(&mut self)
+    {
+        if self.trump.is_none()
+        {
+            if !self.bidding()
+            {
+                println!("Everyone passed, the deal moves on");
+                self.dealer = (self.dealer + 1) % PLAYERS;
+                self.deal();
+            }
+            return
+        }
+
+        self.play_bots();
+        if !self.round.finished()
+        {
+            println!("{}", self);
+            self.get_play();
+            self.play_bots();
+        }
+
+        if self.round.finished()
+        {
+            let (team, points) = self.score_round();
+            let scorers = if team == 0 { "You and Partner" } else { "The bots" };
+            println!("{} scored {}", scorers, points);
+            if !self.game_over()
+            {
+                self.deal();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+    use crate::trick::legal_plays;
+
+    const SPADES: EuchreRules = EuchreRules { trump: Suit::Spade };
+
+    fn trick(leader: usize, cards: &str) -> Trick
+    {
+        let plays = parse_cards(cards).unwrap().into_iter().enumerate().map(|(i, c)| ((leader + i) % PLAYERS, c)).collect();
+
+        Trick { leader, plays }
+    }
+
+    #[test]
+    fn bowers()
+    {
+        assert_eq!(trick(0, "AsJcKsJs").winner(&SPADES), Some(3));
+        assert_eq!(trick(0, "AsJcKs").winner(&SPADES), Some(1));
+        // The left bower isn't a club, so it trumps a club lead
+        assert_eq!(trick(0, "AcJc").winner(&SPADES), Some(1));
+
+        // Holding the left bower means following a spade lead with it
+        let hand = parse_cards("JcAcTh").unwrap();
+        assert_eq!(legal_plays(&hand, &trick(0, "9s"), &SPADES), vec![0]);
+        assert_eq!(legal_plays(&hand, &trick(0, "9c"), &SPADES), vec![1]);
+    }
+
+    #[test]
+    fn scoring()
+    {
+        assert_eq!(round_points(3, false), (true, 1));
+        assert_eq!(round_points(5, false), (true, 2));
+        assert_eq!(round_points(5, true), (true, 4));
+        assert_eq!(round_points(4, true), (true, 1));
+        assert_eq!(round_points(2, true), (false, 2));
+    }
+
+    #[test]
+    fn bot_calls()
+    {
+        assert_eq!(bot_call(&parse_cards("JsJcAsKhAd").unwrap(), Suit::Spade), Some(false));
+        assert_eq!(bot_call(&parse_cards("JsJcAsKsAd").unwrap(), Suit::Spade), Some(true));
+        assert_eq!(bot_call(&parse_cards("9sTcKh9hQd").unwrap(), Suit::Spade), None);
+        assert_eq!(bot_discard(&parse_cards("JsJcAsKh9dTd").unwrap(), &SPADES), 4);
+    }
+
+    #[test]
+    fn picking_up()
+    {
+        let mut game = Euchre::new();
+        game.up_card = "Jh".parse().unwrap();
+        game.call(1, Suit::Heart, false).unwrap();
+        game.pick_up(0).unwrap();
+
+        assert_eq!(game.round.hands[game.dealer].len(), HAND_SIZE);
+        assert!(game.round.hands[game.dealer].contains(&game.up_card));
+        assert!(game.call(2, Suit::Spade, false).is_err());
+    }
+
+    #[test]
+    fn going_alone()
+    {
+        let mut game = Euchre::new();
+        // The seat left of the dealer would lead, but sits out
+        game.call(2, Suit::Club, true).unwrap();
+        game.pick_up(0).unwrap();
+        assert!(game.round.sitting_out[HUMAN]);
+        assert_eq!(game.round.to_play(), 1);
+
+        while !game.round.finished()
+        {
+            let seat = game.round.to_play();
+            let index = bot_play(&game.round.hands[seat], &game.legal(), &game.round.trick, seat, &game.rules());
+            game.play_card(index).unwrap();
+        }
+
+        assert_eq!(game.round.tricks_won[HUMAN], 0);
+        assert_eq!(game.round.tricks_played(), HAND_SIZE);
+        let (team, points) = game.score_round();
+        assert!(points > 0 && team < 2);
+    }
+}
@@ -0,0 +1,328 @@
+use std::fmt;
+use std::cmp::Reverse;
+use crate::deck::{Stack, Card};
+use crate::betting::Betting;
+use crate::poker::{evaluate, Category};
+use crate::poker_table::{
+    STARTING_CHIPS, next_active, post_blinds, last_standing,
+    made_strength, seat_lines, betting_round, showdown
+};
+use crate::game::{Game, HUMAN, player_names, read_players};
+use dialoguer::{
+    Select,
+    MultiSelect,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+// Six hands of five leave enough in the stock and the discards for everyone to draw
+const MAX_PLAYERS: usize = 6;
+const HAND_SIZE: usize = 5;
+const SMALL_BLIND: u32 = 5;
+const BIG_BLIND: u32 = 10;
+
+// A bot keeps anything that already makes a hand and four to a flush, otherwise
+// it holds on to its two highest cards
+fn bot_discards(hand: &[Card]) -> Vec<usize>
+{
+    let rank = evaluate(hand).unwrap();
+    let count = |rank: u8| hand.iter().filter(|c| c.rank == rank).count();
+
+    match rank.category() {
+        Category::HighCard => {
+            let flush_draw = hand.iter().map(|c| c.suit).find(|s| hand.iter().filter(|c| c.suit == *s).count() == 4);
+            if let Some(suit) = flush_draw
+            {
+                return (0..hand.len()).filter(|i| hand[*i].suit != suit).collect()
+            }
+
+            let mut order: Vec<usize> = (0..hand.len()).collect();
+            order.sort_by_key(|i| Reverse(hand[*i].rank));
+            let mut discards = order.split_off(2);
+            discards.sort_unstable();
+            discards
+        },
+        Category::Pair | Category::TwoPair | Category::ThreeOfAKind => (0..hand.len()).filter(|i| count(hand[*i].rank) == 1).collect(),
+        _ => vec![]
+    }
+}
+
+fn strength(hand: &[Card]) -> f64
+{
+    made_strength(evaluate(hand).unwrap())
+}
+
+// Five card draw with blinds, a betting round either side of the draw and no limit
+pub struct FiveCardDraw
+{
+    names: Vec<String>,
+    deck: Stack,
+    // Discards, which are shuffled into a new stock if it runs out
+    muck: Vec<Card>,
+    hands: Vec<Vec<Card>>,
+    button: usize,
+    betting: Betting,
+    leave: bool,
+}
+
+impl FiveCardDraw
+{
+    pub fn new(players: usize) -> Result<FiveCardDraw, String>
+    {
+        if !(2..=MAX_PLAYERS).contains(&players)
+        {
+            return Err(format!("Five card draw is played by 2 to {} players, {} provided", MAX_PLAYERS, players))
+        }
+
+        Ok(FiveCardDraw
+        {
+            names: player_names(players),
+            deck: Stack::new_deck(false),
+            muck: vec![],
+            hands: vec![vec![]; players],
+            button: players - 1,
+            betting: Betting::new(&vec![STARTING_CHIPS; players], BIG_BLIND),
+            leave: false
+        })
+    }
+
+    pub fn play()
+    {
+        let mut game = match FiveCardDraw::new(read_players(MAX_PLAYERS)) {
+            Ok(game) => game,
+            Err(err) => return println!("{}", err)
+        };
+
+        while !game.leave && game.betting.seats[HUMAN].chips > 0 && !game.win()
+        {
+            game.handle_input();
+        }
+
+        println!("You leave the table with {} chips", game.betting.seats[HUMAN].chips);
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    pub fn start_hand(&mut self)
+    {
+        let chips: Vec<u32> = self.betting.seats.iter().map(|s| s.chips).collect();
+        self.betting = Betting::new(&chips, BIG_BLIND);
+        self.button = next_active(&self.betting, self.button);
+        self.deck = Stack::new_deck(false);
+        self.deck.shuffle();
+        self.muck.clear();
+
+        for seat in 0..self.names.len()
+        {
+            self.hands[seat] = if self.betting.seats[seat].folded { vec![] } else {
+                self.deck.deal(HAND_SIZE).into_iter().map(Card::hide).collect()
+            };
+        }
+
+        post_blinds(&mut self.betting, self.button, SMALL_BLIND, BIG_BLIND);
+    }
+
+    // Swaps the cards at `discards` in a seat's hand for new ones from the stock
+    pub fn draw(&mut self, seat: usize, discards: &[usize]) -> Result<(), String>
+    {
+        let mut discards = discards.to_vec();
+        discards.sort_unstable();
+        discards.dedup();
+        if discards.iter().any(|i| *i >= self.hands[seat].len())
+        {
+            return Err(String::from("Unable to discard those cards"))
+        }
+
+        if self.deck.size() < discards.len()
+        {
+            let mut cards = self.deck.deal(self.deck.size());
+            cards.append(&mut self.muck);
+            self.deck = Stack::from_cards(cards);
+            self.deck.shuffle();
+        }
+
+        for i in discards.iter().rev()
+        {
+            let card = self.hands[seat].remove(*i);
+            self.muck.push(card);
+        }
+        let cards = self.deck.deal(discards.len());
+        self.hands[seat].extend(cards.into_iter().map(Card::hide));
+
+        Ok(())
+    }
+
+    fn get_discards(&self) -> Vec<usize>
+    {
+        let cards: Vec<String> = self.hands[HUMAN].iter().map(|c| c.see().to_string()).collect();
+
+        MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Cards to discard (space to toggle)")
+            .items(&cards)
+            .interact_on(&Term::stderr()).unwrap()
+    }
+
+    fn draw_round(&mut self)
+    {
+        let n = self.names.len();
+        let order: Vec<usize> = (1..=n).map(|i| (self.button + i) % n).collect();
+
+        for seat in order
+        {
+            if self.betting.seats[seat].folded { continue }
+
+            let discards = if seat == HUMAN { self.get_discards() } else { bot_discards(&self.hands[seat]) };
+            match self.draw(seat, &discards) {
+                Ok(()) if discards.is_empty() => println!("{}: stands pat", self.names[seat]),
+                Ok(()) => println!("{}: draws {}", self.names[seat], discards.len()),
+                Err(err) => println!("{}", err)
+            }
+        }
+    }
+
+    fn betting_round(&mut self)
+    {
+        let hands = &self.hands;
+
+        betting_round(&mut self.betting, &self.names, |seat| strength(&hands[seat]));
+    }
+
+    pub fn play_hand(&mut self)
+    {
+        self.start_hand();
+        println!("{}", self);
+        self.betting_round();
+
+        if !self.betting.hand_over()
+        {
+            println!("+ --- Draw --- +");
+            self.draw_round();
+            self.betting.next_street(next_active(&self.betting, self.button));
+            println!("{}", self);
+            self.betting_round();
+        }
+
+        showdown(&mut self.betting, &self.names, &mut self.hands, |hand| evaluate(hand).ok());
+    }
+}
+
+impl fmt::Display for FiveCardDraw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![format!("+ --- Pot: {} --- +", self.betting.pot())];
+
+        lines.extend(seat_lines(&self.betting, &self.names, &self.hands, self.button));
+        lines.push("+ ------------------- +".to_string());
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for FiveCardDraw
+{
+    // The human wins by taking every chip on the table
+    fn win(&self) -> bool
+    {
+        last_standing(&self.betting)
+    }
+
+    fn handle_input(&mut self)
+    {
+        let commands = ["Deal", "Display", "Leave"];
+        println!("Select a command: ");
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&commands)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) =>
+            {
+                println!("+ -------------------- + ");
+                match commands[index] {
+                    "Deal" => self.play_hand(),
+                    "Display" => println!("{}", self),
+                    "Leave" => self.leave = true,
+                    _ => {
+                        println!("Invalid selection");
+                        self.handle_input();
+                    }
+                }
+            }
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    fn bot_round(game: &mut FiveCardDraw)
+    {
+        while !game.betting.round_over()
+        {
+            let bet = game.betting.bot_bet(strength(&game.hands[game.betting.to_act]));
+            game.betting.act(bet).unwrap();
+        }
+    }
+
+    #[test]
+    fn bot_draws()
+    {
+        assert_eq!(bot_discards(&parse_cards("9s9d2c5hKs").unwrap()), vec![2, 3, 4]);
+        assert_eq!(bot_discards(&parse_cards("2h7hJhQh9c").unwrap()), vec![4]);
+        assert_eq!(bot_discards(&parse_cards("2c7hJdAh9c").unwrap()), vec![0, 1, 4]);
+        assert!(bot_discards(&parse_cards("5c6h7d8h9c").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn drawing()
+    {
+        let mut game = FiveCardDraw::new(6).unwrap();
+        game.start_hand();
+        assert_eq!(game.deck.size(), 22);
+
+        for seat in 0..6
+        {
+            game.draw(seat, &[0, 1, 2, 3, 4]).unwrap();
+            assert_eq!(game.hands[seat].len(), HAND_SIZE);
+            assert!(game.hands[seat].iter().all(|c| !c.visible));
+        }
+        // Every card is still in play after the stock was rebuilt from the discards
+        assert_eq!(game.deck.size() + game.muck.len(), 22);
+        assert!(game.draw(0, &[5]).is_err());
+    }
+
+    #[test]
+    fn chips_are_conserved()
+    {
+        let mut game = FiveCardDraw::new(4).unwrap();
+
+        for _ in 0..20
+        {
+            game.start_hand();
+            bot_round(&mut game);
+            if !game.betting.hand_over()
+            {
+                for seat in game.betting.in_hand()
+                {
+                    let discards = bot_discards(&game.hands[seat]);
+                    game.draw(seat, &discards).unwrap();
+                }
+                game.betting.next_street(next_active(&game.betting, game.button));
+                bot_round(&mut game);
+            }
+            showdown(&mut game.betting, &game.names, &mut game.hands, |hand| evaluate(hand).ok());
+
+            assert_eq!(game.betting.seats.iter().map(|s| s.chips).sum::<u32>(), STARTING_CHIPS * 4);
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use crate::deck::{Stack, Card};
+use crate::game::Game;
+use text_io::read;
+use std::fmt;
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const DECKS: usize = 2;
+const COLUMNS: usize = 10;
+const COLUMN_DEPTH: usize = 4;
+const FOUNDATIONS: usize = 8;
+const FOUNDATION_SIZE: usize = 13;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Position
+{
+    Waste,
+    Columns,
+    Foundation
+}
+
+#[derive(Copy, Clone, Debug)]
+struct MoveCommand
+{
+    location: Position,
+    i: usize
+}
+
+pub struct FortyThieves
+{
+    deck: Stack,
+    waste: Vec<Card>,
+    columns: Vec<Vec<Card>>,
+    foundations: Vec<Vec<Card>>,
+}
+
+impl FortyThieves
+{
+    fn new() -> FortyThieves
+    {
+        let mut deck = Stack::new_decks(DECKS, false);
+
+        deck.shuffle();
+
+        let columns = (0..COLUMNS).map(|_| deck.deal(COLUMN_DEPTH)).collect();
+        let foundations = (0..FOUNDATIONS).map(|_| vec![]).collect();
+
+        FortyThieves { deck, waste: vec![], columns, foundations }
+    }
+
+    pub fn play()
+    {
+        let mut game = FortyThieves::new();
+
+        while !game.win()
+        {
+            game.handle_input();
+        }
+
+        println!("You won 😀");
+    }
+
+    fn get_move_input(&self) -> Option<MoveCommand>
+    {
+        let get_move_position = |min, max| {
+            println!("Select a number between {} and {}", min, max);
+            let position_i: usize = read!("{}\n");
+
+            position_i - 1
+        };
+
+        let positions = ["Waste", "Columns", "Foundation", "Back"];
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&positions)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) =>
+            {
+                println!("+ -------------------- + ");
+                match positions[index] {
+                    "Waste" => Some(MoveCommand { location: Position::Waste, i: 0 }),
+                    "Columns" => Some(MoveCommand { location: Position::Columns, i: get_move_position(1, COLUMNS) }),
+                    "Foundation" => Some(MoveCommand { location: Position::Foundation, i: 0 }),
+                    "Back" => None,
+                    _ => {
+                        println!("Invalid selection");
+                        self.get_move_input()
+                    }
+                }
+            },
+            None => {
+                println!("Invalid selection");
+                self.get_move_input()
+            }
+        }
+    }
+
+    fn moving_card(&self, from: MoveCommand) -> Option<&Card>
+    {
+        match from.location
+        {
+            Position::Waste => self.waste.last(),
+            Position::Columns => self.columns.get(from.i)?.last(),
+            Position::Foundation => None
+        }
+    }
+
+    // Both decks share the foundations, so a card goes up on the first one that takes it
+    fn foundation_for(&self, card: &Card) -> Option<usize>
+    {
+        self.foundations.iter().position(|f| match f.last() {
+            Some(c) => c.suit == card.suit && c.low_rank() + 1 == card.low_rank(),
+            None => card.is_ace()
+        })
+    }
+
+    fn is_move_valid(&self, from: MoveCommand, to: MoveCommand) -> bool
+    {
+        let move_card = match self.moving_card(from) { Some(c) => c, None => return false };
+
+        match to.location
+        {
+            Position::Waste => false,
+            Position::Columns => {
+                if from.location == Position::Columns && from.i == to.i { return false }
+                match self.columns.get(to.i)
+                {
+                    Some(column) => match column.last() {
+                        Some(c) => move_card.suit == c.suit && move_card.low_rank() + 1 == c.low_rank(),
+                        None => true
+                    },
+                    None => false
+                }
+            },
+            Position::Foundation => self.foundation_for(move_card).is_some()
+        }
+    }
+
+    fn make_move(&mut self, from: MoveCommand, to: MoveCommand) -> Result<(), String>
+    {
+        if !self.is_move_valid(from, to)
+        {
+            return Err(String::from("Unable to make that move"))
+        }
+
+        let move_card = match from.location
+        {
+            Position::Waste => self.waste.pop().unwrap(),
+            Position::Columns => self.columns[from.i].pop().unwrap(),
+            Position::Foundation => return Err(String::from("Unable to make that move"))
+        };
+
+        match to.location
+        {
+            Position::Columns => self.columns[to.i].push(move_card),
+            Position::Foundation => {
+                let i = self.foundation_for(&move_card).unwrap();
+                self.foundations[i].push(move_card)
+            },
+            Position::Waste => return Err(String::from("Unable to make that move"))
+        };
+
+        Ok(())
+    }
+
+    fn handle_move(&mut self) -> Result<(), String>
+    {
+        let from = match self.get_move_input() { Some(e) => e, None => return Err(String::from("Going back"))};
+        let to = match self.get_move_input() { Some(e) => e, None => return Err(String::from("Going back"))};
+
+        self.make_move(from, to)
+    }
+
+    // Forty Thieves only goes through the stock once
+    fn handle_draw(&mut self) -> Result<(), String>
+    {
+        if self.deck.size() == 0
+        {
+            return Err(String::from("The stock is empty"))
+        }
+
+        self.waste.push(self.deck.draw());
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for FortyThieves {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let max_column = self.columns.iter().map(|c| c.len()).max().unwrap();
+        let foundation_strs: Vec<String> = self.foundations.iter().map(|f| match f.last() { Some(e) => e.to_string(), None => String::from("---") }).collect();
+        let waste_str = match self.waste.last() { Some(e) => e.to_string(), None => String::from("---") };
+
+        let mut lines = vec![
+            format!("🃏 : {} Cards remaining", self.deck.size()),
+            format!("+ TOP: ---{}--- +", foundation_strs.join("---")),
+            format!("+ --- Waste: {} --- +", waste_str)
+            ];
+        for i in 0..max_column
+        {
+            let strings: Vec<String> = self.columns.iter().map(|c| c.get(i)).map(|c| {
+                match c {
+                    Some(m) => format!("{}", m),
+                    None => "   ".to_string()
+                }
+            }).collect();
+            lines.push(strings.join(" | "));
+        };
+
+        lines.push("+ ------------------- +".to_string());
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for FortyThieves
+{
+    fn win(&self) -> bool
+    {
+        self.foundations.iter().all(|f| f.len() == FOUNDATION_SIZE)
+    }
+
+    fn handle_input(&mut self)
+    {
+        let commands = ["Display", "Move", "Draw"];
+        println!("Select a command: ");
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&commands)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) =>
+            {
+                println!("+ -------------------- + ");
+                match commands[index] {
+                    "Display" => println!("{}", self),
+                    "Move" => self.handle_move().unwrap_or_else(|err| println!("{}", err)),
+                    "Draw" => self.handle_draw().unwrap_or_else(|err| println!("{}", err)),
+                    _ => {
+                        println!("Invalid selection");
+                        self.handle_input();
+                    }
+                }
+            }
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::Suit;
+
+    fn card(suit: Suit, rank: u8) -> Card
+    {
+        Card { suit, rank, visible: true }
+    }
+
+    const FOUNDATION: MoveCommand = MoveCommand { location: Position::Foundation, i: 0 };
+
+    #[test]
+    fn deal()
+    {
+        let game = FortyThieves::new();
+
+        assert_eq!(game.columns.len(), COLUMNS);
+        assert!(game.columns.iter().all(|c| c.len() == COLUMN_DEPTH));
+        assert_eq!(game.deck.size(), 104 - COLUMNS * COLUMN_DEPTH);
+    }
+
+    #[test]
+    fn builds_down_in_suit()
+    {
+        let mut game = FortyThieves::new();
+        game.columns[0] = vec![card(Suit::Heart, 9)];
+        game.columns[1] = vec![card(Suit::Heart, 8)];
+        game.columns[2] = vec![card(Suit::Diamond, 8)];
+
+        let to = MoveCommand { location: Position::Columns, i: 0 };
+        assert!(!game.is_move_valid(MoveCommand { location: Position::Columns, i: 2 }, to));
+        assert!(game.make_move(MoveCommand { location: Position::Columns, i: 1 }, to).is_ok());
+        assert!(game.columns[1].is_empty());
+
+        // Any card can fill an empty column
+        assert!(game.is_move_valid(MoveCommand { location: Position::Columns, i: 2 }, MoveCommand { location: Position::Columns, i: 1 }));
+    }
+
+    #[test]
+    fn foundations_take_both_decks()
+    {
+        let mut game = FortyThieves::new();
+        let from = MoveCommand { location: Position::Waste, i: 0 };
+
+        game.waste = vec![card(Suit::Spade, 2), card(Suit::Spade, 14), card(Suit::Spade, 14)];
+        assert!(game.make_move(from, FOUNDATION).is_ok());
+        assert!(game.make_move(from, FOUNDATION).is_ok());
+        assert!(game.make_move(from, FOUNDATION).is_ok());
+
+        assert_eq!(game.foundations[0].len(), 2);
+        assert_eq!(game.foundations[1].len(), 1);
+    }
+
+    #[test]
+    fn single_pass_through_stock()
+    {
+        let mut game = FortyThieves::new();
+
+        while game.deck.size() > 0 { game.handle_draw().unwrap() }
+
+        assert!(game.handle_draw().is_err());
+    }
+}
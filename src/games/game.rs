@@ -1,4 +1,58 @@
+use text_io::read;
+
 pub trait Game {
     fn win(&self) -> bool;
     fn handle_input(&mut self);
-}
\ No newline at end of file
+}
+
+// A game where every seat holds cards the others can't see, so what the table
+// looks like depends on who is looking
+pub trait HiddenHands: Game {
+    fn to_play(&self) -> usize;
+    fn over(&self) -> bool;
+    fn view(&self, seat: usize) -> String;
+    fn bot_turn(&mut self);
+    fn human_turn(&mut self);
+
+    // Bots play until it's the human's turn, who sees only their side of the table
+    fn take_turns(&mut self) {
+        while !self.over() && self.to_play() != HUMAN {
+            self.bot_turn();
+        }
+        if !self.over() {
+            println!("{}", self.view(HUMAN));
+            self.human_turn();
+        }
+        while !self.over() && self.to_play() != HUMAN {
+            self.bot_turn();
+        }
+    }
+}
+
+// Every table seats the human first and bots in the rest
+pub const HUMAN: usize = 0;
+
+pub fn player_names(players: usize) -> Vec<String>
+{
+    (0..players).map(|i| if i == HUMAN { "You".to_string() } else { format!("Bot {}", i) }).collect()
+}
+
+pub fn read_players(max_players: usize) -> usize
+{
+    read_players_between(2, max_players)
+}
+
+// For games that need more than one opponent. Out of range answers are clamped, and
+// the player is told what they got instead.
+pub fn read_players_between(min_players: usize, max_players: usize) -> usize
+{
+    println!("How many opponents? ({}-{})", min_players - 1, max_players - 1);
+    let opponents: usize = read!("{}\n");
+    let clamped = opponents.clamp(min_players - 1, max_players - 1);
+
+    if clamped != opponents
+    {
+        println!("Playing with {} opponents", clamped);
+    }
+    clamped + 1
+}
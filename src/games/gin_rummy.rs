@@ -0,0 +1,463 @@
+use std::fmt;
+use std::cmp::Reverse;
+use crate::deck::{Stack, Card};
+use crate::rummy::{deadwood_value, best_melds, lay_off};
+use crate::game::{Game, HUMAN};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const BOT: usize = 1;
+const HAND_SIZE: usize = 10;
+const KNOCK_LIMIT: u32 = 10;
+const GIN_BONUS: u32 = 25;
+const UNDERCUT_BONUS: u32 = 25;
+const GAME_OVER: u32 = 100;
+// Added at the end of the game: the winner's bonus and a box for every hand won
+const GAME_BONUS: u32 = 100;
+const BOX_BONUS: u32 = 25;
+// The hand is a draw once only this many cards are left in the stock
+const DEAD_STOCK: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome
+{
+    Gin(u32),
+    Knock(u32),
+    Undercut(u32)
+}
+
+// Gin scores the defender's whole deadwood. Otherwise the defender lays off what
+// they can on the knocker's melds, and undercuts them with the same or less deadwood.
+pub fn settle(knocker: &[Card], defender: &[Card]) -> Outcome
+{
+    let knock = best_melds(knocker);
+    let defence = best_melds(defender);
+    if knock.deadwood.is_empty()
+    {
+        return Outcome::Gin(GIN_BONUS + defence.deadwood_points())
+    }
+
+    let mut melds = knock.melds.clone();
+    let left: u32 = lay_off(&mut melds, &defence.deadwood).iter().map(deadwood_value).sum();
+    let knocked = knock.deadwood_points();
+
+    if left <= knocked { Outcome::Undercut(knocked - left + UNDERCUT_BONUS) } else { Outcome::Knock(left - knocked) }
+}
+
+fn without(hand: &[Card], index: usize) -> Vec<Card>
+{
+    let mut rest = hand.to_vec();
+    rest.remove(index);
+    rest
+}
+
+fn sort(hand: &mut [Card])
+{
+    hand.sort_by_key(|c| (c.suit as u8, c.low_rank()));
+}
+
+// Throws whatever leaves the least deadwood, the highest card on ties, but never
+// the card just taken from the discards
+fn bot_discard(hand: &[Card], taken: Option<Card>) -> usize
+{
+    (0..hand.len())
+        .filter(|i| Some(hand[*i]) != taken)
+        .min_by_key(|i| (best_melds(&without(hand, *i)).deadwood_points(), Reverse(deadwood_value(&hand[*i]))))
+        .unwrap()
+}
+
+// Takes the top discard when it leaves less deadwood than the hand has now
+fn bot_takes_discard(hand: &[Card], top: Card) -> bool
+{
+    let mut with = hand.to_vec();
+    with.push(top);
+    let after = best_melds(&without(&with, bot_discard(&with, Some(top)))).deadwood_points();
+
+    after < best_melds(hand).deadwood_points()
+}
+
+pub struct GinRummy
+{
+    names: Vec<String>,
+    scores: Vec<u32>,
+    // Hands won by each player
+    boxes: Vec<u32>,
+    dealer: usize,
+    stock: Stack,
+    discards: Vec<Card>,
+    hands: Vec<Vec<Card>>,
+    to_play: usize,
+    // The card taken from the discards this turn, which can't be thrown straight back
+    taken: Option<Card>,
+    leave: bool,
+}
+
+impl GinRummy
+{
+    pub fn new() -> GinRummy
+    {
+        let mut game = GinRummy
+        {
+            names: vec!["You".to_string(), "Bot".to_string()],
+            scores: vec![0; 2],
+            boxes: vec![0; 2],
+            dealer: BOT,
+            stock: Stack::new_deck(false),
+            discards: vec![],
+            hands: vec![vec![]; 2],
+            to_play: HUMAN,
+            taken: None,
+            leave: false
+        };
+
+        game.deal();
+        game
+    }
+
+    pub fn play()
+    {
+        let mut game = GinRummy::new();
+
+        while !game.leave && !game.game_over()
+        {
+            game.handle_input();
+        }
+
+        let totals = game.final_totals();
+        println!("Final score: You {}, Bot {}", totals[HUMAN], totals[BOT]);
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    // Ten cards each and one to start the discards. The player who didn't deal goes first.
+    pub fn deal(&mut self)
+    {
+        self.stock = Stack::new_deck(false);
+        self.stock.shuffle();
+
+        for seat in 0..2
+        {
+            self.hands[seat] = self.stock.deal(HAND_SIZE);
+            sort(&mut self.hands[seat]);
+        }
+        self.discards = vec![self.stock.draw()];
+        self.to_play = 1 - self.dealer;
+        self.taken = None;
+    }
+
+    pub fn draw(&mut self, from_discards: bool) -> Result<Card, String>
+    {
+        let seat = self.to_play;
+        if self.hands[seat].len() != HAND_SIZE
+        {
+            return Err(String::from("You have already drawn this turn"))
+        }
+
+        let card = if from_discards
+        {
+            let card = self.discards.pop().ok_or_else(|| String::from("There are no discards to take"))?;
+            self.taken = Some(card);
+            card
+        }
+        else
+        {
+            self.stock.draw()
+        };
+        self.hands[seat].push(card);
+        sort(&mut self.hands[seat]);
+
+        Ok(card)
+    }
+
+    // Ends the turn by throwing a card on to the discards
+    pub fn discard(&mut self, index: usize) -> Result<Card, String>
+    {
+        let seat = self.to_play;
+        if self.hands[seat].len() != HAND_SIZE + 1
+        {
+            return Err(String::from("Draw a card before discarding"))
+        }
+        if index >= self.hands[seat].len() || Some(self.hands[seat][index]) == self.taken
+        {
+            return Err(String::from("Unable to discard that card"))
+        }
+
+        let card = self.hands[seat].remove(index);
+        self.discards.push(card);
+        self.taken = None;
+        self.to_play = 1 - seat;
+
+        Ok(card)
+    }
+
+    fn deadwood(&self, seat: usize) -> u32
+    {
+        best_melds(&self.hands[seat]).deadwood_points()
+    }
+
+    // Lays down the hand of a player who has just discarded, scores it and passes the deal
+    pub fn knock(&mut self, seat: usize) -> Result<Outcome, String>
+    {
+        if self.hands[seat].len() != HAND_SIZE || self.to_play == seat
+        {
+            return Err(String::from("Knock after discarding"))
+        }
+        if self.deadwood(seat) > KNOCK_LIMIT
+        {
+            return Err(format!("You need {} or less deadwood to knock", KNOCK_LIMIT))
+        }
+
+        let defender = 1 - seat;
+        let outcome = settle(&self.hands[seat], &self.hands[defender]);
+        let (winner, points) = match outcome {
+            Outcome::Gin(points) | Outcome::Knock(points) => (seat, points),
+            Outcome::Undercut(points) => (defender, points)
+        };
+        println!("{}: {}", self.names[seat], best_melds(&self.hands[seat]));
+        println!("{}: {}", self.names[defender], best_melds(&self.hands[defender]));
+
+        self.scores[winner] += points;
+        self.boxes[winner] += 1;
+        self.dealer = 1 - self.dealer;
+
+        Ok(outcome)
+    }
+
+    pub fn stock_dead(&self) -> bool
+    {
+        self.stock.size() <= DEAD_STOCK
+    }
+
+    pub fn game_over(&self) -> bool
+    {
+        self.scores.iter().any(|s| *s >= GAME_OVER)
+    }
+
+    // Points plus boxes, with the game bonus for the winner, doubled for a shutout
+    pub fn final_totals(&self) -> Vec<u32>
+    {
+        let mut totals: Vec<u32> = (0..2).map(|seat| self.scores[seat] + BOX_BONUS * self.boxes[seat]).collect();
+
+        if self.game_over()
+        {
+            let winner = if self.scores[HUMAN] >= self.scores[BOT] { HUMAN } else { BOT };
+            totals[winner] += GAME_BONUS;
+            if self.scores[1 - winner] == 0 { totals[winner] *= 2 }
+        }
+
+        totals
+    }
+
+    fn report(&self, seat: usize, outcome: Outcome)
+    {
+        match outcome {
+            Outcome::Gin(points) => println!("{} went gin for {}", self.names[seat], points),
+            Outcome::Knock(points) => println!("{} knocked for {}", self.names[seat], points),
+            Outcome::Undercut(points) => println!("{} undercut {} for {}", self.names[1 - seat], self.names[seat], points)
+        }
+    }
+
+    fn select(&self, prompt: &str, options: &[String]) -> Option<usize>
+    {
+        Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap()
+    }
+
+    // Draw, discard and perhaps knock. Returns whether the player knocked.
+    fn human_turn(&mut self) -> bool
+    {
+        println!("{}", self);
+        let mut options = vec!["Draw from the stock".to_string()];
+        if let Some(top) = self.discards.last()
+        {
+            options.push(format!("Take {} from the discards", top));
+        }
+        options.push("Leave".to_string());
+
+        match self.select("Your draw", &options) {
+            Some(index) if index == options.len() - 1 => {
+                self.leave = true;
+                return false
+            },
+            Some(index) => println!("You drew {}", self.draw(index == 1).unwrap()),
+            None => return false
+        }
+
+        while self.hands[HUMAN].len() > HAND_SIZE
+        {
+            let cards: Vec<String> = self.hands[HUMAN].iter().map(|c| c.to_string()).collect();
+            let index = self.select("Discard a card", &cards).unwrap_or(0);
+            self.discard(index).map(|_| ()).unwrap_or_else(|err| println!("{}", err));
+        }
+
+        let deadwood = self.deadwood(HUMAN);
+        if deadwood > KNOCK_LIMIT { return false }
+        let knock = if deadwood == 0 { "Go gin" } else { "Knock" };
+        let options = vec!["Keep playing".to_string(), format!("{} with {} deadwood", knock, deadwood)];
+        self.select("Knock?", &options) == Some(1)
+    }
+
+    fn bot_turn(&mut self) -> bool
+    {
+        let top = self.discards.last().copied();
+        let from_discards = top.is_some_and(|top| bot_takes_discard(&self.hands[BOT], top));
+        let card = self.draw(from_discards).unwrap();
+        if from_discards { println!("Bot takes {}", card) } else { println!("Bot draws from the stock") }
+
+        let index = bot_discard(&self.hands[BOT], self.taken);
+        println!("Bot discards {}", self.discard(index).unwrap());
+
+        self.deadwood(BOT) <= KNOCK_LIMIT
+    }
+}
+
+impl fmt::Display for GinRummy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![format!("+ --- You {} ({} boxes), Bot {} ({} boxes) --- +", self.scores[HUMAN], self.boxes[HUMAN], self.scores[BOT], self.boxes[BOT])];
+        lines.push(format!("Stock: {} cards, Discard: {}", self.stock.size(), self.discards.last().map_or(String::from("none"), |c| c.to_string())));
+        lines.push(format!("Your hand: {}", best_melds(&self.hands[HUMAN])));
+        lines.push("+ ------------------- +".to_string());
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for GinRummy
+{
+    fn win(&self) -> bool
+    {
+        self.game_over() && self.scores[HUMAN] > self.scores[BOT]
+    }
+
+    fn handle_input(&mut self)
+    {
+        let seat = self.to_play;
+        let knocked = if seat == HUMAN { self.human_turn() } else { self.bot_turn() };
+        if self.leave { return }
+
+        if knocked
+        {
+            match self.knock(seat) {
+                Ok(outcome) => {
+                    self.report(seat, outcome);
+                    if !self.game_over() { self.deal() }
+                },
+                Err(err) => println!("{}", err)
+            }
+        }
+        else if self.stock_dead()
+        {
+            println!("The stock has run out, the hand is a draw");
+            self.dealer = 1 - self.dealer;
+            self.deal();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    fn cards(cards: &str) -> Vec<Card>
+    {
+        parse_cards(cards).unwrap()
+    }
+
+    #[test]
+    fn settling()
+    {
+        assert_eq!(settle(&cards("AsAhAd2c3c4c5c9d9s9h"), &cards("KsKdQh2h3d4s5s6s7s8c")), Outcome::Gin(25 + 43));
+        // The defender lays the six of clubs off on the knocker's run
+        let knocker = cards("2c3c4c5cKsKdKhAsAh2h");
+        assert_eq!(settle(&knocker, &cards("6cQdQhJsTs2d3d4d5d7h")), Outcome::Knock(47 - 4));
+        // And the king of clubs on their kings, leaving less deadwood than the knocker
+        assert_eq!(settle(&knocker, &cards("6cKcQhQsQd8s9sTs2dAd")), Outcome::Undercut(4 - 3 + 25));
+    }
+
+    #[test]
+    fn bot_choices()
+    {
+        let hand = cards("AsAhAd2c3c4c5c9d9sKh");
+        assert!(bot_takes_discard(&hand, "9h".parse().unwrap()));
+        assert!(!bot_takes_discard(&hand, "Qd".parse().unwrap()));
+
+        let mut drawn = hand.clone();
+        drawn.push("9h".parse().unwrap());
+        assert_eq!(drawn[bot_discard(&drawn, Some(drawn[10]))], "Kh".parse().unwrap());
+        drawn[10] = "Qh".parse().unwrap();
+        assert_eq!(drawn[bot_discard(&drawn, Some(drawn[10]))], "Kh".parse().unwrap());
+    }
+
+    #[test]
+    fn turns()
+    {
+        let mut game = GinRummy::new();
+        let seat = game.to_play;
+
+        assert!(game.discard(0).is_err());
+        let card = game.draw(true).unwrap();
+        assert!(game.draw(false).is_err());
+        let taken = game.hands[seat].iter().position(|c| *c == card).unwrap();
+        assert!(game.discard(taken).is_err());
+        game.discard((taken + 1) % 11).unwrap();
+        assert_eq!(game.to_play, 1 - seat);
+
+        game.hands[seat] = cards("AsAhAd2c3c4c5c9dKsQh");
+        assert!(game.knock(seat).is_err());
+        game.hands[seat] = cards("AsAhAd2c3c4c5c9d9s9h");
+        game.hands[1 - seat] = cards("KsKdQh2h3d4s5s6s7s8c");
+        assert_eq!(game.knock(seat), Ok(Outcome::Gin(68)));
+        assert_eq!(game.boxes[seat], 1);
+    }
+
+    #[test]
+    fn box_scoring()
+    {
+        let mut game = GinRummy::new();
+        game.scores = vec![104, 0];
+        game.boxes = vec![3, 0];
+        assert_eq!(game.final_totals(), vec![(104 + 75 + 100) * 2, 0]);
+
+        game.scores = vec![40, 110];
+        game.boxes = vec![2, 4];
+        assert_eq!(game.final_totals(), vec![90, 310]);
+    }
+
+    #[test]
+    fn bots_play_a_hand()
+    {
+        let mut game = GinRummy::new();
+
+        loop
+        {
+            let seat = game.to_play;
+            let top = *game.discards.last().unwrap();
+            let from_discards = bot_takes_discard(&game.hands[seat], top);
+            game.draw(from_discards).unwrap();
+            let index = bot_discard(&game.hands[seat], game.taken);
+            game.discard(index).unwrap();
+
+            if game.deadwood(seat) <= KNOCK_LIMIT
+            {
+                game.knock(seat).unwrap();
+                assert_eq!(game.scores.iter().filter(|s| **s > 0).count(), 1);
+                break
+            }
+            if game.stock_dead() { break }
+        }
+    }
+}
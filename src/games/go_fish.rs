@@ -0,0 +1,390 @@
+use rand::{thread_rng, seq::SliceRandom};
+use crate::deck::{Stack, Card, rank_name};
+use crate::game::{Game, HiddenHands, HUMAN, player_names, read_players};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const MAX_PLAYERS: usize = 6;
+const BOOKS: usize = 13;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Answer {
+    // The player asked handed over this many cards
+    Took(usize),
+    // Nobody had any, so a card came from the stock, unless it was empty, and the
+    // asker goes again if it was the rank they asked for
+    Fished(Option<Card>, bool),
+}
+
+pub struct GoFish
+{
+    names: Vec<String>,
+    hands: Vec<Vec<Card>>,
+    stock: Stack,
+    books: Vec<Vec<u8>>,
+    // The ranks every seat is known to hold, from having asked for them
+    known: Vec<Vec<u8>>,
+    to_play: usize,
+    leave: bool,
+}
+
+impl GoFish
+{
+    // Seven cards each for two or three players, five for more
+    pub fn new(players: usize) -> Result<GoFish, String>
+    {
+        if !(2..=MAX_PLAYERS).contains(&players)
+        {
+            return Err(format!("Go Fish is played by 2 to {} players, {} provided", MAX_PLAYERS, players))
+        }
+
+        let mut stock = Stack::new_deck(false);
+        stock.shuffle();
+        let hand_size = if players <= 3 { 7 } else { 5 };
+        let hands = (0..players).map(|_| stock.deal(hand_size)).collect();
+
+        let mut game = GoFish { names: player_names(players), hands, stock, books: vec![vec![]; players], known: vec![vec![]; players], to_play: 0, leave: false };
+        for seat in 0..players
+        {
+            game.lay_books(seat);
+        }
+        game.pass_to(0);
+        Ok(game)
+    }
+
+    pub fn play()
+    {
+        let mut game = match GoFish::new(read_players(MAX_PLAYERS)) {
+            Ok(game) => game,
+            Err(err) => return println!("{}", err)
+        };
+
+        while !game.leave && !game.over()
+        {
+            game.handle_input();
+        }
+
+        println!("{}", game.view(HUMAN));
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    fn ranks(&self, seat: usize) -> Vec<u8>
+    {
+        let mut ranks: Vec<u8> = self.hands[seat].iter().map(|c| c.rank).collect();
+        ranks.sort_unstable();
+        ranks.dedup();
+        ranks
+    }
+
+    // A player with no cards left can still be asked while they could draw more
+    fn in_game(&self, seat: usize) -> bool
+    {
+        !self.hands[seat].is_empty() || self.stock.size() > 0
+    }
+
+    fn opponents(&self, seat: usize) -> Vec<usize>
+    {
+        (0..self.hands.len()).filter(|s| *s != seat && self.in_game(*s)).collect()
+    }
+
+    fn forget(&mut self, seat: usize, rank: u8)
+    {
+        self.known[seat].retain(|r| *r != rank);
+    }
+
+    // Puts down any four of a kind, which nobody can ask for again
+    fn lay_books(&mut self, seat: usize)
+    {
+        for rank in self.ranks(seat)
+        {
+            if self.hands[seat].iter().filter(|c| c.rank == rank).count() == 4
+            {
+                self.hands[seat].retain(|c| c.rank != rank);
+                self.books[seat].push(rank);
+                for known in 0..self.known.len()
+                {
+                    self.forget(known, rank);
+                }
+            }
+        }
+    }
+
+    // Hands the turn to the first seat from `seat` on that has cards, or can draw
+    // one from the stock to carry on with
+    fn pass_to(&mut self, seat: usize)
+    {
+        let n = self.hands.len();
+
+        for s in (0..n).map(|step| (seat + step) % n)
+        {
+            if self.hands[s].is_empty() && self.stock.size() > 0
+            {
+                let card = self.stock.draw();
+                self.hands[s].push(card);
+            }
+            if !self.hands[s].is_empty()
+            {
+                self.to_play = s;
+                return
+            }
+        }
+    }
+
+    // The player to play asks `target` for every card of a rank they hold themselves
+    pub fn ask(&mut self, target: usize, rank: u8) -> Result<Answer, String>
+    {
+        let seat = self.to_play;
+        if target == seat || target >= self.hands.len() || !self.in_game(target)
+        {
+            return Err(String::from("Ask another player who is still in the game"))
+        }
+        if !self.hands[seat].iter().any(|c| c.rank == rank)
+        {
+            return Err(format!("You can only ask for a rank you hold, not{}", rank_name(rank)))
+        }
+
+        if !self.known[seat].contains(&rank) { self.known[seat].push(rank) }
+        let given: Vec<Card> = self.hands[target].iter().filter(|c| c.rank == rank).copied().collect();
+
+        let answer = if given.is_empty()
+        {
+            let card = if self.stock.size() > 0 { Some(self.stock.draw()) } else { None };
+            self.hands[seat].extend(card);
+            Answer::Fished(card, card.map(|c| c.rank) == Some(rank))
+        }
+        else
+        {
+            self.hands[target].retain(|c| c.rank != rank);
+            self.hands[seat].extend(given.iter());
+            self.forget(target, rank);
+            Answer::Took(given.len())
+        };
+        self.lay_books(seat);
+
+        match answer {
+            Answer::Took(_) | Answer::Fished(_, true) => self.pass_to(seat),
+            Answer::Fished(_, false) => self.pass_to(seat + 1)
+        }
+        Ok(answer)
+    }
+
+    // Asks whoever is known to hold one of its ranks, and otherwise asks anyone for
+    // the rank it holds most of
+    fn bot_ask(&self, seat: usize) -> (usize, u8)
+    {
+        let opponents = self.opponents(seat);
+        let ranks = self.ranks(seat);
+
+        for rank in ranks.iter()
+        {
+            if let Some(target) = opponents.iter().find(|s| self.known[**s].contains(rank))
+            {
+                return (*target, *rank)
+            }
+        }
+
+        let count = |rank: &u8| self.hands[seat].iter().filter(|c| c.rank == *rank).count();
+        let rank = *ranks.iter().max_by_key(|r| count(r)).unwrap();
+        (*opponents.choose(&mut thread_rng()).unwrap(), rank)
+    }
+
+    fn report(&self, seat: usize, target: usize, rank: u8, answer: Answer)
+    {
+        let asked = format!("{} asked {} for{}s", self.names[seat], self.names[target], rank_name(rank));
+
+        match answer {
+            Answer::Took(n) => println!("{} and took {}", asked, n),
+            Answer::Fished(Some(card), true) if seat == HUMAN => println!("{}, fished and caught {}", asked, card),
+            Answer::Fished(Some(card), false) if seat == HUMAN => println!("{} and fished {}", asked, card),
+            Answer::Fished(Some(_), true) => println!("{}, fished and caught one", asked),
+            Answer::Fished(_, _) => println!("{} and went fishing", asked)
+        }
+    }
+
+    fn select(&self, prompt: &str, options: &[String]) -> Option<usize>
+    {
+        Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap()
+    }
+}
+
+impl HiddenHands for GoFish
+{
+    fn to_play(&self) -> usize
+    {
+        self.to_play
+    }
+
+    fn over(&self) -> bool
+    {
+        self.books.iter().map(|b| b.len()).sum::<usize>() == BOOKS
+    }
+
+    // Everyone's books and hand sizes, but only the cards of the seat looking
+    fn view(&self, seat: usize) -> String
+    {
+        let mut lines = vec![format!("+ --- Stock: {} cards --- +", self.stock.size())];
+        for s in 0..self.hands.len()
+        {
+            let books: Vec<String> = self.books[s].iter().map(|r| rank_name(*r).trim().to_string()).collect();
+            lines.push(format!("{:<6} {} cards, books: {}", self.names[s], self.hands[s].len(), books.join(" ")));
+        }
+        let mut hand = self.hands[seat].clone();
+        hand.sort_by_key(|c| (c.rank, c.suit as u8));
+        let hand: Vec<String> = hand.iter().map(|c| c.to_string()).collect();
+        lines.push(format!("Your hand: {}", hand.join(" ")));
+
+        lines.join("\n")
+    }
+
+    fn bot_turn(&mut self)
+    {
+        let seat = self.to_play;
+        let (target, rank) = self.bot_ask(seat);
+        let answer = self.ask(target, rank).unwrap();
+        self.report(seat, target, rank, answer);
+    }
+
+    fn human_turn(&mut self)
+    {
+        let ranks = self.ranks(HUMAN);
+        let mut options: Vec<String> = ranks.iter().map(|r| format!("Ask for{}s", rank_name(*r))).collect();
+        options.push("Leave".to_string());
+
+        let rank = match self.select("Which rank?", &options) {
+            Some(index) if index < ranks.len() => ranks[index],
+            Some(_) => return self.leave = true,
+            None => return println!("User did not select anything")
+        };
+
+        let opponents = self.opponents(HUMAN);
+        let target = if opponents.len() == 1
+        {
+            opponents[0]
+        }
+        else
+        {
+            let names: Vec<String> = opponents.iter().map(|s| self.names[*s].clone()).collect();
+            match self.select("Who do you ask?", &names) {
+                Some(index) => opponents[index],
+                None => return println!("User did not select anything")
+            }
+        };
+
+        match self.ask(target, rank) {
+            Ok(answer) => self.report(HUMAN, target, rank, answer),
+            Err(err) => println!("{}", err)
+        }
+    }
+}
+
+impl Game for GoFish
+{
+    // Most books wins, and sharing the most is good enough
+    fn win(&self) -> bool
+    {
+        self.over() && self.books.iter().all(|b| b.len() <= self.books[HUMAN].len())
+    }
+
+    fn handle_input(&mut self)
+    {
+        self.take_turns();
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    fn deal(hands: &[&str], stock: &str) -> GoFish
+    {
+        let mut game = GoFish::new(hands.len()).unwrap();
+        game.hands = hands.iter().map(|h| parse_cards(h).unwrap()).collect();
+        game.stock = Stack::from_cards(parse_cards(stock).unwrap());
+        game.to_play = 0;
+        game
+    }
+
+    #[test]
+    fn asking()
+    {
+        let mut game = deal(&["7s7h2c", "7d3c", "4d"], "9s5h");
+
+        assert!(game.ask(0, 7).is_err());
+        assert!(game.ask(1, 9).is_err());
+        assert_eq!(game.ask(1, 7), Ok(Answer::Took(1)));
+        assert_eq!(game.to_play, 0);
+        assert_eq!(game.ask(2, 2), Ok(Answer::Fished(Some("9s".parse().unwrap()), false)));
+        assert_eq!(game.to_play, 1);
+        assert_eq!(game.known[0], vec![7, 2]);
+    }
+
+    #[test]
+    fn books()
+    {
+        let mut game = deal(&["7s7h7c", "7d", "4d"], "");
+
+        assert_eq!(game.ask(1, 7), Ok(Answer::Took(1)));
+        assert_eq!(game.books[0], vec![7]);
+        assert!(game.known[0].is_empty());
+
+        // With no cards and no stock the turn moves on to whoever still has some
+        assert_eq!(game.to_play, 2);
+        assert!(game.view(0).contains("books: 7"));
+        assert!(!game.view(0).contains("4"));
+    }
+
+    #[test]
+    fn lucky_fishing()
+    {
+        let mut game = deal(&["5s", "6d"], "5h");
+
+        assert_eq!(game.ask(1, 5), Ok(Answer::Fished(Some("5h".parse().unwrap()), true)));
+        assert_eq!(game.to_play, 0);
+    }
+
+    #[test]
+    fn bots_remember()
+    {
+        let mut game = deal(&["9s", "9d2h", "9h3c", "5s"], "Ks");
+        game.to_play = 3;
+        game.ask(2, 5).unwrap();
+        game.known[1].push(9);
+
+        // Bot 2 knows bot 1 asked for nines, and bot 3 for fives
+        assert_eq!(game.bot_ask(2).0, 1);
+        assert_eq!(game.bot_ask(2).1, 9);
+        game.hands[2] = parse_cards("5c").unwrap();
+        assert_eq!(game.bot_ask(2), (3, 5));
+    }
+
+    #[test]
+    fn bots_play_a_game()
+    {
+        let mut game = GoFish::new(4).unwrap();
+
+        for _ in 0..1000
+        {
+            if game.over() { break }
+            let (target, rank) = game.bot_ask(game.to_play);
+            game.ask(target, rank).unwrap();
+        }
+        assert!(game.over());
+        assert_eq!(game.books.iter().map(|b| b.len()).sum::<usize>(), 13);
+    }
+}
@@ -0,0 +1,500 @@
+use std::fmt;
+use crate::deck::{Stack, Card, Suit};
+use crate::trick::{StandardRules, Trick, TrickRound, sort_hand, prefer};
+use crate::game::{Game, HUMAN, player_names};
+use dialoguer::{
+    Select,
+    MultiSelect,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const PLAYERS: usize = 4;
+const PASS_COUNT: usize = 3;
+const GAME_OVER: u32 = 100;
+// Every heart and the queen of spades, all of which one player can take to shoot the moon
+const MOON: u32 = 26;
+const RULES: StandardRules = StandardRules { trump: None };
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pass
+{
+    Left,
+    Right,
+    Across,
+    Hold
+}
+
+impl Pass
+{
+    // Passing goes left, right, across and then holds, round after round
+    fn for_round(round: usize) -> Pass
+    {
+        [Pass::Left, Pass::Right, Pass::Across, Pass::Hold][round % 4]
+    }
+
+    fn target(&self, seat: usize) -> Option<usize>
+    {
+        match self {
+            Pass::Left => Some((seat + 1) % PLAYERS),
+            Pass::Across => Some((seat + 2) % PLAYERS),
+            Pass::Right => Some((seat + 3) % PLAYERS),
+            Pass::Hold => None
+        }
+    }
+}
+
+impl fmt::Display for Pass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pass::Left => write!(f, "left"),
+            Pass::Right => write!(f, "right"),
+            Pass::Across => write!(f, "across"),
+            Pass::Hold => write!(f, "hold")
+        }
+    }
+}
+
+fn is_queen_of_spades(card: &Card) -> bool
+{
+    card.suit == Suit::Spade && card.rank == 12
+}
+
+fn is_two_of_clubs(card: &Card) -> bool
+{
+    card.suit == Suit::Club && card.rank == 2
+}
+
+pub fn points(card: &Card) -> u32
+{
+    if card.suit == Suit::Heart { 1 } else if is_queen_of_spades(card) { 13 } else { 0 }
+}
+
+// Bots get rid of the queen of spades and whatever might catch her, then high hearts
+fn bot_pass(hand: &[Card]) -> Vec<usize>
+{
+    let danger = |card: &Card| match card.suit {
+        _ if is_queen_of_spades(card) => 100,
+        Suit::Spade if card.rank > 12 => 80 + card.rank,
+        Suit::Heart => 40 + card.rank,
+        _ => card.rank
+    };
+
+    let mut order: Vec<usize> = (0..hand.len()).collect();
+    order.sort_by_key(|i| std::cmp::Reverse(danger(&hand[*i])));
+    order.truncate(PASS_COUNT);
+    order
+}
+
+// Bots lead low, duck under the winning card when they can and otherwise dump
+// the queen of spades and their highest hearts on someone else's trick
+fn bot_play(hand: &[Card], legal: &[usize], trick: &Trick) -> usize
+{
+    let card = |i: &usize| hand[*i];
+
+    let led = match trick.led_suit(&RULES) {
+        Some(suit) => suit,
+        None => return *legal.iter().min_by_key(|i| (points(&card(i)) > 0, card(i).rank)).unwrap()
+    };
+
+    if card(&legal[0]).suit != led
+    {
+        return *legal.iter().max_by_key(|i| (points(&card(i)), card(i).rank)).unwrap()
+    }
+
+    let winning = trick.cards().iter().filter(|c| c.suit == led).map(|c| c.rank).max().unwrap();
+    match legal.iter().filter(|i| card(i).rank < winning).max_by_key(|i| card(i).rank) {
+        Some(duck) => *duck,
+        None => *legal.iter().max_by_key(|i| (!is_queen_of_spades(&card(i)), card(i).rank)).unwrap()
+    }
+}
+
+pub struct Hearts
+{
+    names: Vec<String>,
+    scores: Vec<u32>,
+    hand_number: usize,
+    round: TrickRound,
+    passed: bool,
+    hearts_broken: bool,
+    leave: bool,
+}
+
+impl Hearts
+{
+    pub fn new() -> Hearts
+    {
+        let mut game = Hearts
+        {
+            names: player_names(PLAYERS),
+            scores: vec![0; PLAYERS],
+            hand_number: 0,
+            round: TrickRound::new(vec![vec![]; PLAYERS], 0),
+            passed: false,
+            hearts_broken: false,
+            leave: false
+        };
+
+        game.deal();
+        game
+    }
+
+    pub fn play()
+    {
+        let mut game = Hearts::new();
+
+        while !game.leave && !game.game_over()
+        {
+            game.handle_input();
+        }
+
+        println!("{}", game.score_lines().join("\n"));
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    // The two of clubs leads the first trick
+    fn start_round(&mut self, hands: Vec<Vec<Card>>)
+    {
+        let leader = hands.iter().position(|h| h.iter().any(is_two_of_clubs)).unwrap();
+
+        self.round = TrickRound::new(hands, leader);
+        self.hearts_broken = false;
+    }
+
+    pub fn deal(&mut self)
+    {
+        let mut deck = Stack::new_deck(false);
+        deck.shuffle();
+
+        let hands = (0..PLAYERS).map(|_| {
+            let mut hand = deck.deal(52 / PLAYERS);
+            sort_hand(&mut hand, &RULES);
+            hand
+        }).collect();
+
+        self.start_round(hands);
+        self.passed = Pass::for_round(self.hand_number) == Pass::Hold;
+    }
+
+    // Everyone passes the cards at their chosen indices at the same time
+    pub fn pass_cards(&mut self, choices: &[Vec<usize>]) -> Result<(), String>
+    {
+        let direction = Pass::for_round(self.hand_number);
+        if choices.iter().any(|c| c.len() != PASS_COUNT || (1..c.len()).any(|i| c[..i].contains(&c[i])))
+        {
+            return Err(format!("Pick {} different cards to pass", PASS_COUNT))
+        }
+
+        let mut hands = self.round.hands.clone();
+        for (seat, choice) in choices.iter().enumerate()
+        {
+            let mut indices = choice.clone();
+            indices.sort_unstable_by(|a, b| b.cmp(a));
+            let target = direction.target(seat).unwrap();
+            for i in indices
+            {
+                let card = self.round.hands[seat][i];
+                hands[seat].retain(|c| *c != card);
+                hands[target].push(card);
+            }
+        }
+        for hand in hands.iter_mut()
+        {
+            sort_hand(hand, &RULES);
+        }
+
+        self.start_round(hands);
+        self.passed = true;
+        Ok(())
+    }
+
+    // Follow suit, with no points on the first trick and no leading hearts until
+    // they are broken, unless there is no other choice
+    pub fn legal(&self) -> Vec<usize>
+    {
+        let hand = &self.round.hands[self.round.to_play()];
+        let legal = self.round.legal(&RULES);
+        let leading = self.round.trick.plays.is_empty();
+
+        if self.round.tricks_played() == 0
+        {
+            if leading { return prefer(hand, legal, is_two_of_clubs) }
+            return prefer(hand, legal, |c| points(c) == 0)
+        }
+        if leading && !self.hearts_broken
+        {
+            return prefer(hand, legal, |c| c.suit != Suit::Heart)
+        }
+
+        legal
+    }
+
+    pub fn play_card(&mut self, index: usize) -> Result<Option<usize>, String>
+    {
+        if !self.legal().contains(&index)
+        {
+            return Err(String::from("Unable to play that card"))
+        }
+
+        let seat = self.round.to_play();
+        let card = self.round.hands[seat][index];
+        let winner = self.round.play(index, &RULES)?;
+        self.hearts_broken |= card.suit == Suit::Heart;
+
+        println!("{}: {}", self.names[seat], card);
+        if let Some(winner) = winner
+        {
+            println!("{} took the trick", self.names[winner]);
+        }
+
+        Ok(winner)
+    }
+
+    // Adds up the round and returns each player's points. Taking every point
+    // shoots the moon and gives everyone else the lot instead.
+    pub fn score_round(&mut self) -> Vec<u32>
+    {
+        let mut taken: Vec<u32> = self.round.taken.iter().map(|cards| cards.iter().map(points).sum()).collect();
+
+        if let Some(shooter) = taken.iter().position(|p| *p == MOON)
+        {
+            println!("{} shot the moon!", self.names[shooter]);
+            taken = (0..PLAYERS).map(|seat| if seat == shooter { 0 } else { MOON }).collect();
+        }
+        for (score, points) in self.scores.iter_mut().zip(taken.iter())
+        {
+            *score += points;
+        }
+        self.hand_number += 1;
+
+        taken
+    }
+
+    pub fn game_over(&self) -> bool
+    {
+        self.scores.iter().any(|s| *s >= GAME_OVER)
+    }
+
+    fn get_pass(&self) -> Vec<usize>
+    {
+        let direction = Pass::for_round(self.hand_number);
+        let cards: Vec<String> = self.round.hands[HUMAN].iter().map(|c| c.to_string()).collect();
+
+        loop
+        {
+            let chosen = MultiSelect::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("Choose {} cards to pass {} (space to toggle)", PASS_COUNT, direction))
+                .items(&cards)
+                .interact_on(&Term::stderr()).unwrap();
+            if chosen.len() == PASS_COUNT { return chosen }
+
+            println!("Pick exactly {} cards", PASS_COUNT);
+        }
+    }
+
+    fn passing(&mut self)
+    {
+        let mut choices = vec![self.get_pass()];
+        for seat in 1..PLAYERS
+        {
+            choices.push(bot_pass(&self.round.hands[seat]));
+        }
+
+        self.pass_cards(&choices).unwrap_or_else(|err| println!("{}", err));
+    }
+
+    fn play_bots(&mut self)
+    {
+        while !self.round.finished() && self.round.to_play() != HUMAN
+        {
+            let seat = self.round.to_play();
+            let index = bot_play(&self.round.hands[seat], &self.legal(), &self.round.trick);
+            self.play_card(index).unwrap();
+        }
+    }
+
+    fn get_play(&mut self)
+    {
+        let legal = self.legal();
+        let mut options: Vec<String> = legal.iter().map(|i| self.round.hands[HUMAN][*i].to_string()).collect();
+        options.push("Leave".to_string());
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Play a card")
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) if index == legal.len() => self.leave = true,
+            Some(index) => { self.play_card(legal[index]).unwrap_or_else(|err| { println!("{}", err); None }); },
+            None => println!("User did not select anything")
+        }
+    }
+
+    fn score_lines(&self) -> Vec<String>
+    {
+        (0..PLAYERS).map(|seat| format!("{:<6} {:>3}", self.names[seat], self.scores[seat])).collect()
+    }
+}
+
+impl fmt::Display for Hearts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let trick: Vec<String> = self.round.trick.plays.iter().map(|(seat, card)| format!("{}: {}", self.names[*seat], card)).collect();
+        let hand: Vec<String> = self.round.hands[HUMAN].iter().map(|c| c.to_string()).collect();
+
+        let mut lines = vec!["+ --- Scores --- +".to_string()];
+        lines.extend(self.score_lines());
+        lines.push("+ ------------------- +".to_string());
+        lines.push(format!("Trick: {}", trick.join(" | ")));
+        lines.push(format!("Your hand: {}", hand.join(" ")));
+        lines.push("+ ------------------- +".to_string());
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Hearts
+{
+    // Lowest score when someone reaches 100, ties included
+    fn win(&self) -> bool
+    {
+        let lowest = self.scores.iter().min().unwrap();
+
+        self.game_over() && self.scores[HUMAN] == *lowest
+    }
+
+    fn handle_input(&mut self)
+    {
+        if !self.passed
+        {
+            println!("{}", self);
+            self.passing();
+            return
+        }
+
+        self.play_bots();
+        if !self.round.finished()
+        {
+            println!("{}", self);
+            self.get_play();
+            self.play_bots();
+        }
+
+        if self.round.finished()
+        {
+            let points = self.score_round();
+            for (name, points) in self.names.iter().zip(points.iter())
+            {
+                println!("{} took {} points", name, points);
+            }
+            if !self.game_over()
+            {
+                self.deal();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+
+    use super::*;
+    use crate::deck::parse_cards;
+
+    fn hands(hands: [&str; 4]) -> Vec<Vec<Card>>
+    {
+        hands.iter().map(|h| parse_cards(h).unwrap()).collect()
+    }
+
+    #[test]
+    fn opening_trick()
+    {
+        let mut game = Hearts::new();
+        game.start_round(hands(["AcQs", "2c5h", "3cAh", "Kd4h"]));
+
+        assert_eq!(game.round.to_play(), 1);
+        assert_eq!(game.legal(), vec![0]);
+        game.play_card(0).unwrap();
+        assert_eq!(game.legal(), vec![0]);
+        game.play_card(0).unwrap();
+        game.play_card(0).unwrap();
+        // Void in clubs, but hearts can't be dumped on the first trick when there's a choice
+        assert_eq!(game.legal(), vec![0]);
+        assert!(game.play_card(1).is_err());
+        assert_eq!(game.play_card(0), Ok(Some(0)));
+    }
+
+    #[test]
+    fn hearts_broken()
+    {
+        let mut game = Hearts::new();
+        game.start_round(hands(["5h2c9d", "3c6h8s", "4c3d", "5c7d"]));
+        game.round.tricks_won = vec![1, 0, 0, 0];
+        game.round.trick = Trick::new(0);
+
+        assert_eq!(game.legal(), vec![1, 2]);
+        game.play_card(1).unwrap();
+        game.play_card(0).unwrap();
+        game.play_card(0).unwrap();
+        game.play_card(0).unwrap();
+
+        game.round.trick = Trick::new(0);
+        assert_eq!(game.legal(), vec![1]);
+        game.play_card(1).unwrap();
+        assert!(!game.hearts_broken);
+        game.play_card(0).unwrap();
+        assert!(game.hearts_broken);
+    }
+
+    #[test]
+    fn passing()
+    {
+        let mut game = Hearts::new();
+        let before = game.round.hands.clone();
+        let choices: Vec<Vec<usize>> = (0..PLAYERS).map(|_| vec![0, 1, 2]).collect();
+
+        assert!(game.pass_cards(&[vec![0, 0, 1], vec![0, 1, 2], vec![0, 1, 2], vec![0, 1, 2]]).is_err());
+        game.pass_cards(&choices).unwrap();
+        assert!(before[0][..3].iter().all(|c| game.round.hands[1].contains(c)));
+        assert!(game.round.hands.iter().all(|h| h.len() == 13));
+        assert!(game.round.hands[game.round.to_play()].iter().any(is_two_of_clubs));
+    }
+
+    #[test]
+    fn shooting_the_moon()
+    {
+        let mut game = Hearts::new();
+        game.round.taken = vec![parse_cards("QsAh2h3h4h5h6h7h8h9hThJhQhKh").unwrap(), vec![], vec![], vec![]];
+
+        assert_eq!(game.score_round(), vec![0, 26, 26, 26]);
+        assert_eq!(game.hand_number, 1);
+    }
+
+    #[test]
+    fn bots_play_a_round()
+    {
+        let mut game = Hearts::new();
+        let choices: Vec<Vec<usize>> = (0..PLAYERS).map(|seat| bot_pass(&game.round.hands[seat])).collect();
+        game.pass_cards(&choices).unwrap();
+
+        while !game.round.finished()
+        {
+            let seat = game.round.to_play();
+            let index = bot_play(&game.round.hands[seat], &game.legal(), &game.round.trick);
+            game.play_card(index).unwrap();
+        }
+
+        assert_eq!(game.round.tricks_played(), 13);
+        let points: u32 = game.score_round().iter().sum();
+        assert!(points == MOON || points == MOON * 3);
+    }
+}
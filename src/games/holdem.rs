@@ -0,0 +1,348 @@
+use std::fmt;
+use crate::deck::{Stack, Card};
+use crate::betting::Betting;
+use crate::poker::{evaluate, evaluate_partial, Category};
+use crate::poker_table::{
+    STARTING_CHIPS, next_active, post_blinds, last_standing,
+    made_strength, seat_lines, betting_round, showdown
+};
+use crate::game::{Game, HUMAN, player_names, read_players};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const MAX_PLAYERS: usize = 9;
+const SMALL_BLIND: u32 = 5;
+const BIG_BLIND: u32 = 10;
+const STREETS: [(&str, usize); 3] = [("Flop", 3), ("Turn", 1), ("River", 1)];
+
+// A rough preflop hand score in the spirit of the Chen formula, scaled so that
+// kings or better count as unbeatable
+fn preflop_strength(hole: &[Card]) -> f64
+{
+    let high = hole[0].rank.max(hole[1].rank);
+    let low = hole[0].rank.min(hole[1].rank);
+    let mut score = match high {
+        14 => 10.0,
+        13 => 8.0,
+        12 => 7.0,
+        11 => 6.0,
+        rank => rank as f64 / 2.0
+    };
+
+    if high == low
+    {
+        score = (score * 2.0).max(5.0);
+    }
+    else
+    {
+        let gap = high - low - 1;
+        if hole[0].suit == hole[1].suit { score += 2.0 }
+        score -= match gap { 0 => 0.0, 1 => 1.0, 2 => 2.0, 3 => 4.0, _ => 5.0 };
+        if gap <= 1 && high < 12 { score += 1.0 }
+    }
+
+    (score / 16.0).clamp(0.0, 1.0)
+}
+
+// What the board makes on its own, which every player shares
+fn board_category(board: &[Card]) -> Category
+{
+    if board.len() >= 5 { evaluate(board).unwrap().category() } else { evaluate_partial(board).unwrap().category() }
+}
+
+fn postflop_strength(hole: &[Card], board: &[Card]) -> f64
+{
+    let cards: Vec<Card> = hole.iter().chain(board.iter()).copied().collect();
+    let rank = evaluate(&cards).unwrap();
+
+    // A hand that only matches the board isn't worth much
+    if board_category(board) == rank.category()
+    {
+        return (made_strength(rank) - 0.3).max(0.1)
+    }
+
+    made_strength(rank)
+}
+
+fn strength(hole: &[Card], board: &[Card]) -> f64
+{
+    if board.is_empty() { preflop_strength(hole) } else { postflop_strength(hole, board) }
+}
+
+// No limit Texas Hold'em against up to eight bots
+pub struct Holdem
+{
+    names: Vec<String>,
+    deck: Stack,
+    holes: Vec<Vec<Card>>,
+    board: Vec<Card>,
+    button: usize,
+    betting: Betting,
+    leave: bool,
+}
+
+impl Holdem
+{
+    pub fn new(players: usize) -> Result<Holdem, String>
+    {
+        if !(2..=MAX_PLAYERS).contains(&players)
+        {
+            return Err(format!("Hold'em is played by 2 to {} players, {} provided", MAX_PLAYERS, players))
+        }
+
+        Ok(Holdem
+        {
+            names: player_names(players),
+            deck: Stack::new_deck(false),
+            holes: vec![vec![]; players],
+            board: vec![],
+            // The button moves on before every hand, so the first hand starts with the human
+            button: players - 1,
+            betting: Betting::new(&vec![STARTING_CHIPS; players], BIG_BLIND),
+            leave: false
+        })
+    }
+
+    pub fn play()
+    {
+        let mut game = match Holdem::new(read_players(MAX_PLAYERS)) {
+            Ok(game) => game,
+            Err(err) => return println!("{}", err)
+        };
+
+        while !game.leave && game.chips()[HUMAN] > 0 && !game.win()
+        {
+            game.handle_input();
+        }
+
+        println!("You leave the table with {} chips", game.chips()[HUMAN]);
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    pub fn chips(&self) -> Vec<u32>
+    {
+        self.betting.seats.iter().map(|s| s.chips).collect()
+    }
+
+    // Moves the button, deals the hole cards and posts the blinds. Players without
+    // chips sit the hand out.
+    pub fn start_hand(&mut self)
+    {
+        self.betting = Betting::new(&self.chips(), BIG_BLIND);
+        self.button = next_active(&self.betting, self.button);
+        self.deck = Stack::new_deck(false);
+        self.deck.shuffle();
+        self.board.clear();
+
+        for seat in 0..self.names.len()
+        {
+            self.holes[seat] = if self.betting.seats[seat].folded { vec![] } else {
+                self.deck.deal(2).into_iter().map(Card::hide).collect()
+            };
+        }
+
+        post_blinds(&mut self.betting, self.button, SMALL_BLIND, BIG_BLIND);
+    }
+
+    pub fn deal_street(&mut self, count: usize)
+    {
+        self.board.extend(self.deck.deal(count));
+        self.betting.next_street(next_active(&self.betting, self.button));
+    }
+
+    fn betting_round(&mut self)
+    {
+        let (holes, board) = (&self.holes, &self.board);
+        betting_round(&mut self.betting, &self.names, |seat| strength(&holes[seat], board));
+    }
+
+    pub fn showdown(&mut self) -> Vec<u32>
+    {
+        let board = &self.board;
+        let rank = |hole: &[Card]| evaluate(&hole.iter().chain(board.iter()).copied().collect::<Vec<Card>>()).ok();
+
+        showdown(&mut self.betting, &self.names, &mut self.holes, rank)
+    }
+
+    pub fn play_hand(&mut self)
+    {
+        self.start_hand();
+        println!("{}", self);
+        self.betting_round();
+
+        for (street, count) in STREETS.iter()
+        {
+            if self.betting.hand_over() { break }
+
+            self.deal_street(*count);
+            println!("+ --- {} --- +", street);
+            println!("{}", self);
+            self.betting_round();
+        }
+
+        self.showdown();
+    }
+}
+
+impl fmt::Display for Holdem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let board: Vec<String> = self.board.iter().map(|c| c.to_string()).collect();
+
+        let mut lines = vec![
+            format!("Board: {}", board.join(" ")),
+            format!("+ --- Pot: {} --- +", self.betting.pot())
+            ];
+        lines.extend(seat_lines(&self.betting, &self.names, &self.holes, self.button));
+        lines.push("+ ------------------- +".to_string());
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Holdem
+{
+    // The human wins by taking every chip on the table
+    fn win(&self) -> bool
+    {
+        last_standing(&self.betting)
+    }
+
+    fn handle_input(&mut self)
+    {
+        let commands = ["Deal", "Display", "Leave"];
+        println!("Select a command: ");
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&commands)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) =>
+            {
+                println!("+ -------------------- + ");
+                match commands[index] {
+                    "Deal" => self.play_hand(),
+                    "Display" => println!("{}", self),
+                    "Leave" => self.leave = true,
+                    _ => {
+                        println!("Invalid selection");
+                        self.handle_input();
+                    }
+                }
+            }
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::betting::Bet;
+    use crate::deck::Suit;
+
+    fn card(rank: u8, suit: Suit) -> Card
+    {
+        Card { suit, rank, visible: true }
+    }
+
+    // Lets the bots play every seat, the human included
+    fn bot_round(game: &mut Holdem)
+    {
+        while !game.betting.round_over()
+        {
+            let bet = game.betting.bot_bet(strength(&game.holes[game.betting.to_act], &game.board));
+            game.betting.act(bet).unwrap();
+        }
+    }
+
+    #[test]
+    fn table_size()
+    {
+        assert!(Holdem::new(1).is_err());
+        assert!(Holdem::new(10).is_err());
+        assert_eq!(Holdem::new(9).unwrap().chips(), vec![STARTING_CHIPS; 9]);
+    }
+
+    #[test]
+    fn blinds()
+    {
+        let mut game = Holdem::new(2).unwrap();
+        game.start_hand();
+
+        // Heads up the button is the small blind and acts first
+        assert_eq!(game.button, 0);
+        assert_eq!(game.betting.seats[0].street, SMALL_BLIND);
+        assert_eq!(game.betting.seats[1].street, BIG_BLIND);
+        assert_eq!(game.betting.to_act, 0);
+        assert!(!game.holes[1][0].visible);
+
+        let mut game = Holdem::new(4).unwrap();
+        game.start_hand();
+        assert_eq!(game.betting.seats[1].street, SMALL_BLIND);
+        assert_eq!(game.betting.seats[2].street, BIG_BLIND);
+        assert_eq!(game.betting.to_act, 3);
+    }
+
+    #[test]
+    fn busted_players_sit_out()
+    {
+        let mut game = Holdem::new(3).unwrap();
+        game.betting = Betting::new(&[500, 0, 2500], BIG_BLIND);
+        game.start_hand();
+
+        assert!(game.holes[1].is_empty());
+        assert_eq!(game.betting.in_hand(), vec![0, 2]);
+        game.betting.act(Bet::Fold).unwrap();
+        assert_eq!(game.showdown(), vec![0, 0, 15]);
+        assert!(!game.win());
+    }
+
+    #[test]
+    fn chips_are_conserved()
+    {
+        let mut game = Holdem::new(6).unwrap();
+
+        for _ in 0..20
+        {
+            game.start_hand();
+            bot_round(&mut game);
+            for (_, count) in STREETS.iter()
+            {
+                if game.betting.hand_over() { break }
+                game.deal_street(*count);
+                bot_round(&mut game);
+            }
+            game.showdown();
+
+            assert_eq!(game.chips().iter().sum::<u32>(), STARTING_CHIPS * 6);
+        }
+    }
+
+    #[test]
+    fn hand_strength()
+    {
+        let aces = [card(14, Suit::Spade), card(14, Suit::Heart)];
+        let suited = [card(14, Suit::Spade), card(13, Suit::Spade)];
+        let rags = [card(7, Suit::Spade), card(2, Suit::Heart)];
+
+        assert!(preflop_strength(&aces) > preflop_strength(&suited));
+        assert!(preflop_strength(&suited) > preflop_strength(&rags));
+
+        let paired_board = [card(9, Suit::Club), card(9, Suit::Diamond), card(4, Suit::Heart)];
+        let dry_board = [card(14, Suit::Club), card(9, Suit::Diamond), card(4, Suit::Heart)];
+        assert!(postflop_strength(&rags, &paired_board) < postflop_strength(&suited, &dry_board));
+        assert!(postflop_strength(&aces, &dry_board) > postflop_strength(&suited, &dry_board));
+    }
+}
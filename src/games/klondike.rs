@@ -1,10 +1,7 @@
-#[path = "../utils/deck.rs"] mod deck;
-#[path = "../games/game.rs"] mod game;
-
-use deck::{Stack, Card, Suit};
+use crate::deck::{Stack, Card, Suit};
 use std::collections::HashMap;
 use text_io::read;
-use game::Game;
+use crate::game::Game;
 use std::fmt;
 use dialoguer::{
     Select,
@@ -61,13 +58,13 @@ impl Klondike
         while adding
         {
             adding = false;
-            for i in 0..COLUMNS
+            for (i, column) in columns.iter_mut().enumerate()
             {
-                if columns[i].len() < i + 1
+                if column.len() < i + 1
                 { 
                     let mut card = deck.draw();
-                    if columns[i].len() < i { card.set_visible(false) }
-                    columns[i].push(card); 
+                    if column.len() < i { card.set_visible(false) }
+                    column.push(card); 
                     adding = true;
 
                 }
@@ -87,7 +84,7 @@ impl Klondike
         }
     }
 
-    fn get_move_input(&self, toggle: bool) -> Option<MoveCommand>
+    fn get_move_input(&self) -> Option<MoveCommand>
     {
 
         let get_move_position = |min, max| {
@@ -109,19 +106,19 @@ impl Klondike
             {
                 println!("+ -------------------- + ");
                 match positions[index] {
-                    "Top" => return Some(MoveCommand { location: Position::Top, i: 1 }),
-                    "Stack" => return Some(MoveCommand { location: Position::Stack, i: 1 }),
-                    "Columns" => return Some(MoveCommand { location: Position::Columns, i: get_move_position(1,7) }),
-                    "Back" => return None,
+                    "Top" => Some(MoveCommand { location: Position::Top, i: 1 }),
+                    "Stack" => Some(MoveCommand { location: Position::Stack, i: 1 }),
+                    "Columns" => Some(MoveCommand { location: Position::Columns, i: get_move_position(1,7) }),
+                    "Back" => None,
                     _ => {
                         println!("Invalid selection");
-                        return self.get_move_input(toggle);
+                        self.get_move_input()
                     }
-                };
+                }
             },
             None => {
                 println!("Invalid selection");
-                return self.get_move_input(toggle);
+                self.get_move_input()
             }
         }
     }
@@ -134,7 +131,6 @@ impl Klondike
             Position::Stack => self.deck.top_card().unwrap(),
             Position::Top => return Ok(false)
         };
-        let opposite_suit = if move_card.color() { Suit::Club } else { Suit::Diamond };
         let to_card = match to.location
         {
             Position::Columns => self.columns[to.i].last(),
@@ -142,11 +138,11 @@ impl Klondike
             Position::Stack => return Ok(false)
         };
         match to.location {
-            Position::Stack => return Ok(false),
+            Position::Stack => Ok(false),
             Position::Columns => {
                 match to_card {
                     Some(c) => {
-                        return Ok((move_card.color() != c.color()) && (move_card.rank == c.rank - 1))
+                        Ok((move_card.color() != c.color()) && (move_card.rank == c.rank - 1))
                     },
                     None => Ok(move_card.rank == 13)
                 }
@@ -154,7 +150,7 @@ impl Klondike
             Position::Top => {
                 match to_card {
                     Some(c) => {
-                        return Ok((move_card.color() == c.color()) && (move_card.rank == c.rank - 1))
+                        Ok((move_card.color() == c.color()) && (move_card.rank == c.rank - 1))
                     },
                     None => Ok(move_card.rank == 14)
                 }
@@ -165,13 +161,13 @@ impl Klondike
 
     fn handle_move(&mut self) -> Result<(), String>
     {
-        let from = match self.get_move_input(true) { Some(e) => e, None => return Err(String::from("Going back"))};
-        let to = match self.get_move_input(false) { Some(e) => e, None => return Err(String::from("Going back"))};
+        let from = match self.get_move_input() { Some(e) => e, None => return Err(String::from("Going back"))};
+        let to = match self.get_move_input() { Some(e) => e, None => return Err(String::from("Going back"))};
     
         match self.is_move_valid(from, to)
         {
             Ok(v) => if !v { return Err(String::from("Unable to make that move")) },
-            Err(e) => return Err(String::from("Unable to make that move"))
+            Err(_) => return Err(String::from("Unable to make that move"))
         };
 
         let move_card = match from.location
@@ -228,13 +224,13 @@ impl fmt::Display for Klondike {
             let strings: Vec<String> = self.columns.iter().map(|c| c.get(i)).map(|c| {
                 match c {
                     Some(m) => format!("{}", m),
-                    None => format!("   ")
+                    None => "   ".to_string()
                 }
             }).collect();
             lines.push(strings.join(" | "));
         };
 
-        lines.push(format!("+ ------------------- +"));
+        lines.push("+ ------------------- +".to_string());
         write!(f, "{}", lines.join("\n"))
     }
 }
@@ -271,7 +267,7 @@ impl Game for Klondike
                     "Move" => {
                         self.handle_move().unwrap_or_else(|err| {
                             println!("{}", err);
-                            return self.handle_input()
+                            self.handle_input()
                         })
                     },
                     "Draw" => self.handle_draw(),
@@ -0,0 +1,446 @@
+use std::fmt;
+use crate::deck::{Stack, Card, Suit};
+use crate::trick::{StandardRules, Trick, TrickRound, sort_hand, bot_card};
+use crate::game::{Game, HUMAN, player_names, read_players_between};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const MIN_PLAYERS: usize = 3;
+const MAX_PLAYERS: usize = 7;
+const MAX_HAND: usize = 10;
+// Making a bid exactly scores this on top of a point a trick
+const BID_BONUS: usize = 10;
+
+// Hands grow by a card a round up to the most the deck allows, keeping one card back
+// to turn up for trumps, and then shrink back down to one
+pub fn hand_sizes(players: usize) -> Vec<usize>
+{
+    let most = MAX_HAND.min(51 / players);
+
+    (1..=most).chain((1..most).rev()).collect()
+}
+
+pub fn points(bid: usize, tricks: usize) -> usize
+{
+    if bid == tricks { BID_BONUS + bid } else { 0 }
+}
+
+// A trick for each ace and high trump, half a trick for kings with some cover and
+// for lower trumps
+fn bot_estimate(hand: &[Card], trump: Suit) -> usize
+{
+    let held = |suit: Suit| hand.iter().filter(|c| c.suit == suit).count();
+    let tricks: f64 = hand.iter().map(|c| match (c.suit == trump, c.rank) {
+        (true, 11..=14) => 1.0,
+        (true, _) => 0.5,
+        (false, 14) => 1.0,
+        (false, 13) if held(c.suit) >= 2 => 0.5,
+        _ => 0.0
+    }).sum();
+
+    (tricks.round() as usize).min(hand.len())
+}
+
+// Leads an ace when it still needs tricks and otherwise lets the trick engine's bot
+// take or duck as its bid requires
+fn bot_play(hand: &[Card], legal: &[usize], trick: &Trick, seat: usize, rules: &StandardRules, needs: bool) -> usize
+{
+    if needs && trick.plays.is_empty()
+    {
+        if let Some(ace) = legal.iter().find(|i| hand[**i].rank == 14 && Some(hand[**i].suit) != rules.trump)
+        {
+            return *ace
+        }
+    }
+
+    bot_card(hand, legal, trick, seat, rules, needs)
+}
+
+pub struct OhHell
+{
+    names: Vec<String>,
+    scores: Vec<usize>,
+    dealer: usize,
+    sizes: Vec<usize>,
+    deal_number: usize,
+    // Turned up after the deal to name trumps
+    turn_up: Card,
+    bids: Vec<Option<usize>>,
+    round: TrickRound,
+    leave: bool,
+}
+
+impl OhHell
+{
+    pub fn new(players: usize) -> Result<OhHell, String>
+    {
+        if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&players)
+        {
+            return Err(format!("Oh Hell is played by {} to {} players, {} provided", MIN_PLAYERS, MAX_PLAYERS, players))
+        }
+
+        let mut game = OhHell
+        {
+            names: player_names(players),
+            scores: vec![0; players],
+            dealer: players - 1,
+            sizes: hand_sizes(players),
+            deal_number: 0,
+            turn_up: "2c".parse().unwrap(),
+            bids: vec![None; players],
+            round: TrickRound::new(vec![vec![]; players], 0),
+            leave: false
+        };
+        game.deal();
+        Ok(game)
+    }
+
+    pub fn play()
+    {
+        let mut game = match OhHell::new(read_players_between(MIN_PLAYERS, MAX_PLAYERS)) {
+            Ok(game) => game,
+            Err(err) => return println!("{}", err)
+        };
+
+        while !game.leave && !game.game_over()
+        {
+            game.handle_input();
+        }
+
+        println!("{}", game.score_line());
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    fn players(&self) -> usize
+    {
+        self.names.len()
+    }
+
+    pub fn hand_size(&self) -> usize
+    {
+        self.sizes[self.deal_number]
+    }
+
+    fn rules(&self) -> StandardRules
+    {
+        StandardRules { trump: Some(self.turn_up.suit) }
+    }
+
+    // The player left of the dealer bids first and leads the first trick
+    pub fn deal(&mut self)
+    {
+        let n = self.players();
+        let mut deck = Stack::new_deck(false);
+        deck.shuffle();
+
+        let mut hands: Vec<Vec<Card>> = (0..n).map(|_| deck.deal(self.hand_size())).collect();
+        self.turn_up = deck.draw();
+        let rules = self.rules();
+        for hand in hands.iter_mut()
+        {
+            sort_hand(hand, &rules);
+        }
+
+        self.round = TrickRound::new(hands, (self.dealer + 1) % n);
+        self.bids = vec![None; n];
+    }
+
+    fn to_bid(&self) -> Option<usize>
+    {
+        let bids = self.bids.iter().filter(|b| b.is_some()).count();
+
+        if bids == self.players() { None } else { Some((self.dealer + 1 + bids) % self.players()) }
+    }
+
+    // The hook: the dealer bids last and can't make the bids add up to the tricks
+    // there are, so somebody has to miss
+    pub fn forbidden_bid(&self) -> Option<usize>
+    {
+        if self.to_bid() != Some(self.dealer) { return None }
+
+        let total: usize = self.bids.iter().flatten().sum();
+        self.hand_size().checked_sub(total)
+    }
+
+    pub fn bid(&mut self, bid: usize) -> Result<(), String>
+    {
+        let seat = self.to_bid().ok_or_else(|| String::from("Bidding is over"))?;
+        if bid > self.hand_size()
+        {
+            return Err(format!("Bid between 0 and {} tricks", self.hand_size()))
+        }
+        if self.forbidden_bid() == Some(bid)
+        {
+            return Err(format!("The dealer can't bid {}, the bids would add up to the tricks", bid))
+        }
+
+        println!("{} bid {}", self.names[seat], bid);
+        self.bids[seat] = Some(bid);
+        Ok(())
+    }
+
+    fn bot_bid(&self, seat: usize) -> usize
+    {
+        let bid = bot_estimate(&self.round.hands[seat], self.turn_up.suit);
+
+        match self.forbidden_bid() {
+            Some(forbidden) if forbidden == bid && bid < self.hand_size() => bid + 1,
+            Some(forbidden) if forbidden == bid => bid - 1,
+            _ => bid
+        }
+    }
+
+    pub fn play_card(&mut self, index: usize) -> Result<Option<usize>, String>
+    {
+        let seat = self.round.to_play();
+        let card = *self.round.hands[seat].get(index).ok_or_else(|| String::from("Unable to play that card"))?;
+        let winner = self.round.play(index, &self.rules())?;
+
+        println!("{}: {}", self.names[seat], card);
+        if let Some(winner) = winner
+        {
+            println!("{} took the trick", self.names[winner]);
+        }
+
+        Ok(winner)
+    }
+
+    // Scores every seat and passes the deal on. Returns each seat's points for the round.
+    pub fn score_round(&mut self) -> Vec<usize>
+    {
+        let round_points: Vec<usize> = (0..self.players()).map(|s| points(self.bids[s].unwrap(), self.round.tricks_won[s])).collect();
+
+        for (score, points) in self.scores.iter_mut().zip(round_points.iter())
+        {
+            *score += points;
+        }
+        self.dealer = (self.dealer + 1) % self.players();
+        self.deal_number += 1;
+
+        round_points
+    }
+
+    pub fn game_over(&self) -> bool
+    {
+        self.deal_number >= self.sizes.len()
+    }
+
+    fn get_bid(&self) -> usize
+    {
+        println!("{}", self);
+        let bids: Vec<usize> = (0..=self.hand_size()).filter(|b| self.forbidden_bid() != Some(*b)).collect();
+        let options: Vec<String> = bids.iter().map(|b| b.to_string()).collect();
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Your bid")
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        bids[selection.unwrap_or(0)]
+    }
+
+    fn bidding(&mut self)
+    {
+        while let Some(seat) = self.to_bid()
+        {
+            let bid = if seat == HUMAN { self.get_bid() } else { self.bot_bid(seat) };
+            self.bid(bid).unwrap_or_else(|err| println!("{}", err));
+        }
+    }
+
+    fn play_bots(&mut self)
+    {
+        while !self.round.finished() && self.round.to_play() != HUMAN
+        {
+            let seat = self.round.to_play();
+            let needs = self.round.tricks_won[seat] < self.bids[seat].unwrap();
+            let rules = self.rules();
+            let index = bot_play(&self.round.hands[seat], &self.round.legal(&rules), &self.round.trick, seat, &rules, needs);
+            self.play_card(index).unwrap();
+        }
+    }
+
+    fn get_play(&mut self)
+    {
+        let legal = self.round.legal(&self.rules());
+        let mut options: Vec<String> = legal.iter().map(|i| self.round.hands[HUMAN][*i].to_string()).collect();
+        options.push("Leave".to_string());
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Play a card")
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) if index == legal.len() => self.leave = true,
+            Some(index) => { self.play_card(legal[index]).unwrap_or_else(|err| { println!("{}", err); None }); },
+            None => println!("User did not select anything")
+        }
+    }
+
+    fn score_line(&self) -> String
+    {
+        let scores: Vec<String> = (0..self.players()).map(|s| format!("{} {}", self.names[s], self.scores[s])).collect();
+        format!("Scores: {}", scores.join(", "))
+    }
+}
+
+impl fmt::Display for OhHell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let trick: Vec<String> = self.round.trick.plays.iter().map(|(seat, card)| format!("{}: {}", self.names[*seat], card)).collect();
+        let hand: Vec<String> = self.round.hands[HUMAN].iter().map(|c| c.to_string()).collect();
+
+        let mut lines = vec![
+            format!("+ --- Deal {} of {}: {} cards, trumps {} ({}) --- +", self.deal_number + 1, self.sizes.len(), self.hand_size(), self.turn_up.suit, self.turn_up),
+            self.score_line()
+        ];
+        for seat in 0..self.players()
+        {
+            let bid = self.bids[seat].map_or(String::from("-"), |b| b.to_string());
+            let dealer = if seat == self.dealer { " (dealer)" } else { "" };
+            lines.push(format!("{:<6} bid {:<2} took {}{}", self.names[seat], bid, self.round.tricks_won[seat], dealer));
+        }
+        lines.push(format!("Trick: {}", trick.join(" | ")));
+        lines.push(format!("Your hand: {}", hand.join(" ")));
+        lines.push("+ ------------------- +".to_string());
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for OhHell
+{
+    fn win(&self) -> bool
+    {
+        self.game_over() && self.scores.iter().all(|s| *s <= self.scores[HUMAN])
+    }
+
+    fn handle_input(&mut self)
+    {
+        if self.to_bid().is_some()
+        {
+            self.bidding();
+            return
+        }
+
+        self.play_bots();
+        if !self.round.finished()
+        {
+            println!("{}", self);
+            self.get_play();
+            self.play_bots();
+        }
+
+        if self.round.finished()
+        {
+            let points: Vec<String> = self.score_round().iter().enumerate().map(|(s, p)| format!("{} {}", self.names[s], p)).collect();
+            println!("This round: {}", points.join(", "));
+            if !self.game_over()
+            {
+                self.deal();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    #[test]
+    fn hand_sizes_and_points()
+    {
+        assert_eq!(hand_sizes(4), vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(hand_sizes(7), vec![1, 2, 3, 4, 5, 6, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(points(3, 3), 13);
+        assert_eq!(points(0, 0), 10);
+        assert_eq!(points(2, 3), 0);
+    }
+
+    #[test]
+    fn hook()
+    {
+        let mut game = OhHell::new(3).unwrap();
+        game.deal_number = 2;
+        game.deal();
+
+        assert_eq!(game.to_bid(), Some(0));
+        assert!(game.bid(4).is_err());
+        game.bid(1).unwrap();
+        assert_eq!(game.forbidden_bid(), None);
+        game.bid(1).unwrap();
+        assert_eq!(game.forbidden_bid(), Some(1));
+        assert!(game.bid(1).is_err());
+
+        // A bot dealer holding one trick's worth bids two rather than one
+        game.round.hands[2] = parse_cards("As3d4d").unwrap();
+        game.turn_up = "2h".parse().unwrap();
+        assert_eq!(game.bot_bid(2), 2);
+        game.bid(0).unwrap();
+        assert_eq!(game.to_bid(), None);
+    }
+
+    #[test]
+    fn scoring_rounds()
+    {
+        let mut game = OhHell::new(3).unwrap();
+        game.bids = vec![Some(1), Some(0), Some(1)];
+        game.round.tricks_won = vec![1, 1, 0];
+
+        assert_eq!(game.score_round(), vec![11, 0, 0]);
+        assert_eq!((game.dealer, game.deal_number), (0, 1));
+    }
+
+    #[test]
+    fn bot_plays()
+    {
+        let rules = StandardRules { trump: Some(Suit::Heart) };
+        let hand = parse_cards("AcKc2h").unwrap();
+        let trick = Trick::new(0);
+
+        assert_eq!(bot_play(&hand, &[0, 1, 2], &trick, 0, &rules, true), 0);
+        assert_eq!(bot_estimate(&hand, Suit::Heart), 2);
+        assert_eq!(bot_estimate(&parse_cards("Kc5d").unwrap(), Suit::Heart), 0);
+    }
+
+    #[test]
+    fn bots_play_a_game()
+    {
+        let mut game = OhHell::new(5).unwrap();
+
+        while !game.game_over()
+        {
+            while let Some(seat) = game.to_bid()
+            {
+                game.bid(game.bot_bid(seat)).unwrap();
+            }
+            assert_ne!(game.bids.iter().flatten().sum::<usize>(), game.hand_size());
+
+            while !game.round.finished()
+            {
+                let seat = game.round.to_play();
+                let rules = game.rules();
+                let needs = game.round.tricks_won[seat] < game.bids[seat].unwrap();
+                let index = bot_play(&game.round.hands[seat], &game.round.legal(&rules), &game.round.trick, seat, &rules, needs);
+                game.play_card(index).unwrap();
+            }
+            assert_eq!(game.round.tricks_played(), game.hand_size());
+            game.score_round();
+            if !game.game_over() { game.deal() }
+        }
+        assert_eq!(game.deal_number, 19);
+    }
+}
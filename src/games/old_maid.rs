@@ -0,0 +1,294 @@
+use rand::{thread_rng, Rng, seq::SliceRandom};
+use crate::deck::{Stack, Card, Suit};
+use crate::game::{Game, HiddenHands, HUMAN, player_names, read_players};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const MAX_PLAYERS: usize = 6;
+
+// Throws out every pair in a hand, returning the ranks paired off
+fn discard_pairs(hand: &mut Vec<Card>) -> Vec<u8>
+{
+    let mut paired = vec![];
+
+    for rank in 2..=14
+    {
+        let count = hand.iter().filter(|c| c.rank == rank).count();
+        for _ in 0..count / 2
+        {
+            for _ in 0..2
+            {
+                let i = hand.iter().position(|c| c.rank == rank).unwrap();
+                hand.remove(i);
+            }
+            paired.push(rank);
+        }
+    }
+    paired
+}
+
+pub struct OldMaid
+{
+    names: Vec<String>,
+    hands: Vec<Vec<Card>>,
+    pairs: Vec<usize>,
+    to_play: usize,
+    leave: bool,
+}
+
+impl OldMaid
+{
+    // The queen of clubs comes out, leaving the queen of spades without a pair
+    pub fn new(players: usize) -> Result<OldMaid, String>
+    {
+        if !(2..=MAX_PLAYERS).contains(&players)
+        {
+            return Err(format!("Old Maid is played by 2 to {} players, {} provided", MAX_PLAYERS, players))
+        }
+
+        let mut deck = Stack::new_deck(false);
+        deck.shuffle();
+        let mut hands = vec![vec![]; players];
+        let cards = deck.deal(deck.size()).into_iter().filter(|c| !(c.rank == 12 && c.suit == Suit::Club));
+        for (i, card) in cards.enumerate()
+        {
+            hands[i % players].push(card);
+        }
+
+        let mut game = OldMaid { names: player_names(players), hands, pairs: vec![0; players], to_play: 0, leave: false };
+        for seat in 0..players
+        {
+            game.pairs[seat] += discard_pairs(&mut game.hands[seat]).len();
+        }
+        game.to_play = game.next_seat(players - 1);
+        Ok(game)
+    }
+
+    pub fn play()
+    {
+        let mut game = match OldMaid::new(read_players(MAX_PLAYERS)) {
+            Ok(game) => game,
+            Err(err) => return println!("{}", err)
+        };
+
+        while !game.leave && game.loser().is_none()
+        {
+            game.handle_input();
+        }
+
+        if let Some(loser) = game.loser()
+        {
+            println!("{} {} left holding the old maid", game.names[loser], if loser == HUMAN { "are" } else { "is" });
+        }
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    // The next seat after `seat` that still holds cards
+    fn next_seat(&self, seat: usize) -> usize
+    {
+        let n = self.hands.len();
+        (1..=n).map(|step| (seat + step) % n).find(|s| !self.hands[*s].is_empty()).unwrap_or(seat)
+    }
+
+    // Whoever the player to play draws from
+    pub fn neighbour(&self) -> usize
+    {
+        self.next_seat(self.to_play)
+    }
+
+    // Once everything else has been paired off, the last player holding a card has
+    // the old maid
+    pub fn loser(&self) -> Option<usize>
+    {
+        let mut left = (0..self.hands.len()).filter(|s| !self.hands[*s].is_empty());
+
+        match (left.next(), left.next()) {
+            (Some(seat), None) => Some(seat),
+            _ => None
+        }
+    }
+
+    // Draws the card at `index` from the neighbour's hand, pairs it off if it can,
+    // and passes the turn on. The neighbour's hand is shuffled after, so where a
+    // card sits gives nothing away.
+    pub fn draw(&mut self, index: usize) -> Result<(Card, bool), String>
+    {
+        let seat = self.to_play;
+        let from = self.neighbour();
+        if from == seat || index >= self.hands[from].len()
+        {
+            return Err(String::from("Unable to draw that card"))
+        }
+
+        let card = self.hands[from].remove(index);
+        self.hands[from].shuffle(&mut thread_rng());
+        self.hands[seat].push(card);
+        let paired = discard_pairs(&mut self.hands[seat]).len();
+        self.pairs[seat] += paired;
+
+        self.to_play = self.next_seat(seat);
+        Ok((card, paired > 0))
+    }
+
+    fn report(&self, seat: usize, from: usize, card: Card, paired: bool)
+    {
+        let drew = if seat == HUMAN || from == HUMAN { card.to_string() } else { "a card".to_string() };
+        let paired = if paired { " and made a pair" } else { "" };
+
+        println!("{} drew {} from {}{}", self.names[seat], drew, self.names[from], paired);
+    }
+
+    fn take_turn(&mut self, index: usize)
+    {
+        let (seat, from) = (self.to_play, self.neighbour());
+
+        match self.draw(index) {
+            Ok((card, paired)) => self.report(seat, from, card, paired),
+            Err(err) => println!("{}", err)
+        }
+    }
+}
+
+impl HiddenHands for OldMaid
+{
+    fn to_play(&self) -> usize
+    {
+        self.to_play
+    }
+
+    fn over(&self) -> bool
+    {
+        self.loser().is_some()
+    }
+
+    fn view(&self, seat: usize) -> String
+    {
+        let mut lines = vec!["+ --- Old Maid --- +".to_string()];
+        for s in (0..self.hands.len()).filter(|s| *s != seat)
+        {
+            lines.push(format!("{:<6} {} cards, {} pairs", self.names[s], self.hands[s].len(), self.pairs[s]));
+        }
+        let mut hand = self.hands[seat].clone();
+        hand.sort_by_key(|c| c.rank);
+        let hand: Vec<String> = hand.iter().map(|c| c.to_string()).collect();
+        lines.push(format!("Your hand: {}, {} pairs", hand.join(" "), self.pairs[seat]));
+
+        lines.join("\n")
+    }
+
+    // Bots draw blind, so any card will do
+    fn bot_turn(&mut self)
+    {
+        let index = thread_rng().gen_range(0..self.hands[self.neighbour()].len());
+        self.take_turn(index);
+    }
+
+    fn human_turn(&mut self)
+    {
+        let from = self.neighbour();
+        let mut options: Vec<String> = (1..=self.hands[from].len()).map(|i| format!("Card {}", i)).collect();
+        options.push("Leave".to_string());
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Draw from {}", self.names[from]))
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) if index < self.hands[from].len() => self.take_turn(index),
+            Some(_) => self.leave = true,
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+impl Game for OldMaid
+{
+    fn win(&self) -> bool
+    {
+        self.loser().is_some_and(|loser| loser != HUMAN)
+    }
+
+    fn handle_input(&mut self)
+    {
+        self.take_turns();
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    fn deal(hands: &[&str]) -> OldMaid
+    {
+        let mut game = OldMaid::new(hands.len()).unwrap();
+        game.hands = hands.iter().map(|h| parse_cards(h).unwrap()).collect();
+        game.to_play = 0;
+        game
+    }
+
+    #[test]
+    fn dealing()
+    {
+        let game = OldMaid::new(4).unwrap();
+        let held: Vec<Card> = game.hands.iter().flatten().copied().collect();
+
+        assert_eq!(held.len() + 2 * game.pairs.iter().sum::<usize>(), 51);
+        assert_eq!(held.iter().filter(|c| c.rank == 12).count() % 2, 1);
+        for hand in game.hands.iter()
+        {
+            assert!((2..=14).all(|rank| hand.iter().filter(|c| c.rank == rank).count() < 2));
+        }
+    }
+
+    #[test]
+    fn pairing()
+    {
+        let mut hand = parse_cards("5s5h5dQs2c2d").unwrap();
+        assert_eq!(discard_pairs(&mut hand), vec![2, 5]);
+        assert_eq!(hand, parse_cards("5dQs").unwrap());
+    }
+
+    #[test]
+    fn drawing()
+    {
+        let mut game = deal(&["5s9c", "", "5hQs"]);
+
+        assert_eq!(game.neighbour(), 2);
+        let (card, paired) = game.draw(0).unwrap();
+        assert_eq!((card, paired), ("5h".parse().unwrap(), true));
+        assert_eq!(game.hands[0], parse_cards("9c").unwrap());
+        assert_eq!(game.to_play, 2);
+        assert!(game.draw(5).is_err());
+        assert_eq!(game.loser(), None);
+
+        let mut game = deal(&["9c", "Qs9h"]);
+        game.draw(1).unwrap();
+        assert_eq!(game.loser(), Some(1));
+        assert!(game.win());
+    }
+
+    #[test]
+    fn hidden_hands()
+    {
+        let game = deal(&["5s9c", "Qs"]);
+
+        assert!(game.view(0).contains("9"));
+        assert!(!game.view(0).contains(&"Qs".parse::<Card>().unwrap().to_string()));
+        assert!(game.view(1).contains(&"Qs".parse::<Card>().unwrap().to_string()));
+        assert!(!game.over());
+    }
+}
@@ -0,0 +1,636 @@
+use std::fmt;
+use crate::deck::{Stack, Card, Suit};
+use crate::trick::{TrickRules, Trick, TrickRound, sort_hand, bot_card};
+use crate::game::{Game, HUMAN};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const PLAYERS: usize = 4;
+const HAND_SIZE: usize = 12;
+const MIN_BID: u32 = 250;
+const BID_STEP: u32 = 10;
+const GAME_OVER: i32 = 1500;
+const LAST_TRICK: u32 = 10;
+const SUITS: [Suit; 4] = [Suit::Spade, Suit::Heart, Suit::Club, Suit::Diamond];
+// What a bot counts on its partner bringing in meld and counters
+const PARTNER: u32 = 100;
+
+// Aces rank highest, then tens above kings, queens, jacks and nines. When both copies
+// of a card land in one trick the first one played takes it. A player out of the suit
+// led has to trump, and has to beat the winning card whenever they can.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PinochleRules
+{
+    pub trump: Option<Suit>,
+}
+
+impl TrickRules for PinochleRules {
+    fn trump(&self) -> Option<Suit> {
+        self.trump
+    }
+
+    fn card_order(&self, card: &Card) -> u8 {
+        match card.rank {
+            14 => 6,
+            10 => 5,
+            13 => 4,
+            12 => 3,
+            11 => 2,
+            _ => 1
+        }
+    }
+
+    fn must_trump(&self) -> bool {
+        true
+    }
+
+    fn must_head(&self) -> bool {
+        true
+    }
+}
+
+// Hands are sorted without a trump until the auction is won
+const NO_TRUMP: PinochleRules = PinochleRules { trump: None };
+
+fn team(seat: usize) -> usize
+{
+    seat % 2
+}
+
+// Aces, tens and kings are the counters, worth ten each
+pub fn counters(cards: &[Card]) -> u32
+{
+    cards.iter().filter(|c| [14, 10, 13].contains(&c.rank)).count() as u32 * 10
+}
+
+// Meld for a hand once `trump` is named. A card can count once in each kind of meld,
+// so the king of a run can't also make a royal marriage but can go around the kings.
+pub fn meld(hand: &[Card], trump: Suit) -> u32
+{
+    let count = |suit: Suit, rank: u8| hand.iter().filter(|c| c.suit == suit && c.rank == rank).count() as u32;
+    let single_or_double = |n: u32, single: u32| match n { 0 => 0, 1 => single, _ => single * 10 };
+
+    let runs = [14, 10, 13, 12, 11].iter().map(|r| count(trump, *r)).min().unwrap();
+    let run = match runs { 0 => 0, 1 => 150, _ => 1500 };
+    let marriages: u32 = SUITS.iter().map(|s| {
+        let pairs = count(*s, 13).min(count(*s, 12));
+        if *s == trump { (pairs - runs) * 40 } else { pairs * 20 }
+    }).sum();
+    let dix = count(trump, 9) * 10;
+    let pinochle = match count(Suit::Spade, 12).min(count(Suit::Diamond, 11)) { 0 => 0, 1 => 40, _ => 300 };
+    let arounds: u32 = [(14, 100), (13, 80), (12, 60), (11, 40)].iter()
+        .map(|(rank, single)| single_or_double(SUITS.iter().map(|s| count(*s, *rank)).min().unwrap(), *single))
+        .sum();
+
+    run + marriages + dix + pinochle + arounds
+}
+
+// The suit a bot would name and what it reckons the hand is worth with it: its meld,
+// twenty for each trump and ten for each ace
+fn bot_value(hand: &[Card]) -> (Suit, u32)
+{
+    let aces = hand.iter().filter(|c| c.rank == 14).count() as u32 * 10;
+
+    SUITS.iter()
+        .map(|s| (*s, meld(hand, *s) + hand.iter().filter(|c| c.suit == *s).count() as u32 * 20 + aces))
+        .max_by_key(|(_, value)| *value)
+        .unwrap()
+}
+
+// Cashes aces when leading, feeds counters to a partner who has the trick and
+// otherwise tries to take it as cheaply as possible
+fn bot_play(hand: &[Card], legal: &[usize], trick: &Trick, seat: usize, rules: &PinochleRules) -> usize
+{
+    if trick.plays.is_empty()
+    {
+        if let Some(ace) = legal.iter().find(|i| hand[**i].rank == 14 && Some(hand[**i].suit) != rules.trump)
+        {
+            return *ace
+        }
+    }
+    let partner_winning = trick.winner(rules) == Some((seat + 2) % PLAYERS);
+
+    bot_card(hand, legal, trick, seat, rules, !partner_winning)
+}
+
+pub struct Pinochle
+{
+    names: Vec<String>,
+    // Scores by team, with the human's team first
+    scores: Vec<i32>,
+    dealer: usize,
+    // The seat to act in the auction, and the highest bid so far with who made it
+    turn: usize,
+    high_bid: Option<(usize, u32)>,
+    passed: Vec<bool>,
+    trump: Option<Suit>,
+    melds: Vec<u32>,
+    round: TrickRound,
+    leave: bool,
+}
+
+impl Pinochle
+{
+    pub fn new() -> Pinochle
+    {
+        let names = ["You", "Bot 1", "Partner", "Bot 3"].iter().map(|n| n.to_string()).collect();
+        let mut game = Pinochle
+        {
+            names,
+            scores: vec![0; 2],
+            dealer: PLAYERS - 1,
+            turn: 0,
+            high_bid: None,
+            passed: vec![false; PLAYERS],
+            trump: None,
+            melds: vec![0; PLAYERS],
+            round: TrickRound::new(vec![vec![]; PLAYERS], 0),
+            leave: false
+        };
+
+        game.deal();
+        game
+    }
+
+    pub fn play()
+    {
+        let mut game = Pinochle::new();
+
+        while !game.leave && !game.game_over()
+        {
+            game.handle_input();
+        }
+
+        println!("{}", game.score_lines().join("\n"));
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    fn rules(&self) -> PinochleRules
+    {
+        PinochleRules { trump: self.trump }
+    }
+
+    // The player left of the dealer opens the auction
+    pub fn deal(&mut self)
+    {
+        let mut deck = Stack::new_partial_decks(2, 9..=14);
+        deck.shuffle();
+
+        let hands = (0..PLAYERS).map(|_| {
+            let mut hand = deck.deal(HAND_SIZE);
+            sort_hand(&mut hand, &NO_TRUMP);
+            hand
+        }).collect();
+
+        self.round = TrickRound::new(hands, (self.dealer + 1) % PLAYERS);
+        self.turn = (self.dealer + 1) % PLAYERS;
+        self.high_bid = None;
+        self.passed = vec![false; PLAYERS];
+        self.trump = None;
+        self.melds = vec![0; PLAYERS];
+    }
+
+    // The auction ends once everyone but the high bidder has passed
+    fn to_bid(&self) -> Option<usize>
+    {
+        let passes = self.passed.iter().filter(|p| **p).count();
+
+        if passes == PLAYERS - 1 && self.high_bid.is_some() { None } else { Some(self.turn) }
+    }
+
+    pub fn declarer(&self) -> Option<usize>
+    {
+        if self.to_bid().is_some() { None } else { self.high_bid.map(|(seat, _)| seat) }
+    }
+
+    fn min_bid(&self) -> u32
+    {
+        self.high_bid.map_or(MIN_BID, |(_, bid)| bid + BID_STEP)
+    }
+
+    // If everyone else passes the dealer is stuck and has to open at the minimum
+    fn stuck(&self) -> bool
+    {
+        self.high_bid.is_none() && self.passed.iter().filter(|p| **p).count() == PLAYERS - 1
+    }
+
+    fn next_turn(&mut self)
+    {
+        if let Some(seat) = (1..=PLAYERS).map(|i| (self.turn + i) % PLAYERS).find(|s| !self.passed[*s])
+        {
+            self.turn = seat;
+        }
+    }
+
+    pub fn bid(&mut self, bid: u32) -> Result<(), String>
+    {
+        let seat = self.to_bid().ok_or_else(|| String::from("The auction is over"))?;
+        if bid < self.min_bid() || !bid.is_multiple_of(BID_STEP)
+        {
+            return Err(format!("Bid {} or more in steps of {}", self.min_bid(), BID_STEP))
+        }
+
+        println!("{} bid {}", self.names[seat], bid);
+        self.high_bid = Some((seat, bid));
+        self.next_turn();
+        Ok(())
+    }
+
+    pub fn pass(&mut self) -> Result<(), String>
+    {
+        let seat = self.to_bid().ok_or_else(|| String::from("The auction is over"))?;
+        if self.stuck()
+        {
+            return Err(format!("Everyone else passed, so the dealer has to bid at least {}", MIN_BID))
+        }
+
+        println!("{} passed", self.names[seat]);
+        self.passed[seat] = true;
+        self.next_turn();
+        Ok(())
+    }
+
+    // The declarer names trumps, everyone lays down their meld, and the declarer
+    // leads the first trick
+    pub fn name_trump(&mut self, suit: Suit) -> Result<(), String>
+    {
+        let declarer = self.declarer().ok_or_else(|| String::from("The auction isn't over"))?;
+        if self.trump.is_some()
+        {
+            return Err(String::from("Trumps have already been named"))
+        }
+
+        println!("{} named {} trumps", self.names[declarer], suit);
+        self.trump = Some(suit);
+        let rules = self.rules();
+        let mut hands = std::mem::take(&mut self.round.hands);
+        for (seat, hand) in hands.iter_mut().enumerate()
+        {
+            sort_hand(hand, &rules);
+            self.melds[seat] = meld(hand, suit);
+            println!("{} melds {}", self.names[seat], self.melds[seat]);
+        }
+        self.round = TrickRound::new(hands, declarer);
+        Ok(())
+    }
+
+    pub fn play_card(&mut self, index: usize) -> Result<Option<usize>, String>
+    {
+        let seat = self.round.to_play();
+        let card = *self.round.hands[seat].get(index).ok_or_else(|| String::from("Unable to play that card"))?;
+        let winner = self.round.play(index, &self.rules())?;
+
+        println!("{}: {}", self.names[seat], card);
+        if let Some(winner) = winner
+        {
+            println!("{} took the trick", self.names[winner]);
+        }
+
+        Ok(winner)
+    }
+
+    // A team's meld and the counters it took, with ten more for the last trick. Meld
+    // only stands for a team that takes something in the tricks.
+    fn team_points(&self, us: usize) -> (u32, u32)
+    {
+        let last = self.round.last_trick.as_ref().and_then(|t| t.winner(&self.rules()));
+        let seats = [us, us + 2];
+        let mut took: u32 = seats.iter().map(|s| counters(&self.round.taken[*s])).sum();
+        if last.is_some_and(|seat| team(seat) == us)
+        {
+            took += LAST_TRICK;
+        }
+        let meld = if took > 0 { seats.iter().map(|s| self.melds[*s]).sum() } else { 0 };
+
+        (meld, took)
+    }
+
+    // The declaring team has to make its bid in meld and counters or loses the bid,
+    // the other team scores whatever it makes. Returns each team's points for the round.
+    pub fn score_round(&mut self) -> Vec<i32>
+    {
+        let (declarer, bid) = self.high_bid.unwrap();
+        let mut round_points = vec![];
+
+        for us in 0..2
+        {
+            let (meld, took) = self.team_points(us);
+            let made = (meld + took) as i32;
+            let points = if team(declarer) == us && made < bid as i32 { -(bid as i32) } else { made };
+
+            self.scores[us] += points;
+            round_points.push(points);
+        }
+        self.dealer = (self.dealer + 1) % PLAYERS;
+
+        round_points
+    }
+
+    pub fn game_over(&self) -> bool
+    {
+        let high = *self.scores.iter().max().unwrap();
+        let low = *self.scores.iter().min().unwrap();
+
+        high >= GAME_OVER && high != low
+    }
+
+    fn bot_bid(&mut self, seat: usize)
+    {
+        let (_, value) = bot_value(&self.round.hands[seat]);
+        let result = if self.stuck() || value + PARTNER >= self.min_bid() { self.bid(self.min_bid()) } else { self.pass() };
+
+        result.unwrap_or_else(|err| println!("{}", err));
+    }
+
+    fn get_bid(&mut self)
+    {
+        println!("{}", self);
+        let bids: Vec<u32> = (0..10).map(|i| self.min_bid() + i * BID_STEP).collect();
+        let mut options: Vec<String> = bids.iter().map(|b| b.to_string()).collect();
+        if !self.stuck()
+        {
+            options.insert(0, "Pass".to_string());
+        }
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Your bid")
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        let result = match selection {
+            Some(0) if !self.stuck() => self.pass(),
+            Some(index) if !self.stuck() => self.bid(bids[index - 1]),
+            Some(index) => self.bid(bids[index]),
+            None => { println!("User did not select anything"); Ok(()) }
+        };
+        result.unwrap_or_else(|err| println!("{}", err));
+    }
+
+    fn auction(&mut self)
+    {
+        while let Some(seat) = self.to_bid()
+        {
+            if seat == HUMAN
+            {
+                self.get_bid();
+            }
+            else
+            {
+                self.bot_bid(seat);
+            }
+        }
+    }
+
+    fn get_trump(&self) -> Suit
+    {
+        println!("{}", self);
+        let options: Vec<String> = SUITS.iter().map(|s| format!("{} (meld {})", s, meld(&self.round.hands[HUMAN], *s))).collect();
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Name trumps")
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        SUITS[selection.unwrap_or(0)]
+    }
+
+    fn play_bots(&mut self)
+    {
+        while !self.round.finished() && self.round.to_play() != HUMAN
+        {
+            let seat = self.round.to_play();
+            let rules = self.rules();
+            let index = bot_play(&self.round.hands[seat], &self.round.legal(&rules), &self.round.trick, seat, &rules);
+            self.play_card(index).unwrap();
+        }
+    }
+
+    fn get_play(&mut self)
+    {
+        let legal = self.round.legal(&self.rules());
+        let mut options: Vec<String> = legal.iter().map(|i| self.round.hands[HUMAN][*i].to_string()).collect();
+        options.push("Leave".to_string());
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Play a card")
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) if index == legal.len() => self.leave = true,
+            Some(index) => { self.play_card(legal[index]).unwrap_or_else(|err| { println!("{}", err); None }); },
+            None => println!("User did not select anything")
+        }
+    }
+
+    fn score_lines(&self) -> Vec<String>
+    {
+        vec![
+            format!("You and Partner {:>5}", self.scores[0]),
+            format!("Bots 1 and 3    {:>5}", self.scores[1])
+        ]
+    }
+}
+
+impl fmt::Display for Pinochle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let trick: Vec<String> = self.round.trick.plays.iter().map(|(seat, card)| format!("{}: {}", self.names[*seat], card)).collect();
+        let hand: Vec<String> = self.round.hands[HUMAN].iter().map(|c| c.to_string()).collect();
+
+        let mut lines = vec!["+ --- Scores --- +".to_string()];
+        lines.extend(self.score_lines());
+        lines.push("+ ------------------- +".to_string());
+        match (self.high_bid, self.trump) {
+            (Some((seat, bid)), Some(trump)) => lines.push(format!("{} bid {} with {} trumps", self.names[seat], bid, trump)),
+            (Some((seat, bid)), None) => lines.push(format!("High bid: {} by {}", bid, self.names[seat])),
+            _ => lines.push("No bids yet".to_string())
+        }
+        if self.trump.is_some()
+        {
+            for seat in 0..PLAYERS
+            {
+                lines.push(format!("{:<8} meld {:<5} took {}", self.names[seat], self.melds[seat], counters(&self.round.taken[seat])));
+            }
+        }
+        lines.push(format!("Trick: {}", trick.join(" | ")));
+        lines.push(format!("Your hand: {}", hand.join(" ")));
+        lines.push("+ ------------------- +".to_string());
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Pinochle
+{
+    fn win(&self) -> bool
+    {
+        self.game_over() && self.scores[0] > self.scores[1]
+    }
+
+    fn handle_input(&mut self)
+    {
+        if self.to_bid().is_some()
+        {
+            self.auction();
+            return
+        }
+        if self.trump.is_none()
+        {
+            let declarer = self.declarer().unwrap();
+            let suit = if declarer == HUMAN { self.get_trump() } else { bot_value(&self.round.hands[declarer]).0 };
+            self.name_trump(suit).unwrap();
+            return
+        }
+
+        self.play_bots();
+        if !self.round.finished()
+        {
+            println!("{}", self);
+            self.get_play();
+            self.play_bots();
+        }
+
+        if self.round.finished()
+        {
+            let points = self.score_round();
+            println!("You and Partner scored {}, the bots scored {}", points[0], points[1]);
+            if !self.game_over()
+            {
+                self.deal();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+    use crate::trick::legal_plays;
+
+    fn hand(cards: &str) -> Vec<Card>
+    {
+        parse_cards(cards).unwrap()
+    }
+
+    #[test]
+    fn melds()
+    {
+        assert_eq!(meld(&hand("AsTsKsQsJs9s"), Suit::Spade), 160);
+        assert_eq!(meld(&hand("AsTsKsQsJs9sJdKsQs"), Suit::Spade), 240);
+        assert_eq!(meld(&hand("KhQh"), Suit::Spade), 20);
+        assert_eq!(meld(&hand("KhQh"), Suit::Heart), 40);
+        assert_eq!(meld(&hand("QsQsJdJd"), Suit::Club), 300);
+        assert_eq!(meld(&hand("AsAhAcAd"), Suit::Club), 100);
+        assert_eq!(meld(&hand("AsAhAcAdAsAhAcAd"), Suit::Club), 1000);
+        assert_eq!(meld(&hand("KsKhKcKdQs"), Suit::Club), 100);
+    }
+
+    #[test]
+    fn tricks()
+    {
+        let rules = PinochleRules { trump: Some(Suit::Heart) };
+        let mut trick = Trick::new(0);
+        trick.plays = vec![(0, "Ks".parse().unwrap()), (1, "Ts".parse().unwrap()), (2, "Ts".parse().unwrap())];
+        assert_eq!(trick.winner(&rules), Some(1));
+
+        assert_eq!(legal_plays(&hand("Ac9h9d"), &trick, &rules), vec![1]);
+        assert_eq!(legal_plays(&hand("AsKs9c"), &trick, &rules), vec![0]);
+        assert_eq!(legal_plays(&hand("Ks9s9c"), &trick, &rules), vec![0, 1]);
+
+        trick.plays.push((3, "Jh".parse().unwrap()));
+        assert_eq!(legal_plays(&hand("Ac9hQh"), &trick, &rules), vec![2]);
+        assert_eq!(counters(&hand("AsTsKsQsJs9s")), 30);
+    }
+
+    #[test]
+    fn auction()
+    {
+        let mut game = Pinochle::new();
+
+        assert_eq!(game.to_bid(), Some(0));
+        assert!(game.bid(240).is_err());
+        game.bid(250).unwrap();
+        assert!(game.bid(250).is_err());
+        game.pass().unwrap();
+        game.bid(300).unwrap();
+        game.pass().unwrap();
+        assert_eq!(game.to_bid(), Some(0));
+        game.pass().unwrap();
+
+        assert_eq!(game.to_bid(), None);
+        assert_eq!(game.declarer(), Some(2));
+        assert!(game.name_trump(Suit::Club).is_ok());
+        assert_eq!(game.round.to_play(), 2);
+    }
+
+    #[test]
+    fn stuck_dealer()
+    {
+        let mut game = Pinochle::new();
+        for _ in 0..3
+        {
+            game.pass().unwrap();
+        }
+
+        assert_eq!(game.to_bid(), Some(3));
+        assert!(game.pass().is_err());
+        game.bid(MIN_BID).unwrap();
+        assert_eq!(game.declarer(), Some(3));
+    }
+
+    #[test]
+    fn scoring()
+    {
+        let mut game = Pinochle::new();
+        game.high_bid = Some((0, 300));
+        game.trump = Some(Suit::Heart);
+        game.melds = vec![150, 0, 40, 60];
+        game.round.taken = vec![hand("AsAsTsKs"), vec![], hand("Kd9d"), vec![]];
+        let mut last = Trick::new(1);
+        last.plays = vec![(1, "9c".parse().unwrap()), (2, "Jc".parse().unwrap()), (3, "Qc".parse().unwrap()), (0, "Qc".parse().unwrap())];
+        game.round.last_trick = Some(last);
+
+        assert_eq!(game.score_round(), vec![-300, 70]);
+
+        game.high_bid = Some((1, 250));
+        assert_eq!(game.score_round(), vec![240, -250]);
+        assert_eq!(game.scores, vec![-60, -180]);
+    }
+
+    #[test]
+    fn bots_play_a_hand()
+    {
+        let mut game = Pinochle::new();
+
+        while let Some(seat) = game.to_bid()
+        {
+            game.bot_bid(seat);
+        }
+        let declarer = game.declarer().unwrap();
+        game.name_trump(bot_value(&game.round.hands[declarer]).0).unwrap();
+
+        while !game.round.finished()
+        {
+            let seat = game.round.to_play();
+            let rules = game.rules();
+            let index = bot_play(&game.round.hands[seat], &game.round.legal(&rules), &game.round.trick, seat, &rules);
+            game.play_card(index).unwrap();
+        }
+        let took: u32 = (0..2).map(|us| game.team_points(us).1).sum();
+        assert_eq!(took, 250);
+    }
+}
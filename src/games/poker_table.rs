@@ -0,0 +1,181 @@
+use crate::deck::Card;
+use crate::betting::{Betting, Bet};
+use crate::poker::{Category, HandRank};
+use crate::game::HUMAN;
+use text_io::read;
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+// Pieces shared by the poker games, which all bet through the same engine
+
+pub const STARTING_CHIPS: u32 = 1000;
+// How much a bot thinks of each made hand, from high card up to a straight flush
+const MADE_STRENGTH: [f64; 9] = [0.15, 0.45, 0.65, 0.75, 0.82, 0.86, 0.92, 0.97, 1.0];
+
+// The next seat to the left that is still in the hand
+pub fn next_active(betting: &Betting, seat: usize) -> usize
+{
+    let n = betting.seats.len();
+
+    (1..=n).map(|i| (seat + i) % n).find(|i| !betting.seats[*i].folded).unwrap_or(seat)
+}
+
+// Posts the blinds and opens the betting. Heads up the button posts the small
+// blind and acts first.
+pub fn post_blinds(betting: &mut Betting, button: usize, small_blind: u32, big_blind: u32)
+{
+    let small = if betting.in_hand().len() == 2 { button } else { next_active(betting, button) };
+    let big = next_active(betting, small);
+
+    betting.post(small, small_blind);
+    betting.post(big, big_blind);
+    betting.open(next_active(betting, big));
+}
+
+// The human has every chip on the table
+pub fn last_standing(betting: &Betting) -> bool
+{
+    betting.seats.iter().enumerate().all(|(i, seat)| i == HUMAN || seat.chips == 0)
+}
+
+pub fn made_strength(rank: HandRank) -> f64
+{
+    let strength = MADE_STRENGTH[rank.category() as usize];
+
+    // Higher pairs are worth more than lower ones
+    if rank.category() == Category::Pair
+    {
+        return strength + (rank.tiebreaks()[0] - 2) as f64 / 12.0 * 0.15
+    }
+
+    strength
+}
+
+// How a seat's cards look from the `viewer` seat. Face down cards are only shown to
+// the player holding them, in brackets as a reminder that nobody else can see them.
+pub fn seat_view(cards: &[Card], owner: usize, viewer: usize) -> String
+{
+    cards.iter()
+        .map(|c| if !c.visible && owner == viewer { format!("[{}]", c.see()) } else { c.to_string() })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// A line for each seat as the human sees the table
+pub fn seat_lines(betting: &Betting, names: &[String], hands: &[Vec<Card>], button: usize) -> Vec<String>
+{
+    betting.seats.iter().enumerate().map(|(i, seat)| {
+        let status = if seat.folded { "out" } else if seat.all_in() { "all in" } else { "" };
+        format!(
+            "{} {:<6} {:>5} chips  bet {:>4}  {} {}",
+            if i == button { "(D)" } else { "   " }, names[i], seat.chips, seat.street, seat_view(&hands[i], i, HUMAN), status
+            )
+    }).collect()
+}
+
+pub fn get_bet(betting: &Betting) -> Bet
+{
+    let legal = betting.legal();
+    let (min, max) = (betting.min_raise_to(), betting.max_raise_to());
+    let options: Vec<String> = legal.iter().map(|bet| match bet {
+        Bet::Fold => "Fold".to_string(),
+        Bet::Check => "Check".to_string(),
+        Bet::Call => format!("Call {}", betting.to_call()),
+        Bet::Raise(_) => format!("Raise ({} to {})", min, max),
+        Bet::AllIn => format!("All in ({})", max)
+    }).collect();
+
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Your action")
+        .items(&options)
+        .default(0)
+        .interact_on_opt(&Term::stderr()).unwrap();
+
+    match selection.map(|index| legal[index]) {
+        Some(Bet::Raise(_)) => {
+            println!("Raise to how much? ({}-{})", min, max);
+            let to: u32 = read!("{}\n");
+            Bet::Raise(to)
+        },
+        Some(bet) => bet,
+        None => if legal.contains(&Bet::Check) { Bet::Check } else { Bet::Fold }
+    }
+}
+
+// Takes bets until the street is done, asking the human and letting `strength`
+// guide the bots
+pub fn betting_round(betting: &mut Betting, names: &[String], strength: impl Fn(usize) -> f64)
+{
+    while !betting.round_over()
+    {
+        let seat = betting.to_act;
+        let bet = if seat == HUMAN { get_bet(betting) } else { betting.bot_bet(strength(seat)) };
+
+        match betting.act(bet) {
+            Ok(()) => println!("{}: {}", names[seat], bet),
+            Err(err) => println!("{}", err)
+        }
+    }
+}
+
+// Turns over the hands still in at the end, if there is more than one, and pays
+// out every pot. Returns what each seat won.
+pub fn showdown(betting: &mut Betting, names: &[String], hands: &mut [Vec<Card>], rank: impl Fn(&[Card]) -> Option<HandRank>) -> Vec<u32>
+{
+    let contested = !betting.hand_over();
+    let ranks: Vec<Option<HandRank>> = hands.iter().enumerate()
+        .map(|(i, hand)| if contested && !betting.seats[i].folded { rank(hand) } else { None })
+        .collect();
+
+    if contested
+    {
+        for seat in betting.in_hand()
+        {
+            for card in hands[seat].iter_mut() { card.set_visible(true) }
+            println!("{} show {}: {}", names[seat], seat_view(&hands[seat], seat, HUMAN), ranks[seat].unwrap());
+        }
+    }
+
+    let winnings = betting.settle(|i| ranks[i]);
+    for (seat, won) in winnings.iter().enumerate().filter(|(_, won)| **won > 0)
+    {
+        println!("{} won {} chips", names[seat], won);
+    }
+
+    winnings
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::Suit;
+
+    #[test]
+    fn seat_views()
+    {
+        let cards = [Card { suit: Suit::Spade, rank: 14, visible: false }, Card { suit: Suit::Heart, rank: 9, visible: true }];
+
+        assert_eq!(seat_view(&cards, 2, 2), "[ A♠️]  9❤");
+        assert_eq!(seat_view(&cards, 2, HUMAN), "? ?  9❤");
+    }
+
+    #[test]
+    fn blinds()
+    {
+        let mut betting = Betting::new(&[100, 100], 10);
+        post_blinds(&mut betting, 0, 5, 10);
+
+        assert_eq!(betting.seats[0].street, 5);
+        assert_eq!(betting.to_act, 0);
+
+        let mut betting = Betting::new(&[100, 0, 100, 100], 10);
+        post_blinds(&mut betting, 0, 5, 10);
+        assert_eq!(betting.seats[2].street, 5);
+        assert_eq!(betting.seats[3].street, 10);
+        assert_eq!(betting.to_act, 0);
+    }
+}
@@ -0,0 +1,541 @@
+use std::fmt;
+use crate::deck::{Stack, Card, Suit};
+use crate::game::{Game, HUMAN, player_names, read_players_between};
+use dialoguer::{
+    Select,
+    MultiSelect,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const MIN_PLAYERS: usize = 3;
+const MAX_PLAYERS: usize = 7;
+const ROUNDS: usize = 4;
+
+// Threes are lowest and twos highest, above aces
+pub fn power(card: &Card) -> u8
+{
+    if card.rank == 2 { 15 } else { card.rank }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Role
+{
+    President,
+    VicePresident,
+    Citizen,
+    ViceScum,
+    Scum,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Role::President => "President",
+            Role::VicePresident => "Vice President",
+            Role::Citizen => "Citizen",
+            Role::ViceScum => "Vice Scum",
+            Role::Scum => "Scum"
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Each seat's role from the order everyone went out in. The vice roles only come in
+// with four or more players.
+pub fn roles(finished: &[usize]) -> Vec<Role>
+{
+    let n = finished.len();
+    let mut roles = vec![Role::Citizen; n];
+
+    for (place, seat) in finished.iter().enumerate()
+    {
+        roles[*seat] = match place {
+            0 => Role::President,
+            _ if place == n - 1 => Role::Scum,
+            1 if n >= 4 => Role::VicePresident,
+            _ if place == n - 2 && n >= 4 => Role::ViceScum,
+            _ => Role::Citizen
+        };
+    }
+    roles
+}
+
+fn cards_str(cards: &[Card]) -> String
+{
+    cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+pub struct President
+{
+    names: Vec<String>,
+    hands: Vec<Vec<Card>>,
+    // The set to beat and who played it
+    current: Option<(Vec<Card>, usize)>,
+    to_play: usize,
+    // Passes in a row since the current set was played
+    passes: usize,
+    finished: Vec<usize>,
+    roles: Option<Vec<Role>>,
+    scores: Vec<usize>,
+    round: usize,
+    leave: bool,
+}
+
+impl President
+{
+    // The first round is led by whoever holds the three of clubs
+    pub fn new(players: usize) -> Result<President, String>
+    {
+        if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&players)
+        {
+            return Err(format!("President is played by {} to {} players, {} provided", MIN_PLAYERS, MAX_PLAYERS, players))
+        }
+
+        let mut game = President { names: player_names(players), hands: vec![], current: None, to_play: 0, passes: 0, finished: vec![], roles: None, scores: vec![0; players], round: 0, leave: false };
+        game.deal();
+        game.to_play = (0..players).find(|s| game.hands[*s].iter().any(|c| c.rank == 3 && c.suit == Suit::Club)).unwrap();
+        Ok(game)
+    }
+
+    pub fn play()
+    {
+        let mut game = match President::new(read_players_between(MIN_PLAYERS, MAX_PLAYERS)) {
+            Ok(game) => game,
+            Err(err) => return println!("{}", err)
+        };
+
+        while !game.leave && game.round < ROUNDS
+        {
+            game.handle_input();
+        }
+
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    // Deals the whole deck round the table, so some players may get a card more
+    fn deal(&mut self)
+    {
+        let n = self.names.len();
+        let mut deck = Stack::new_deck(false);
+        deck.shuffle();
+
+        self.hands = vec![vec![]; n];
+        for (i, card) in deck.deal(deck.size()).into_iter().enumerate()
+        {
+            self.hands[i % n].push(card);
+        }
+        for hand in self.hands.iter_mut()
+        {
+            hand.sort_by_key(|c| (power(c), c.suit as u8));
+        }
+        self.current = None;
+        self.passes = 0;
+        self.finished.clear();
+    }
+
+    fn is_out(&self, seat: usize) -> bool
+    {
+        self.finished.contains(&seat)
+    }
+
+    fn next_active(&self, seat: usize) -> usize
+    {
+        let n = self.hands.len();
+        (1..=n).map(|step| (seat + step) % n).find(|s| !self.is_out(*s)).unwrap_or(seat)
+    }
+
+    pub fn round_over(&self) -> bool
+    {
+        self.finished.len() == self.hands.len()
+    }
+
+    // Every set the seat could play: any number of a rank to lead, otherwise as many
+    // cards as were led, of a rank at least as high
+    pub fn legal_plays(&self, seat: usize) -> Vec<Vec<usize>>
+    {
+        let hand = &self.hands[seat];
+        let mut plays = vec![];
+
+        for rank in 2..=14
+        {
+            let held: Vec<usize> = (0..hand.len()).filter(|i| hand[*i].rank == rank).collect();
+            match &self.current {
+                None => plays.extend((1..=held.len()).map(|n| held[..n].to_vec())),
+                Some((set, _)) if held.len() >= set.len() && power(&hand[held[0]]) >= power(&set[0]) => plays.push(held[..set.len()].to_vec()),
+                _ => ()
+            }
+        }
+        plays.sort_by_key(|play| (power(&hand[play[0]]), play.len()));
+        plays
+    }
+
+    pub fn play_set(&mut self, indices: &[usize]) -> Result<Vec<Card>, String>
+    {
+        let seat = self.to_play;
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        if !self.legal_plays(seat).iter().any(|play| {
+            play.len() == indices.len() && indices.iter().all(|i| self.hands[seat].get(*i).map(|c| c.rank) == Some(self.hands[seat][play[0]].rank))
+        })
+        {
+            return Err(String::from("That set can't be played now"))
+        }
+
+        let cards: Vec<Card> = indices.iter().rev().map(|i| self.hands[seat].remove(*i)).collect();
+        self.current = Some((cards.clone(), seat));
+        self.passes = 0;
+        if self.hands[seat].is_empty()
+        {
+            self.finished.push(seat);
+        }
+        self.advance();
+        Ok(cards)
+    }
+
+    pub fn pass(&mut self) -> Result<(), String>
+    {
+        if self.current.is_none()
+        {
+            return Err(String::from("The leader has to play something"))
+        }
+
+        self.passes += 1;
+        self.advance();
+        Ok(())
+    }
+
+    // Moves play on, clearing the trick once everyone still in has passed on the last
+    // set. Its player leads the next, or whoever is next if they went out on it.
+    fn advance(&mut self)
+    {
+        let active: Vec<usize> = (0..self.hands.len()).filter(|s| !self.is_out(*s)).collect();
+        if active.len() == 1
+        {
+            self.finished.push(active[0]);
+            return
+        }
+
+        let owner = self.current.as_ref().map(|(_, seat)| *seat);
+        let waiting = active.iter().filter(|s| Some(**s) != owner).count();
+        match owner {
+            Some(owner) if self.passes >= waiting => {
+                self.current = None;
+                self.passes = 0;
+                self.to_play = if self.is_out(owner) { self.next_active(owner) } else { owner };
+            },
+            _ => self.to_play = self.next_active(self.to_play)
+        }
+    }
+
+    // Moves the lower role's best `count` cards to the higher role
+    fn tribute(&mut self, low: usize, high: usize, count: usize)
+    {
+        for _ in 0..count
+        {
+            let best = (0..self.hands[low].len()).max_by_key(|i| power(&self.hands[low][*i])).unwrap();
+            let card = self.hands[low].remove(best);
+            self.hands[high].push(card);
+        }
+        self.hands[high].sort_by_key(|c| (power(c), c.suit as u8));
+    }
+
+    fn give_back(&mut self, high: usize, low: usize, indices: &[usize])
+    {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        for i in indices.iter().rev()
+        {
+            let card = self.hands[high].remove(*i);
+            self.hands[low].push(card);
+        }
+        self.hands[low].sort_by_key(|c| (power(c), c.suit as u8));
+        self.hands[high].sort_by_key(|c| (power(c), c.suit as u8));
+    }
+
+    // What goes back in return for the tribute, which for a bot is its lowest cards
+    fn choose_returns(&self, high: usize, count: usize) -> Vec<usize>
+    {
+        if high != HUMAN
+        {
+            return (0..count).collect()
+        }
+
+        loop
+        {
+            let options: Vec<String> = self.hands[HUMAN].iter().map(|c| c.to_string()).collect();
+            let chosen = MultiSelect::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("Give back {} card{}", count, if count == 1 { "" } else { "s" }))
+                .items(&options)
+                .interact_on(&Term::stderr()).unwrap();
+            if chosen.len() == count { return chosen }
+            println!("Pick exactly {}", count);
+        }
+    }
+
+    // The scum hands their two best cards to the president for any two back, and with
+    // four or more players the vice scum swaps one with the vice president
+    fn exchange(&mut self)
+    {
+        let roles = match &self.roles { Some(roles) => roles.clone(), None => return };
+        let seat = |role: Role| roles.iter().position(|r| *r == role);
+
+        for (high, low, count) in [(Role::President, Role::Scum, 2), (Role::VicePresident, Role::ViceScum, 1)].iter()
+        {
+            if let (Some(high), Some(low)) = (seat(*high), seat(*low))
+            {
+                self.tribute(low, high, *count);
+                let back = self.choose_returns(high, *count);
+                self.give_back(high, low, &back);
+                println!("{} and {} swap {} card{}", self.names[low], self.names[high], count, if *count == 1 { "" } else { "s" });
+            }
+        }
+    }
+
+    // Scores the round, a point for each player finished behind, and starts the next
+    // with the scum leading
+    fn end_round(&mut self)
+    {
+        let n = self.hands.len();
+        let roles = roles(&self.finished);
+        for (place, seat) in self.finished.iter().enumerate()
+        {
+            self.scores[*seat] += n - 1 - place;
+            println!("{}. {} ({})", place + 1, self.names[*seat], roles[*seat]);
+        }
+        self.round += 1;
+        self.roles = Some(roles);
+        if self.round >= ROUNDS { return }
+
+        let scum = *self.finished.last().unwrap();
+        self.deal();
+        self.exchange();
+        self.to_play = scum;
+    }
+
+    // Leads the lowest rank it holds, all of it, and follows as cheaply as it can
+    // without splitting up a set. Twos are kept back while the hand is still big.
+    fn bot_play(&self, seat: usize) -> Option<Vec<usize>>
+    {
+        let hand = &self.hands[seat];
+        let held = |rank: u8| hand.iter().filter(|c| c.rank == rank).count();
+        let plays = self.legal_plays(seat);
+
+        if self.current.is_none()
+        {
+            let lowest = power(&hand[plays[0][0]]);
+            return plays.into_iter().filter(|p| power(&hand[p[0]]) == lowest).max_by_key(|p| p.len())
+        }
+
+        let play = plays.into_iter().min_by_key(|p| (held(hand[p[0]].rank) != p.len(), power(&hand[p[0]])))?;
+        if power(&hand[play[0]]) == 15 && hand.len() > 4 { None } else { Some(play) }
+    }
+
+    fn report(&self, seat: usize, cards: &[Card])
+    {
+        let out = if self.is_out(seat) { ", and is out" } else { "" };
+        println!("{} plays {}{}", self.names[seat], cards_str(cards), out);
+    }
+
+    fn play_bots(&mut self)
+    {
+        while !self.round_over() && self.to_play != HUMAN
+        {
+            let seat = self.to_play;
+            match self.bot_play(seat) {
+                Some(play) => {
+                    let cards = self.play_set(&play).unwrap();
+                    self.report(seat, &cards);
+                },
+                None => {
+                    println!("{} passes", self.names[seat]);
+                    self.pass().unwrap();
+                }
+            }
+        }
+    }
+
+    fn human_turn(&mut self)
+    {
+        println!("{}", self);
+        let plays = self.legal_plays(HUMAN);
+        let mut options: Vec<String> = plays.iter().map(|p| cards_str(&p.iter().map(|i| self.hands[HUMAN][*i]).collect::<Vec<Card>>())).collect();
+        if self.current.is_some() { options.push("Pass".to_string()) }
+        options.push("Leave".to_string());
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(if self.current.is_some() { "Beat it or pass" } else { "Lead a set" })
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) if index < plays.len() => match self.play_set(&plays[index]) {
+                Ok(cards) => self.report(HUMAN, &cards),
+                Err(err) => println!("{}", err)
+            },
+            Some(index) if index == plays.len() && self.current.is_some() => self.pass().unwrap(),
+            Some(_) => self.leave = true,
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+impl fmt::Display for President {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![format!("+ --- Round {} of {} --- +", self.round + 1, ROUNDS)];
+        for seat in 0..self.hands.len()
+        {
+            let role = self.roles.as_ref().map_or(String::new(), |roles| format!(" ({})", roles[seat]));
+            let status = match self.finished.iter().position(|s| *s == seat) {
+                Some(place) => format!("out in place {}", place + 1),
+                None => format!("{} cards", self.hands[seat].len())
+            };
+            lines.push(format!("{:<6}{} {}, {} points", self.names[seat], role, status, self.scores[seat]));
+        }
+        match &self.current {
+            Some((cards, seat)) => lines.push(format!("To beat: {} from {}", cards_str(cards), self.names[*seat])),
+            None => lines.push("To beat: nothing, lead anything".to_string())
+        }
+        lines.push(format!("Your hand: {}", cards_str(&self.hands[HUMAN])));
+        lines.push("+ ------------------- +".to_string());
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for President
+{
+    fn win(&self) -> bool
+    {
+        self.round >= ROUNDS && self.scores.iter().all(|s| *s <= self.scores[HUMAN])
+    }
+
+    fn handle_input(&mut self)
+    {
+        self.play_bots();
+        if self.round_over()
+        {
+            return self.end_round()
+        }
+        self.human_turn();
+        self.play_bots();
+        if self.round_over()
+        {
+            self.end_round();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    fn deal(hands: &[&str]) -> President
+    {
+        let mut game = President::new(hands.len()).unwrap();
+        game.hands = hands.iter().map(|h| parse_cards(h).unwrap()).collect();
+        game.to_play = 0;
+        game
+    }
+
+    #[test]
+    fn powers_and_roles()
+    {
+        assert!(power(&"2c".parse().unwrap()) > power(&"As".parse().unwrap()));
+        assert!(power(&"3c".parse().unwrap()) < power(&"4s".parse().unwrap()));
+
+        use Role::*;
+        assert_eq!(roles(&[2, 0, 1]), vec![Citizen, Scum, President]);
+        assert_eq!(roles(&[3, 1, 4, 0, 2]), vec![ViceScum, VicePresident, Scum, President, Citizen]);
+    }
+
+    #[test]
+    fn following()
+    {
+        let mut game = deal(&["5s5h9c", "4c4d6h6s2c", "7h7d"]);
+
+        game.play_set(&[0, 1]).unwrap();
+        assert_eq!(game.to_play, 1);
+        assert_eq!(game.legal_plays(1), vec![vec![2, 3]]);
+        assert!(game.play_set(&[0, 1]).is_err());
+        assert!(game.play_set(&[4]).is_err());
+        game.play_set(&[2, 3]).unwrap();
+        assert_eq!(game.legal_plays(2), vec![vec![0, 1]]);
+    }
+
+    #[test]
+    fn clearing_tricks()
+    {
+        let mut game = deal(&["5s9c", "4c", "7h8d"]);
+
+        game.play_set(&[0]).unwrap();
+        game.pass().unwrap();
+        game.pass().unwrap();
+        assert_eq!(game.current, None);
+        assert_eq!(game.to_play, 0);
+        assert!(game.pass().is_err());
+
+        // Going out on the last set hands the lead on once everyone has passed
+        game.play_set(&[0]).unwrap();
+        assert_eq!(game.finished, vec![0]);
+        assert_eq!(game.to_play, 1);
+        game.pass().unwrap();
+        game.pass().unwrap();
+        assert_eq!((game.current.clone(), game.to_play), (None, 1));
+
+        game.play_set(&[0]).unwrap();
+        assert!(game.round_over());
+        assert_eq!(game.finished, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn exchanging()
+    {
+        let mut game = deal(&["3c3d9h", "5s", "2cAs4h"]);
+        game.roles = Some(vec![Role::President, Role::Citizen, Role::Scum]);
+
+        game.tribute(2, 0, 2);
+        assert_eq!(game.hands[2], parse_cards("4h").unwrap());
+        assert_eq!(game.hands[0], parse_cards("3c3d9hAs2c").unwrap());
+        game.give_back(0, 2, &[2, 0]);
+        assert_eq!(game.hands[0], parse_cards("3dAs2c").unwrap());
+        assert_eq!(game.hands[2], parse_cards("3c4h9h").unwrap());
+
+        // A bot president gives back its two lowest
+        let mut game = deal(&["5s", "3c3d9h", "2cAs4h"]);
+        game.roles = Some(vec![Role::Citizen, Role::President, Role::Scum]);
+        game.exchange();
+        assert_eq!(game.hands[1], parse_cards("9hAs2c").unwrap());
+        assert_eq!(game.hands[2], parse_cards("3c3d4h").unwrap());
+    }
+
+    #[test]
+    fn bots_play_a_round()
+    {
+        let mut game = President::new(5).unwrap();
+
+        for _ in 0..1000
+        {
+            if game.round_over() { break }
+            match game.bot_play(game.to_play) {
+                Some(play) => { game.play_set(&play).unwrap(); },
+                None => game.pass().unwrap()
+            }
+        }
+        assert!(game.round_over());
+        let mut order = game.finished.clone();
+        order.sort_unstable();
+        assert_eq!(order, vec![0, 1, 2, 3, 4]);
+    }
+}
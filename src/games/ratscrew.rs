@@ -0,0 +1,431 @@
+use std::fmt;
+use std::io::stdin;
+use std::thread;
+use std::time::Duration;
+use std::sync::mpsc::{channel, Receiver};
+use rand::{thread_rng, Rng};
+use crate::deck::{Stack, Card};
+use crate::game::{Game, HUMAN, player_names, read_players};
+use dialoguer::{
+    MultiSelect,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const MAX_PLAYERS: usize = 4;
+// How long after a card is turned up you have to slap when no bot is going to
+const SLAP_WINDOW: u64 = 1200;
+// How many milliseconds a bot takes to see a pile worth slapping
+const BOT_REACTION: std::ops::RangeInclusive<u64> = 400..=1200;
+
+// The piles anyone may slap to take, besides the face card challenges
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlapRule {
+    // The top two cards share a rank
+    Doubles,
+    // The top card shares a rank with the one two below it
+    Sandwich,
+    // The top card shares a rank with the bottom card
+    TopBottom,
+    // A king and a queen on top of one another
+    Marriage,
+    // The top two cards add up to ten, aces counting one
+    AddToTen,
+}
+
+const SLAP_RULES: [SlapRule; 5] = [SlapRule::Doubles, SlapRule::Sandwich, SlapRule::TopBottom, SlapRule::Marriage, SlapRule::AddToTen];
+
+impl SlapRule {
+    pub fn applies(&self, pile: &[Card]) -> bool {
+        let top = |n: usize| if pile.len() > n { Some(pile[pile.len() - 1 - n]) } else { None };
+
+        match (self, top(0), top(1)) {
+            (SlapRule::Doubles, Some(a), Some(b)) => a.rank == b.rank,
+            (SlapRule::Sandwich, Some(a), _) => top(2).is_some_and(|c| c.rank == a.rank),
+            (SlapRule::TopBottom, Some(a), Some(_)) => pile[0].rank == a.rank,
+            (SlapRule::Marriage, Some(a), Some(b)) => a.rank.min(b.rank) == 12 && a.rank.max(b.rank) == 13,
+            (SlapRule::AddToTen, Some(a), Some(b)) => a.low_rank() + b.low_rank() == 10,
+            _ => false
+        }
+    }
+}
+
+impl fmt::Display for SlapRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SlapRule::Doubles => "Doubles",
+            SlapRule::Sandwich => "Sandwiches",
+            SlapRule::TopBottom => "Top and bottom",
+            SlapRule::Marriage => "Marriages",
+            SlapRule::AddToTen => "Adding up to ten",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// The number of tries the next player gets to turn up a face card of their own
+fn chances(card: &Card) -> Option<u8>
+{
+    match card.rank {
+        11..=14 => Some(card.rank - 10),
+        _ => None
+    }
+}
+
+// Reads lines off stdin on another thread so a slap can be waited for with a timeout
+fn spawn_input() -> Receiver<String>
+{
+    let (sender, receiver) = channel();
+    thread::spawn(move || loop
+    {
+        let mut line = String::new();
+        if stdin().read_line(&mut line).unwrap_or(0) == 0 { break }
+        if sender.send(line.trim().to_lowercase()).is_err() { break }
+    });
+    receiver
+}
+
+pub struct Ratscrew
+{
+    names: Vec<String>,
+    hands: Vec<Stack>,
+    pile: Vec<Card>,
+    rules: Vec<SlapRule>,
+    to_play: usize,
+    // Who turned up the face card the player to play must answer, and how many
+    // tries they have left
+    challenge: Option<(usize, u8)>,
+    input: Option<Receiver<String>>,
+    leave: bool,
+}
+
+impl Ratscrew
+{
+    pub fn new(players: usize, rules: Vec<SlapRule>) -> Result<Ratscrew, String>
+    {
+        if !(2..=MAX_PLAYERS).contains(&players)
+        {
+            return Err(format!("Egyptian Ratscrew is played by 2 to {} players, {} provided", MAX_PLAYERS, players))
+        }
+
+        let mut deck = Stack::new_deck(false);
+        deck.shuffle();
+        let mut hands = vec![vec![]; players];
+        for (i, card) in deck.deal(deck.size()).into_iter().enumerate()
+        {
+            hands[i % players].push(card);
+        }
+        let hands = hands.into_iter().map(Stack::from_cards).collect();
+
+        Ok(Ratscrew { names: player_names(players), hands, pile: vec![], rules, to_play: 0, challenge: None, input: None, leave: false })
+    }
+
+    pub fn play()
+    {
+        let players = read_players(MAX_PLAYERS);
+        let chosen = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Which piles can be slapped?")
+            .items(&SLAP_RULES)
+            .defaults(&[true, true, false, false, false])
+            .interact_on(&Term::stderr()).unwrap();
+        let rules = chosen.iter().map(|i| SLAP_RULES[*i]).collect();
+
+        let mut game = match Ratscrew::new(players, rules) {
+            Ok(game) => game,
+            Err(err) => return println!("{}", err)
+        };
+        game.input = Some(spawn_input());
+        println!("Press Enter to turn up your card, type s and Enter to slap, or q to leave");
+
+        while !game.leave && game.winner().is_none() && game.in_game(HUMAN)
+        {
+            game.handle_input();
+        }
+
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    // A player is out once they have no cards, unless the pile is still theirs to win
+    fn in_game(&self, seat: usize) -> bool
+    {
+        self.hands[seat].size() > 0 || self.challenge.map(|(challenger, _)| challenger) == Some(seat)
+    }
+
+    fn next_seat(&self, seat: usize) -> usize
+    {
+        let n = self.hands.len();
+        (1..=n).map(|step| (seat + step) % n).find(|s| self.hands[*s].size() > 0).unwrap_or(seat)
+    }
+
+    pub fn winner(&self) -> Option<usize>
+    {
+        let mut left = (0..self.hands.len()).filter(|s| self.in_game(*s));
+
+        match (left.next(), left.next()) {
+            (Some(seat), None) => Some(seat),
+            _ => None
+        }
+    }
+
+    pub fn slappable(&self) -> bool
+    {
+        self.rules.iter().any(|rule| rule.applies(&self.pile))
+    }
+
+    fn collect(&mut self, seat: usize)
+    {
+        let pile = std::mem::take(&mut self.pile);
+        self.hands[seat].put_bottom(pile);
+        self.challenge = None;
+        self.to_play = seat;
+    }
+
+    // The player to play turns up their top card. A face card challenges the next
+    // player, and a challenger whose challenge goes unanswered takes the pile.
+    pub fn play_card(&mut self) -> (Card, Option<usize>)
+    {
+        let seat = self.to_play;
+        let card = self.hands[seat].draw();
+        self.pile.push(card);
+
+        match (chances(&card), self.challenge) {
+            (Some(chances), _) => {
+                self.challenge = Some((seat, chances));
+                self.to_play = self.next_seat(seat);
+            },
+            (None, Some((challenger, left))) => {
+                if left == 1 || self.hands[seat].size() == 0
+                {
+                    self.collect(challenger);
+                    return (card, Some(challenger))
+                }
+                self.challenge = Some((challenger, left - 1));
+            },
+            (None, None) => self.to_play = self.next_seat(seat)
+        }
+
+        (card, None)
+    }
+
+    // A good slap takes the pile, and a bad one costs a card under the pile
+    pub fn slap(&mut self, seat: usize) -> bool
+    {
+        if self.slappable()
+        {
+            self.collect(seat);
+            return true
+        }
+        if self.hands[seat].size() > 0
+        {
+            let card = self.hands[seat].draw();
+            self.pile.insert(0, card);
+        }
+        // Burning the last card of the player to play ends their turn, and an
+        // unanswered challenge with it
+        if self.hands[self.to_play].size() == 0
+        {
+            match self.challenge {
+                Some((challenger, _)) => self.collect(challenger),
+                None => self.to_play = self.next_seat(self.to_play)
+            }
+        }
+        false
+    }
+
+    fn report_slap(&mut self, seat: usize)
+    {
+        if self.slap(seat)
+        {
+            println!("{} slapped and took the pile 👋", self.names[seat]);
+        }
+        else
+        {
+            println!("{} slapped for nothing and burns a card", self.names[seat]);
+        }
+    }
+
+    // Waits for the human to slap, for as long as the quickest bot that has seen a
+    // pile worth slapping takes, or for a while if none has
+    fn slap_window(&mut self)
+    {
+        let mut rng = thread_rng();
+        let bot = if self.slappable()
+        {
+            (0..self.hands.len()).filter(|s| *s != HUMAN && self.hands[*s].size() > 0)
+                .map(|s| (s, rng.gen_range(BOT_REACTION.clone()))).min_by_key(|(_, time)| *time)
+        }
+        else
+        {
+            None
+        };
+        let wait = Duration::from_millis(bot.map_or(SLAP_WINDOW, |(_, time)| time));
+
+        let line = self.input.as_ref().and_then(|input| input.recv_timeout(wait).ok());
+        match (line.as_deref(), bot) {
+            (Some("q"), _) => self.leave = true,
+            (Some("s"), _) if self.in_game(HUMAN) => self.report_slap(HUMAN),
+            (_, Some((seat, _))) => self.report_slap(seat),
+            _ => ()
+        }
+    }
+
+    fn human_turn(&mut self)
+    {
+        let line = match self.input.as_ref().map(|input| input.recv()) {
+            Some(Ok(line)) => line,
+            _ => return self.leave = true
+        };
+
+        match line.as_str() {
+            "q" => self.leave = true,
+            "s" => self.report_slap(HUMAN),
+            _ => self.turn_up()
+        }
+    }
+
+    fn turn_up(&mut self)
+    {
+        let seat = self.to_play;
+        let (card, taker) = self.play_card();
+        println!("{:<6} {}", self.names[seat], card);
+
+        if let Some(taker) = taker
+        {
+            println!("{} takes the pile of {} cards", self.names[taker], self.hands[taker].size());
+        }
+        else if !self.leave
+        {
+            // Anything typed while the card was on its way is too early to count
+            if let Some(input) = self.input.as_ref() { while input.try_recv().is_ok() {} }
+            self.slap_window();
+        }
+    }
+}
+
+impl fmt::Display for Ratscrew {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts: Vec<String> = (0..self.hands.len()).map(|s| format!("{} {}", self.names[s], self.hands[s].size())).collect();
+        let top = self.pile.last().map_or("nothing".to_string(), |c| c.to_string());
+
+        write!(f, "+ --- {} on the pile, {} on top --- +\n{}", self.pile.len(), top, counts.join(", "))
+    }
+}
+
+impl Game for Ratscrew
+{
+    fn win(&self) -> bool
+    {
+        self.winner() == Some(HUMAN)
+    }
+
+    fn handle_input(&mut self)
+    {
+        if self.to_play == HUMAN
+        {
+            println!("{}", self);
+            self.human_turn();
+        }
+        else
+        {
+            self.turn_up();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    fn deal(hands: &[&str], pile: &str) -> Ratscrew
+    {
+        let mut game = Ratscrew::new(hands.len(), SLAP_RULES.to_vec()).unwrap();
+        game.hands = hands.iter().map(|h| Stack::from_cards(parse_cards(h).unwrap())).collect();
+        game.pile = parse_cards(pile).unwrap();
+        game
+    }
+
+    #[test]
+    fn slap_rules()
+    {
+        let slaps = |rule: SlapRule, pile: &str| rule.applies(&parse_cards(pile).unwrap());
+
+        assert!(slaps(SlapRule::Doubles, "2c7s7h"));
+        assert!(!slaps(SlapRule::Doubles, "7s"));
+        assert!(slaps(SlapRule::Sandwich, "7s2c7h"));
+        assert!(!slaps(SlapRule::Sandwich, "2c7h"));
+        assert!(slaps(SlapRule::TopBottom, "7s2c3d7h"));
+        assert!(!slaps(SlapRule::TopBottom, "7s"));
+        assert!(slaps(SlapRule::Marriage, "2cKsQh"));
+        assert!(slaps(SlapRule::AddToTen, "As9d"));
+        assert!(!slaps(SlapRule::AddToTen, "5s6d"));
+    }
+
+    #[test]
+    fn challenges()
+    {
+        let mut game = deal(&["Qs2s", "3c4c5c"], "");
+
+        assert_eq!(game.play_card().1, None);
+        assert_eq!(game.challenge, Some((0, 2)));
+        assert_eq!(game.to_play, 1);
+        assert_eq!(game.play_card().1, None);
+        assert_eq!(game.to_play, 1);
+        assert_eq!(game.play_card().1, Some(0));
+        assert_eq!(game.hands[0].size(), 4);
+        assert_eq!(game.to_play, 0);
+        assert!(game.pile.is_empty());
+
+        // Answering with a face card turns the challenge around
+        let mut game = deal(&["Js", "Kc4c", "5d"], "");
+        game.play_card();
+        game.play_card();
+        assert_eq!(game.challenge, Some((1, 3)));
+        assert_eq!(game.to_play, 2);
+        assert_eq!(game.play_card().1, Some(1));
+        assert_eq!(game.winner(), Some(1));
+    }
+
+    #[test]
+    fn slapping()
+    {
+        let mut game = deal(&["9s2s", "3c4c"], "9h3d");
+
+        assert!(!game.slap(1));
+        assert_eq!(game.pile, parse_cards("3c9h3d").unwrap());
+        assert!(game.slap(0));
+        assert_eq!(game.hands[0].size(), 5);
+        assert_eq!(game.to_play, 0);
+
+        game.rules = vec![SlapRule::Doubles];
+        game.pile = parse_cards("4h5h").unwrap();
+        assert!(!game.slappable());
+    }
+
+    #[test]
+    fn bots_play_a_game()
+    {
+        let mut game = Ratscrew::new(3, vec![SlapRule::Doubles, SlapRule::Sandwich]).unwrap();
+
+        for _ in 0..5000
+        {
+            if game.winner().is_some() { break }
+            game.play_card();
+            if game.slappable()
+            {
+                let seat = game.next_seat(game.to_play);
+                game.slap(seat);
+            }
+        }
+        let cards: usize = game.hands.iter().map(|h| h.size()).sum::<usize>() + game.pile.len();
+        assert_eq!(cards, 52);
+    }
+}
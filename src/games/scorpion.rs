@@ -0,0 +1,264 @@
+use crate::deck::{Stack, Card};
+use crate::game::Game;
+use text_io::read;
+use std::fmt;
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const COLUMNS: usize = 7;
+const COLUMN_DEPTH: usize = 7;
+// The first three columns each start with their bottom three cards face down
+const HIDDEN_COLUMNS: usize = 3;
+const HIDDEN_DEPTH: usize = 3;
+const SUIT_RUNS: usize = 4;
+const RUN_LENGTH: usize = 13;
+
+pub struct Scorpion
+{
+    deck: Stack,
+    columns: Vec<Vec<Card>>,
+}
+
+impl Scorpion
+{
+    fn new() -> Scorpion
+    {
+        let mut deck = Stack::new_deck(false);
+
+        deck.shuffle();
+
+        let columns = (0..COLUMNS).map(|i| {
+            deck.deal(COLUMN_DEPTH).into_iter().enumerate().map(|(depth, card)| {
+                if i < HIDDEN_COLUMNS && depth < HIDDEN_DEPTH { card.hide() } else { card }
+            }).collect()
+        }).collect();
+
+        Scorpion { deck, columns }
+    }
+
+    pub fn play()
+    {
+        let mut game = Scorpion::new();
+
+        while !game.win()
+        {
+            game.handle_input();
+        }
+
+        println!("You won 😀");
+    }
+
+    fn get_number(min: usize, max: usize) -> usize
+    {
+        println!("Select a number between {} and {}", min, max);
+        let number: usize = read!("{}\n");
+
+        number
+    }
+
+    // Moves the card at `depth` in column `from`, along with every card on top of it,
+    // onto column `to`. The card must land on its same-suit successor, and only kings
+    // may be moved into an empty column.
+    fn make_move(&mut self, from: usize, depth: usize, to: usize) -> Result<(), String>
+    {
+        if from == to || from >= COLUMNS || to >= COLUMNS
+        {
+            return Err(String::from("Unable to make that move"))
+        }
+
+        let move_card = match self.columns[from].get(depth) { Some(c) if c.visible => *c, _ => return Err(String::from("Unable to make that move")) };
+        let valid = match self.columns[to].last()
+        {
+            Some(c) => c.suit == move_card.suit && move_card.low_rank() + 1 == c.low_rank(),
+            None => move_card.rank == 13
+        };
+        if !valid
+        {
+            return Err(String::from("Unable to make that move"))
+        }
+
+        let moving = self.columns[from].split_off(depth);
+        if let Some(next) = self.columns[from].last_mut() {
+            next.set_visible(true);
+        };
+        self.columns[to].extend(moving);
+
+        Ok(())
+    }
+
+    fn handle_move(&mut self) -> Result<(), String>
+    {
+        println!("Column to move from:");
+        let from = Scorpion::get_number(1, COLUMNS) - 1;
+        let height = match self.columns.get(from) { Some(c) if !c.is_empty() => c.len(), _ => return Err(String::from("Unable to make that move")) };
+        println!("How many cards to move:");
+        let count = Scorpion::get_number(1, height);
+        println!("Column to move to:");
+        let to = Scorpion::get_number(1, COLUMNS) - 1;
+
+        if count == 0 || count > height
+        {
+            return Err(String::from("Unable to make that move"))
+        }
+
+        self.make_move(from, height - count, to)
+    }
+
+    // The last cards of the stock are dealt one each onto the first columns
+    fn handle_deal(&mut self) -> Result<(), String>
+    {
+        if self.deck.size() == 0
+        {
+            return Err(String::from("The stock is empty"))
+        }
+
+        let remaining = self.deck.size();
+        for column in self.columns.iter_mut().take(remaining)
+        {
+            column.push(self.deck.draw());
+        }
+
+        Ok(())
+    }
+
+    fn is_suit_run(cards: &[Card]) -> bool
+    {
+        cards[0].rank == 13 && cards.windows(2).all(|pair| {
+            pair[0].visible && pair[1].visible
+            && pair[0].suit == pair[1].suit
+            && pair[1].low_rank() + 1 == pair[0].low_rank()
+        })
+    }
+}
+
+impl fmt::Display for Scorpion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let max_column = self.columns.iter().map(|c| c.len()).max().unwrap();
+        let mut lines = vec![
+            format!("🃏 : {} Cards remaining", self.deck.size()),
+            "+ ------------------- +".to_string()
+            ];
+        for i in 0..max_column
+        {
+            let strings: Vec<String> = self.columns.iter().map(|c| c.get(i)).map(|c| {
+                match c {
+                    Some(m) => format!("{}", m),
+                    None => "   ".to_string()
+                }
+            }).collect();
+            lines.push(strings.join(" | "));
+        };
+
+        lines.push("+ ------------------- +".to_string());
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Scorpion
+{
+    fn win(&self) -> bool
+    {
+        let runs: usize = self.columns.iter()
+            .filter(|c| c.len() >= RUN_LENGTH)
+            .map(|c| c.windows(RUN_LENGTH).filter(|w| Scorpion::is_suit_run(w)).count())
+            .sum();
+
+        runs == SUIT_RUNS
+    }
+
+    fn handle_input(&mut self)
+    {
+        let commands = ["Display", "Move", "Deal"];
+        println!("Select a command: ");
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&commands)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) =>
+            {
+                println!("+ -------------------- + ");
+                match commands[index] {
+                    "Display" => println!("{}", self),
+                    "Move" => self.handle_move().unwrap_or_else(|err| println!("{}", err)),
+                    "Deal" => self.handle_deal().unwrap_or_else(|err| println!("{}", err)),
+                    _ => {
+                        println!("Invalid selection");
+                        self.handle_input();
+                    }
+                }
+            }
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::Suit;
+
+    fn card(suit: Suit, rank: u8) -> Card
+    {
+        Card { suit, rank, visible: true }
+    }
+
+    #[test]
+    fn deal()
+    {
+        let game = Scorpion::new();
+
+        assert_eq!(game.deck.size(), 3);
+        assert!(!game.columns[2][2].visible);
+        assert!(game.columns[2][3].visible);
+        assert!(game.columns[3].iter().all(|c| c.visible));
+    }
+
+    #[test]
+    fn move_carries_cards_above()
+    {
+        let mut game = Scorpion::new();
+        game.columns[0] = vec![card(Suit::Club, 3).hide(), card(Suit::Heart, 7), card(Suit::Spade, 2)];
+        game.columns[1] = vec![card(Suit::Heart, 8)];
+
+        assert!(game.make_move(0, 1, 1).is_ok());
+        assert_eq!(game.columns[1].len(), 3);
+        assert!(game.columns[0][0].visible);
+
+        // Only a king may go into a space
+        game.columns[2] = vec![];
+        assert!(game.make_move(1, 1, 2).is_err());
+        game.columns[3] = vec![card(Suit::Club, 13)];
+        assert!(game.make_move(3, 0, 2).is_ok());
+    }
+
+    #[test]
+    fn final_deal()
+    {
+        let mut game = Scorpion::new();
+
+        assert!(game.handle_deal().is_ok());
+        assert_eq!(game.columns[0].len(), COLUMN_DEPTH + 1);
+        assert_eq!(game.columns[3].len(), COLUMN_DEPTH);
+        assert!(game.handle_deal().is_err());
+    }
+
+    #[test]
+    fn win()
+    {
+        let mut game = Scorpion::new();
+        let suits = [Suit::Heart, Suit::Spade, Suit::Club, Suit::Diamond];
+        game.columns = suits.iter().map(|s| (2..14).rev().chain(Some(14)).map(|r| card(*s, r)).collect()).collect();
+
+        assert!(game.win());
+
+        game.columns[0].swap(3, 4);
+        assert!(!game.win());
+    }
+}
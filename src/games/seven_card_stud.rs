@@ -0,0 +1,326 @@
+use std::fmt;
+use crate::deck::{Stack, Card, Suit};
+use crate::betting::Betting;
+use crate::poker::{evaluate, evaluate_partial, HandRank};
+use crate::poker_table::{
+    STARTING_CHIPS, next_active, last_standing,
+    made_strength, seat_lines, betting_round, showdown
+};
+use crate::game::{Game, HUMAN, player_names, read_players};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+// Seven players use 49 cards, so the deck never runs out
+const MAX_PLAYERS: usize = 7;
+const ANTE: u32 = 1;
+const BRING_IN: u32 = 5;
+const BIG_BET: u32 = 10;
+// The card dealt on each street after third, and whether it is dealt face up
+const STREETS: [(&str, bool); 4] = [("Fourth street", true), ("Fifth street", true), ("Sixth street", true), ("Seventh street", false)];
+
+// Breaks ties for the bring-in, from clubs lowest up to spades
+fn suit_order(suit: Suit) -> u8
+{
+    match suit {
+        Suit::Club => 0,
+        Suit::Diamond => 1,
+        Suit::Heart => 2,
+        Suit::Spade => 3,
+        Suit::Joker => 4
+    }
+}
+
+fn up_cards(hand: &[Card]) -> Vec<Card>
+{
+    hand.iter().filter(|c| c.visible).copied().collect()
+}
+
+// Bots judge their own hand, up and down cards alike
+fn rank(hand: &[Card]) -> HandRank
+{
+    if hand.len() >= 5 { evaluate(hand).unwrap() } else { evaluate_partial(hand).unwrap() }
+}
+
+// Seven card stud with antes, a bring-in and no limit betting on every street
+pub struct SevenCardStud
+{
+    names: Vec<String>,
+    deck: Stack,
+    hands: Vec<Vec<Card>>,
+    button: usize,
+    betting: Betting,
+    leave: bool,
+}
+
+impl SevenCardStud
+{
+    pub fn new(players: usize) -> Result<SevenCardStud, String>
+    {
+        if !(2..=MAX_PLAYERS).contains(&players)
+        {
+            return Err(format!("Seven card stud is played by 2 to {} players, {} provided", MAX_PLAYERS, players))
+        }
+
+        Ok(SevenCardStud
+        {
+            names: player_names(players),
+            deck: Stack::new_deck(false),
+            hands: vec![vec![]; players],
+            button: players - 1,
+            betting: Betting::new(&vec![STARTING_CHIPS; players], BIG_BET),
+            leave: false
+        })
+    }
+
+    pub fn play()
+    {
+        let mut game = match SevenCardStud::new(read_players(MAX_PLAYERS)) {
+            Ok(game) => game,
+            Err(err) => return println!("{}", err)
+        };
+
+        while !game.leave && game.betting.seats[HUMAN].chips > 0 && !game.win()
+        {
+            game.handle_input();
+        }
+
+        println!("You leave the table with {} chips", game.betting.seats[HUMAN].chips);
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    // The lowest card showing pays the bring-in
+    fn bring_in_seat(&self) -> usize
+    {
+        self.betting.in_hand().into_iter()
+            .min_by_key(|seat| {
+                let up = up_cards(&self.hands[*seat])[0];
+                (up.rank, suit_order(up.suit))
+            })
+            .unwrap()
+    }
+
+    // The best hand showing acts first, the seat nearest the dealer's left taking ties
+    fn best_showing(&self) -> usize
+    {
+        let n = self.names.len();
+        let mut best: Option<(usize, HandRank)> = None;
+
+        for seat in (1..=n).map(|i| (self.button + i) % n).filter(|seat| !self.betting.seats[*seat].folded)
+        {
+            let showing = evaluate_partial(&up_cards(&self.hands[seat])).unwrap();
+            if best.is_none_or(|(_, rank)| showing > rank)
+            {
+                best = Some((seat, showing));
+            }
+        }
+
+        best.map(|(seat, _)| seat).unwrap_or(self.button)
+    }
+
+    // Antes, two cards down and one up to everyone with chips, then the bring-in
+    pub fn start_hand(&mut self)
+    {
+        let chips: Vec<u32> = self.betting.seats.iter().map(|s| s.chips).collect();
+        self.betting = Betting::new(&chips, BIG_BET);
+        self.button = next_active(&self.betting, self.button);
+        self.deck = Stack::new_deck(false);
+        self.deck.shuffle();
+
+        self.betting.ante(ANTE);
+        for seat in 0..self.names.len()
+        {
+            self.hands[seat] = if self.betting.seats[seat].folded { vec![] } else {
+                let mut cards: Vec<Card> = self.deck.deal(2).into_iter().map(Card::hide).collect();
+                cards.push(self.deck.draw());
+                cards
+            };
+        }
+
+        let bring_in = self.bring_in_seat();
+        self.betting.post(bring_in, BRING_IN);
+        self.betting.open(next_active(&self.betting, bring_in));
+    }
+
+    pub fn deal_street(&mut self, face_up: bool)
+    {
+        for seat in self.betting.in_hand()
+        {
+            let card = self.deck.draw();
+            self.hands[seat].push(if face_up { card } else { card.hide() });
+        }
+
+        let first = self.best_showing();
+        self.betting.next_street(first);
+    }
+
+    fn betting_round(&mut self)
+    {
+        let hands = &self.hands;
+
+        betting_round(&mut self.betting, &self.names, |seat| made_strength(rank(&hands[seat])));
+    }
+
+    pub fn play_hand(&mut self)
+    {
+        self.start_hand();
+        println!("{}", self);
+        self.betting_round();
+
+        for (street, face_up) in STREETS.iter()
+        {
+            if self.betting.hand_over() { break }
+
+            self.deal_street(*face_up);
+            println!("+ --- {} --- +", street);
+            println!("{}", self);
+            self.betting_round();
+        }
+
+        showdown(&mut self.betting, &self.names, &mut self.hands, |hand| evaluate(hand).ok());
+    }
+}
+
+impl fmt::Display for SevenCardStud {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![format!("+ --- Pot: {} --- +", self.betting.pot())];
+
+        lines.extend(seat_lines(&self.betting, &self.names, &self.hands, self.button));
+        lines.push("+ ------------------- +".to_string());
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for SevenCardStud
+{
+    // The human wins by taking every chip on the table
+    fn win(&self) -> bool
+    {
+        last_standing(&self.betting)
+    }
+
+    fn handle_input(&mut self)
+    {
+        let commands = ["Deal", "Display", "Leave"];
+        println!("Select a command: ");
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&commands)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) =>
+            {
+                println!("+ -------------------- + ");
+                match commands[index] {
+                    "Deal" => self.play_hand(),
+                    "Display" => println!("{}", self),
+                    "Leave" => self.leave = true,
+                    _ => {
+                        println!("Invalid selection");
+                        self.handle_input();
+                    }
+                }
+            }
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+    use crate::poker_table::seat_view;
+
+    // Two cards down and the rest up, as they would be dealt before seventh street
+    fn stud_hand(cards: &str) -> Vec<Card>
+    {
+        parse_cards(cards).unwrap().into_iter().enumerate().map(|(i, c)| if i < 2 { c.hide() } else { c }).collect()
+    }
+
+    fn bot_round(game: &mut SevenCardStud)
+    {
+        while !game.betting.round_over()
+        {
+            let bet = game.betting.bot_bet(made_strength(rank(&game.hands[game.betting.to_act])));
+            game.betting.act(bet).unwrap();
+        }
+    }
+
+    #[test]
+    fn bring_in()
+    {
+        let mut game = SevenCardStud::new(3).unwrap();
+        game.hands = vec![stud_hand("AsAh4d"), stud_hand("KsKh4c"), stud_hand("2s3hJd")];
+        assert_eq!(game.bring_in_seat(), 1);
+
+        game.betting.seats[1].folded = true;
+        assert_eq!(game.bring_in_seat(), 0);
+    }
+
+    #[test]
+    fn first_to_act()
+    {
+        let mut game = SevenCardStud::new(3).unwrap();
+        game.button = 0;
+        game.hands = vec![stud_hand("2s3hKdQc"), stud_hand("AsAhQdJc"), stud_hand("9s9h5d5c")];
+
+        // Seat 1 holds aces, but they are face down
+        assert_eq!(game.best_showing(), 2);
+
+        // Equal hands showing go to the first seat after the dealer
+        game.hands[0] = stud_hand("2s3hKdKc");
+        game.hands[2] = stud_hand("9s9hKhKs");
+        assert_eq!(game.best_showing(), 2);
+        game.button = 2;
+        assert_eq!(game.best_showing(), 0);
+    }
+
+    #[test]
+    fn cards_by_seat()
+    {
+        let mut game = SevenCardStud::new(4).unwrap();
+        game.start_hand();
+
+        for (seat, hand) in game.hands.iter().enumerate()
+        {
+            assert_eq!(up_cards(hand).len(), 1);
+            assert!(!seat_view(hand, seat, seat).contains('?'));
+            assert_eq!(seat_view(hand, seat, HUMAN).contains('?'), seat != HUMAN);
+        }
+        assert_eq!(game.betting.pot(), 4 * ANTE + BRING_IN);
+    }
+
+    #[test]
+    fn chips_are_conserved()
+    {
+        let mut game = SevenCardStud::new(7).unwrap();
+
+        for _ in 0..20
+        {
+            game.start_hand();
+            bot_round(&mut game);
+            for (_, face_up) in STREETS.iter()
+            {
+                if game.betting.hand_over() { break }
+                game.deal_street(*face_up);
+                bot_round(&mut game);
+            }
+            showdown(&mut game.betting, &game.names, &mut game.hands, |hand| evaluate(hand).ok());
+
+            assert_eq!(game.betting.seats.iter().map(|s| s.chips).sum::<u32>(), STARTING_CHIPS * 7);
+        }
+    }
+}
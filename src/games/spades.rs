@@ -0,0 +1,548 @@
+use std::fmt;
+use crate::deck::{Stack, Card, Suit};
+use crate::trick::{StandardRules, Trick, TrickRound, sort_hand, prefer, bot_card};
+use crate::game::{Game, HUMAN};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+const PLAYERS: usize = 4;
+const TRICKS: u8 = 13;
+const GAME_OVER: i32 = 500;
+// A team this far down has lost the game
+const MERCY: i32 = -200;
+// Every ten bags costs a team a hundred points
+const BAG_LIMIT: u32 = 10;
+const BAG_PENALTY: i32 = 100;
+// A team must be this far behind before a player can bid blind nil
+const BLIND_NIL_DEFICIT: i32 = 100;
+const RULES: StandardRules = StandardRules { trump: Some(Suit::Spade) };
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bid
+{
+    Nil,
+    BlindNil,
+    Tricks(u8)
+}
+
+impl Bid
+{
+    fn is_nil(&self) -> bool
+    {
+        matches!(self, Bid::Nil | Bid::BlindNil)
+    }
+}
+
+impl fmt::Display for Bid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bid::Nil => write!(f, "nil"),
+            Bid::BlindNil => write!(f, "blind nil"),
+            Bid::Tricks(n) => write!(f, "{}", n)
+        }
+    }
+}
+
+// Partners sit across from each other, so seats 0 and 2 play against 1 and 3
+fn team(seat: usize) -> usize
+{
+    seat % 2
+}
+
+// Points and bags for one team's round. Nil bids win or lose 100, or 200 blind,
+// on their own, and the tricks a nil bidder takes count only as bags.
+pub fn score_team(bids: &[Bid], tricks: &[usize]) -> (i32, u32)
+{
+    let mut points = 0;
+    let mut bags = 0;
+    let mut contract = 0;
+    let mut made = 0;
+
+    for (bid, taken) in bids.iter().zip(tricks.iter())
+    {
+        match bid {
+            Bid::Nil => points += if *taken == 0 { 100 } else { -100 },
+            Bid::BlindNil => points += if *taken == 0 { 200 } else { -200 },
+            Bid::Tricks(n) => {
+                contract += *n as usize;
+                made += taken;
+            }
+        }
+        if bid.is_nil() { bags += *taken as u32 }
+    }
+
+    if contract > 0 || made > 0
+    {
+        if made >= contract
+        {
+            points += 10 * contract as i32 + (made - contract) as i32;
+            bags += (made - contract) as u32;
+        }
+        else
+        {
+            points -= 10 * contract as i32;
+        }
+    }
+
+    (points, bags)
+}
+
+// Counts the tricks a hand should take: high cards in short enough suits, high
+// spades with enough cover and every spade beyond the third
+fn bot_bid(hand: &[Card]) -> Bid
+{
+    let ranks = |suit: Suit| -> Vec<u8> { hand.iter().filter(|c| c.suit == suit).map(|c| c.rank).collect() };
+    let spades = ranks(Suit::Spade);
+    let mut tricks = 0.0;
+
+    if spades.contains(&14) { tricks += 1.0 }
+    if spades.contains(&13) && spades.len() >= 2 { tricks += 1.0 }
+    if spades.contains(&12) && spades.len() >= 3 { tricks += 1.0 }
+    tricks += spades.len().saturating_sub(3) as f64;
+
+    for suit in [Suit::Heart, Suit::Club, Suit::Diamond].iter()
+    {
+        let cards = ranks(*suit);
+        if cards.contains(&14) { tricks += 1.0 }
+        if cards.contains(&13) && (2..=5).contains(&cards.len()) { tricks += 0.8 }
+        // Short suits let spades ruff
+        if cards.len() <= 1 && spades.len() >= 3 { tricks += 0.5 }
+    }
+
+    if tricks < 1.0 && spades.len() <= 3 && spades.iter().all(|r| *r < 10)
+    {
+        return Bid::Nil
+    }
+
+    Bid::Tricks((tricks.round() as u8).clamp(1, TRICKS))
+}
+
+// Nil bidders stay under the winning card. Everyone else leads aces, lets their
+// partner's winning card stand and otherwise takes the trick as cheaply as they can.
+fn bot_play(hand: &[Card], legal: &[usize], trick: &Trick, seat: usize, nil: bool) -> usize
+{
+    let lowest = *legal.iter().min_by_key(|i| (hand[**i].suit == Suit::Spade, hand[**i].rank)).unwrap();
+
+    if nil
+    {
+        return bot_card(hand, legal, trick, seat, &RULES, false)
+    }
+    if trick.plays.is_empty()
+    {
+        return legal.iter().find(|i| hand[**i].rank == 14 && hand[**i].suit != Suit::Spade).copied().unwrap_or(lowest)
+    }
+    if trick.winner(&RULES) == Some((seat + 2) % PLAYERS)
+    {
+        return lowest
+    }
+
+    bot_card(hand, legal, trick, seat, &RULES, true)
+}
+
+pub struct Spades
+{
+    names: Vec<String>,
+    // Scores and bags by team, with the human's team first
+    scores: Vec<i32>,
+    bags: Vec<u32>,
+    dealer: usize,
+    bids: Vec<Option<Bid>>,
+    round: TrickRound,
+    spades_broken: bool,
+    leave: bool,
+}
+
+impl Spades
+{
+    pub fn new() -> Spades
+    {
+        let names = ["You", "Bot 1", "Partner", "Bot 3"].iter().map(|n| n.to_string()).collect();
+        let mut game = Spades
+        {
+            names,
+            scores: vec![0; 2],
+            bags: vec![0; 2],
+            dealer: PLAYERS - 1,
+            bids: vec![None; PLAYERS],
+            round: TrickRound::new(vec![vec![]; PLAYERS], 0),
+            spades_broken: false,
+            leave: false
+        };
+
+        game.deal();
+        game
+    }
+
+    pub fn play()
+    {
+        let mut game = Spades::new();
+
+        while !game.leave && !game.game_over()
+        {
+            game.handle_input();
+        }
+
+        println!("{}", game.score_lines().join("\n"));
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    // The player left of the dealer bids first and leads the first trick
+    pub fn deal(&mut self)
+    {
+        let mut deck = Stack::new_deck(false);
+        deck.shuffle();
+
+        let hands = (0..PLAYERS).map(|_| {
+            let mut hand = deck.deal(TRICKS as usize);
+            sort_hand(&mut hand, &RULES);
+            hand
+        }).collect();
+
+        self.round = TrickRound::new(hands, (self.dealer + 1) % PLAYERS);
+        self.bids = vec![None; PLAYERS];
+        self.spades_broken = false;
+    }
+
+    fn to_bid(&self) -> Option<usize>
+    {
+        let bids = self.bids.iter().filter(|b| b.is_some()).count();
+
+        if bids == PLAYERS { None } else { Some((self.dealer + 1 + bids) % PLAYERS) }
+    }
+
+    fn blind_nil_allowed(&self, seat: usize) -> bool
+    {
+        let us = team(seat);
+
+        self.scores[1 - us] - self.scores[us] >= BLIND_NIL_DEFICIT
+    }
+
+    pub fn bid(&mut self, bid: Bid) -> Result<(), String>
+    {
+        let seat = self.to_bid().ok_or_else(|| String::from("Bidding is over"))?;
+
+        match bid {
+            Bid::Tricks(n) if n == 0 || n > TRICKS => return Err(format!("Bid nil or between 1 and {} tricks", TRICKS)),
+            Bid::BlindNil if !self.blind_nil_allowed(seat) => return Err(String::from("Blind nil is only allowed when your team is 100 behind")),
+            _ => ()
+        };
+
+        println!("{} bid {}", self.names[seat], bid);
+        self.bids[seat] = Some(bid);
+        Ok(())
+    }
+
+    // Spades can't be led until one has been played, unless there's nothing else
+    pub fn legal(&self) -> Vec<usize>
+    {
+        let hand = &self.round.hands[self.round.to_play()];
+        let legal = self.round.legal(&RULES);
+
+        if self.round.trick.plays.is_empty() && !self.spades_broken
+        {
+            return prefer(hand, legal, |c| c.suit != Suit::Spade)
+        }
+
+        legal
+    }
+
+    pub fn play_card(&mut self, index: usize) -> Result<Option<usize>, String>
+    {
+        if !self.legal().contains(&index)
+        {
+            return Err(String::from("Unable to play that card"))
+        }
+
+        let seat = self.round.to_play();
+        let card = self.round.hands[seat][index];
+        let winner = self.round.play(index, &RULES)?;
+        self.spades_broken |= card.suit == Suit::Spade;
+
+        println!("{}: {}", self.names[seat], card);
+        if let Some(winner) = winner
+        {
+            println!("{} took the trick", self.names[winner]);
+        }
+
+        Ok(winner)
+    }
+
+    // Scores both teams, takes off a hundred for every ten bags and passes the deal.
+    // Returns each team's points for the round.
+    pub fn score_round(&mut self) -> Vec<i32>
+    {
+        let mut round_points = vec![];
+
+        for us in 0..2
+        {
+            let seats = [us, us + 2];
+            let bids: Vec<Bid> = seats.iter().map(|s| self.bids[*s].unwrap()).collect();
+            let tricks: Vec<usize> = seats.iter().map(|s| self.round.tricks_won[*s]).collect();
+            let (mut points, bags) = score_team(&bids, &tricks);
+
+            self.bags[us] += bags;
+            while self.bags[us] >= BAG_LIMIT
+            {
+                self.bags[us] -= BAG_LIMIT;
+                points -= BAG_PENALTY;
+            }
+            self.scores[us] += points;
+            round_points.push(points);
+        }
+        self.dealer = (self.dealer + 1) % PLAYERS;
+
+        round_points
+    }
+
+    pub fn game_over(&self) -> bool
+    {
+        let high = *self.scores.iter().max().unwrap();
+        let low = *self.scores.iter().min().unwrap();
+
+        (high >= GAME_OVER || low <= MERCY) && high != low
+    }
+
+    fn get_bid(&self) -> Bid
+    {
+        if self.blind_nil_allowed(HUMAN)
+        {
+            let options = ["Look at my cards", "Bid blind nil"];
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Your team is behind, bid before seeing your cards?")
+                .items(&options)
+                .default(0)
+                .interact_on_opt(&Term::stderr()).unwrap();
+            if selection == Some(1) { return Bid::BlindNil }
+        }
+
+        println!("{}", self);
+        let mut options = vec!["Nil".to_string()];
+        options.extend((1..=TRICKS).map(|n| n.to_string()));
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Your bid")
+            .items(&options)
+            .default(3)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(0) => Bid::Nil,
+            Some(n) => Bid::Tricks(n as u8),
+            None => Bid::Tricks(1)
+        }
+    }
+
+    fn bidding(&mut self)
+    {
+        while let Some(seat) = self.to_bid()
+        {
+            let bid = if seat == HUMAN { self.get_bid() } else { bot_bid(&self.round.hands[seat]) };
+            self.bid(bid).unwrap_or_else(|err| println!("{}", err));
+        }
+    }
+
+    fn play_bots(&mut self)
+    {
+        while !self.round.finished() && self.round.to_play() != HUMAN
+        {
+            let seat = self.round.to_play();
+            let nil = self.bids[seat].is_some_and(|b| b.is_nil());
+            let index = bot_play(&self.round.hands[seat], &self.legal(), &self.round.trick, seat, nil);
+            self.play_card(index).unwrap();
+        }
+    }
+
+    fn get_play(&mut self)
+    {
+        let legal = self.legal();
+        let mut options: Vec<String> = legal.iter().map(|i| self.round.hands[HUMAN][*i].to_string()).collect();
+        options.push("Leave".to_string());
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Play a card")
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(index) if index == legal.len() => self.leave = true,
+            Some(index) => { self.play_card(legal[index]).unwrap_or_else(|err| { println!("{}", err); None }); },
+            None => println!("User did not select anything")
+        }
+    }
+
+    fn score_lines(&self) -> Vec<String>
+    {
+        vec![
+            format!("You and Partner {:>4} ({} bags)", self.scores[0], self.bags[0]),
+            format!("Bots 1 and 3    {:>4} ({} bags)", self.scores[1], self.bags[1])
+        ]
+    }
+}
+
+impl fmt::Display for Spades {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let trick: Vec<String> = self.round.trick.plays.iter().map(|(seat, card)| format!("{}: {}", self.names[*seat], card)).collect();
+        let hand: Vec<String> = self.round.hands[HUMAN].iter().map(|c| c.to_string()).collect();
+
+        let mut lines = vec!["+ --- Scores --- +".to_string()];
+        lines.extend(self.score_lines());
+        lines.push("+ ------------------- +".to_string());
+        for seat in 0..PLAYERS
+        {
+            let bid = self.bids[seat].map_or(String::from("-"), |b| b.to_string());
+            lines.push(format!("{:<8} bid {:<9} took {}", self.names[seat], bid, self.round.tricks_won[seat]));
+        }
+        lines.push(format!("Trick: {}", trick.join(" | ")));
+        lines.push(format!("Your hand: {}", hand.join(" ")));
+        lines.push("+ ------------------- +".to_string());
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Game for Spades
+{
+    fn win(&self) -> bool
+    {
+        self.game_over() && self.scores[0] > self.scores[1]
+    }
+
+    fn handle_input(&mut self)
+    {
+        if self.to_bid().is_some()
+        {
+            self.bidding();
+            return
+        }
+
+        self.play_bots();
+        if !self.round.finished()
+        {
+            println!("{}", self);
+            self.get_play();
+            self.play_bots();
+        }
+
+        if self.round.finished()
+        {
+            let points = self.score_round();
+            println!("You and Partner scored {}, the bots scored {}", points[0], points[1]);
+            if !self.game_over()
+            {
+                self.deal();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    #[test]
+    fn scoring()
+    {
+        assert_eq!(score_team(&[Bid::Tricks(3), Bid::Tricks(2)], &[4, 3]), (52, 2));
+        assert_eq!(score_team(&[Bid::Tricks(3), Bid::Tricks(2)], &[1, 3]), (-50, 0));
+        assert_eq!(score_team(&[Bid::Nil, Bid::Tricks(4)], &[0, 5]), (141, 1));
+        assert_eq!(score_team(&[Bid::Nil, Bid::Tricks(4)], &[2, 3]), (-140, 2));
+        assert_eq!(score_team(&[Bid::BlindNil, Bid::Tricks(4)], &[0, 4]), (240, 0));
+        assert_eq!(score_team(&[Bid::Nil, Bid::Nil], &[0, 1]), (0, 1));
+    }
+
+    #[test]
+    fn bag_penalty()
+    {
+        let mut game = Spades::new();
+        game.bags = vec![8, 0];
+        game.bids = vec![Some(Bid::Tricks(2)), Some(Bid::Tricks(3)), Some(Bid::Tricks(2)), Some(Bid::Tricks(3))];
+        game.round.tricks_won = vec![4, 3, 3, 3];
+
+        assert_eq!(game.score_round(), vec![-57, 60]);
+        assert_eq!(game.bags, vec![1, 0]);
+        assert_eq!(game.dealer, 0);
+    }
+
+    #[test]
+    fn bidding()
+    {
+        let mut game = Spades::new();
+
+        assert_eq!(game.to_bid(), Some(0));
+        assert!(game.bid(Bid::Tricks(14)).is_err());
+        assert!(game.bid(Bid::BlindNil).is_err());
+        game.bid(Bid::Tricks(4)).unwrap();
+        assert_eq!(game.to_bid(), Some(1));
+
+        game.scores = vec![100, 250];
+        assert!(game.blind_nil_allowed(2));
+        assert!(!game.blind_nil_allowed(1));
+    }
+
+    #[test]
+    fn bot_bids()
+    {
+        let strong = parse_cards("AsKsQs9s5sAhKh4hAc2c3d4d5d").unwrap();
+        let weak = parse_cards("2s5s9s3h6h8hJh4c7cTc2d5dQd").unwrap();
+
+        assert_eq!(bot_bid(&strong), Bid::Tricks(8));
+        assert_eq!(bot_bid(&weak), Bid::Nil);
+    }
+
+    #[test]
+    fn spades_broken()
+    {
+        let mut game = Spades::new();
+        game.round = TrickRound::new(vec![parse_cards("2s3h").unwrap(), parse_cards("4s").unwrap(), parse_cards("5c").unwrap(), parse_cards("6c").unwrap()], 0);
+
+        assert_eq!(game.legal(), vec![1]);
+        game.round.hands[0] = parse_cards("2s3s").unwrap();
+        assert_eq!(game.legal(), vec![0, 1]);
+    }
+
+    #[test]
+    fn bots_play_a_round()
+    {
+        let mut game = Spades::new();
+        for seat in 0..PLAYERS
+        {
+            game.bid(bot_bid(&game.round.hands[seat])).unwrap();
+        }
+
+        while !game.round.finished()
+        {
+            let seat = game.round.to_play();
+            let nil = game.bids[seat].unwrap().is_nil();
+            let index = bot_play(&game.round.hands[seat], &game.legal(), &game.round.trick, seat, nil);
+            game.play_card(index).unwrap();
+        }
+
+        assert_eq!(game.round.tricks_played(), TRICKS as usize);
+        let expected: Vec<(i32, u32)> = (0..2).map(|us| {
+            let bids = [game.bids[us].unwrap(), game.bids[us + 2].unwrap()];
+            score_team(&bids, &[game.round.tricks_won[us], game.round.tricks_won[us + 2]])
+        }).collect();
+
+        let points = game.score_round();
+        for us in 0..2
+        {
+            let (team_points, bags) = expected[us];
+            assert_eq!(points[us], team_points - (bags / BAG_LIMIT) as i32 * BAG_PENALTY);
+            assert_eq!(game.scores[us], points[us]);
+            assert_eq!(game.bags[us], bags % BAG_LIMIT);
+        }
+        assert_eq!(game.dealer, 0);
+    }
+}
@@ -0,0 +1,278 @@
+use std::fmt;
+use std::time::Instant;
+use rand::{thread_rng, seq::SliceRandom};
+use crate::deck::{Stack, Card};
+use crate::game::{Game, HUMAN};
+use dialoguer::{
+    Select,
+    theme::ColorfulTheme,
+    console::Term
+};
+
+// Games between two well shuffled halves can go on for ever, so they stop here
+// and whoever holds more cards wins
+const MAX_TURNS: usize = 5000;
+// Cards each player puts face down in a war before turning the next one up
+const WAR_CARDS: usize = 3;
+const DEFAULT_GAMES: usize = 10000;
+
+pub struct War
+{
+    names: Vec<String>,
+    piles: Vec<Stack>,
+    turns: usize,
+    wars: usize,
+    leave: bool,
+}
+
+impl War
+{
+    pub fn new() -> War
+    {
+        let mut deck = Stack::new_deck(false);
+        deck.shuffle();
+        let half = deck.size() / 2;
+        let piles = vec![Stack::from_cards(deck.deal(half)), Stack::from_cards(deck.deal(half))];
+
+        War { names: vec!["You".to_string(), "Bot".to_string()], piles, turns: 0, wars: 0, leave: false }
+    }
+
+    pub fn play()
+    {
+        let mut game = War::new();
+
+        while !game.leave && !game.over()
+        {
+            game.handle_input();
+        }
+
+        if game.leave
+        {
+            return println!("You left after {} turns holding {} cards", game.turns, game.piles[HUMAN].size())
+        }
+        println!("{}", game);
+        if game.win()
+        {
+            println!("You won 😀");
+        }
+        else
+        {
+            println!("You lost 😥");
+        }
+    }
+
+    pub fn over(&self) -> bool
+    {
+        self.turns >= MAX_TURNS || self.piles.iter().any(|p| p.size() == 0)
+    }
+
+    // The player left holding cards, or the one holding more once the turns run out
+    pub fn winner(&self) -> Option<usize>
+    {
+        let (a, b) = (self.piles[0].size(), self.piles[1].size());
+
+        if b == 0 || (self.turns >= MAX_TURNS && a > b)
+        {
+            Some(0)
+        }
+        else if a == 0 || (self.turns >= MAX_TURNS && b > a)
+        {
+            Some(1)
+        }
+        else
+        {
+            None
+        }
+    }
+
+    // Both players turn up a card and the higher takes everything played. On a tie
+    // each puts cards face down and turns up another, keeping back their last card to
+    // turn up if they run short, and a player who can't turn up a card loses the lot.
+    pub fn turn(&mut self) -> (usize, Vec<Card>)
+    {
+        let mut pot = vec![];
+        self.turns += 1;
+
+        let winner = loop
+        {
+            if let Some(empty) = self.piles.iter().position(|p| p.size() == 0)
+            {
+                break 1 - empty
+            }
+
+            let up = [self.piles[0].draw(), self.piles[1].draw()];
+            pot.extend_from_slice(&up);
+            if up[0].rank != up[1].rank
+            {
+                break if up[0].rank > up[1].rank { 0 } else { 1 }
+            }
+
+            self.wars += 1;
+            for pile in self.piles.iter_mut()
+            {
+                let down = WAR_CARDS.min(pile.size().saturating_sub(1));
+                pot.extend(pile.deal(down));
+            }
+        };
+
+        // The winnings go under in no particular order, which keeps games from cycling
+        let mut winnings = pot.clone();
+        winnings.shuffle(&mut thread_rng());
+        self.piles[winner].put_bottom(winnings);
+
+        (winner, pot)
+    }
+
+    fn play_turns(&mut self, turns: usize)
+    {
+        for _ in 0..turns
+        {
+            if self.over() { break }
+
+            let (winner, pot) = self.turn();
+            if turns == 1
+            {
+                let cards: Vec<String> = pot.iter().map(|c| c.to_string()).collect();
+                println!("{} takes {}", self.names[winner], cards.join(" "));
+            }
+        }
+        println!("{}", self);
+    }
+}
+
+impl fmt::Display for War {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "+ --- Turn {}, {} wars --- +\nYou: {} cards\nBot: {} cards",
+            self.turns, self.wars, self.piles[HUMAN].size(), self.piles[1].size())
+    }
+}
+
+impl Game for War
+{
+    fn win(&self) -> bool
+    {
+        self.winner() == Some(HUMAN)
+    }
+
+    fn handle_input(&mut self)
+    {
+        let options = ["Play a turn", "Play 100 turns", "Play to the end", "Leave"];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&options)
+            .default(0)
+            .interact_on_opt(&Term::stderr()).unwrap();
+
+        match selection {
+            Some(0) => self.play_turns(1),
+            Some(1) => self.play_turns(100),
+            Some(2) => self.play_turns(MAX_TURNS),
+            Some(_) => self.leave = true,
+            None => println!("User did not select anything")
+        }
+    }
+}
+
+// Plays whole games of war with nobody at the table, which mostly measures how
+// quickly a `Stack` deals from the top and takes cards underneath
+pub fn benchmark(args: &[String]) -> Result<(), String>
+{
+    let games = match args.first() {
+        Some(arg) => arg.parse().map_err(|_| format!("{} is not a number of games", arg))?,
+        None => DEFAULT_GAMES
+    };
+
+    let start = Instant::now();
+    let (mut turns, mut wars, mut unfinished) = (0, 0, 0);
+    for _ in 0..games
+    {
+        let mut game = War::new();
+        while !game.over()
+        {
+            game.turn();
+        }
+        turns += game.turns;
+        wars += game.wars;
+        if game.piles.iter().all(|p| p.size() > 0) { unfinished += 1 }
+    }
+    let elapsed = start.elapsed();
+
+    println!("Played {} games in {:.2?}", games, elapsed);
+    println!("{} turns and {} wars, {} games cut off at {} turns", turns, wars, unfinished, MAX_TURNS);
+    if turns > 0
+    {
+        println!("{:.0} ns per turn", elapsed.as_nanos() as f64 / turns as f64);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::deck::parse_cards;
+
+    fn deal(a: &str, b: &str) -> War
+    {
+        let mut game = War::new();
+        game.piles = vec![Stack::from_cards(parse_cards(a).unwrap()), Stack::from_cards(parse_cards(b).unwrap())];
+        game
+    }
+
+    #[test]
+    fn battles()
+    {
+        let mut game = deal("As2c", "Kd3c");
+
+        assert_eq!(game.turn().0, 0);
+        assert_eq!(game.piles[0].size(), 3);
+        assert_eq!(game.turn().0, 1);
+        assert_eq!((game.piles[0].size(), game.piles[1].size()), (2, 2));
+        assert_eq!(game.winner(), None);
+    }
+
+    #[test]
+    fn wars()
+    {
+        let mut game = deal("9s2c3c4c5c", "9h2d3d4dTd");
+
+        let (winner, pot) = game.turn();
+        assert_eq!(winner, 1);
+        assert_eq!(pot.len(), 10);
+        assert_eq!(game.wars, 1);
+        assert_eq!(game.winner(), Some(1));
+    }
+
+    #[test]
+    fn running_short()
+    {
+        // Two cards left means one goes down and the other turns up
+        let mut game = deal("9s2cKc", "9h2d3d4d5d6d");
+        assert_eq!(game.turn(), (0, parse_cards("9s9h2c2d3d4dKc5d").unwrap()));
+
+        // No cards left to turn up loses the war
+        let mut game = deal("9s", "9h2d");
+        assert_eq!(game.turn().0, 1);
+        assert_eq!(game.piles[1].size(), 3);
+    }
+
+    #[test]
+    fn cut_off()
+    {
+        let mut game = deal("As", "2s");
+        game.turns = MAX_TURNS;
+        assert!(game.over());
+        assert_eq!(game.winner(), None);
+
+        for _ in 0..20
+        {
+            let mut game = War::new();
+            while !game.over()
+            {
+                game.turn();
+            }
+            assert_eq!(game.piles.iter().map(|p| p.size()).sum::<usize>(), 52);
+            assert!(game.turns <= MAX_TURNS);
+        }
+    }
+}
@@ -1,20 +1,57 @@
 #[path = "games/aces_up.rs"] mod aces_up;
 #[path = "games/klondike.rs"] mod klondike;
-#[path = "games/game.rs"] mod game;
-#[path = "utils/deck.rs"] mod deck;
+#[path = "games/canfield.rs"] mod canfield;
+#[path = "games/forty_thieves.rs"] mod forty_thieves;
+#[path = "games/bakers_dozen.rs"] mod bakers_dozen;
+#[path = "games/scorpion.rs"] mod scorpion;
+#[path = "games/clock.rs"] mod clock;
+#[path = "games/accordion.rs"] mod accordion;
+#[path = "games/blackjack.rs"] mod blackjack;
+#[path = "games/blackjack_trainer.rs"] mod blackjack_trainer;
+#[path = "games/baccarat.rs"] mod baccarat;
+#[path = "games/holdem.rs"] mod holdem;
+#[path = "games/poker_table.rs"] mod poker_table;
+#[path = "games/five_card_draw.rs"] mod five_card_draw;
+#[path = "games/seven_card_stud.rs"] mod seven_card_stud;
+#[path = "games/hearts.rs"] mod hearts;
+#[path = "games/spades.rs"] mod spades;
+#[path = "games/euchre.rs"] mod euchre;
+#[path = "games/bridge.rs"] mod bridge;
+#[path = "games/cribbage_game.rs"] mod cribbage_game;
+#[path = "games/gin_rummy.rs"] mod gin_rummy;
+#[path = "games/crazy_eights.rs"] mod crazy_eights;
+#[path = "games/war.rs"] mod war;
+#[path = "games/ratscrew.rs"] mod ratscrew;
+#[path = "games/go_fish.rs"] mod go_fish;
+#[path = "games/old_maid.rs"] mod old_maid;
+#[path = "games/durak.rs"] mod durak;
+#[path = "games/president.rs"] mod president;
+#[path = "games/oh_hell.rs"] mod oh_hell;
+#[path = "games/pinochle.rs"] mod pinochle;
+#[path = "games/game.rs"] pub mod game;
+#[path = "utils/deck.rs"] pub mod deck;
+#[path = "utils/shoe.rs"] pub mod shoe;
+#[path = "utils/poker.rs"] pub mod poker;
+#[path = "utils/betting.rs"] pub mod betting;
+#[path = "utils/equity.rs"] pub mod equity;
+#[path = "utils/trick.rs"] pub mod trick;
+#[path = "utils/cribbage.rs"] pub mod cribbage;
+#[path = "utils/rummy.rs"] pub mod rummy;
+#[path = "utils/shedding.rs"] pub mod shedding;
+
+pub use war::benchmark as war_benchmark;
 
 use dialoguer::{
     Select,
     theme::ColorfulTheme,
     console::Term
 };
-use std::collections::HashMap;
 
 
 
 pub fn select_game() -> std::io::Result<()> {
 
-    let games = ["Aces Up", "Klondike"];
+    let games = ["Aces Up", "Klondike", "Canfield", "Forty Thieves", "Baker's Dozen", "Scorpion", "Clock", "Accordion", "Blackjack", "Baccarat", "Texas Hold'em", "Five Card Draw", "Seven Card Stud", "Hearts", "Spades", "Euchre", "Bridge", "Cribbage", "Gin Rummy", "Crazy Eights", "War", "Egyptian Ratscrew", "Go Fish", "Old Maid", "Durak", "President", "Oh Hell", "Pinochle"];
 
 
     let selection = Select::with_theme(&ColorfulTheme::default())
@@ -30,9 +67,35 @@ pub fn select_game() -> std::io::Result<()> {
             match games[index] {
                 "Aces Up" => aces_up::AcesUpGame::play(),
                 "Klondike" => klondike::Klondike::play(),
+                "Canfield" => canfield::Canfield::play(),
+                "Forty Thieves" => forty_thieves::FortyThieves::play(),
+                "Baker's Dozen" => bakers_dozen::BakersDozen::play(),
+                "Scorpion" => scorpion::Scorpion::play(),
+                "Clock" => clock::Clock::play(),
+                "Accordion" => accordion::Accordion::play(),
+                "Blackjack" => blackjack::Blackjack::play(),
+                "Baccarat" => baccarat::Baccarat::play(),
+                "Texas Hold'em" => holdem::Holdem::play(),
+                "Five Card Draw" => five_card_draw::FiveCardDraw::play(),
+                "Seven Card Stud" => seven_card_stud::SevenCardStud::play(),
+                "Hearts" => hearts::Hearts::play(),
+                "Spades" => spades::Spades::play(),
+                "Euchre" => euchre::Euchre::play(),
+                "Bridge" => bridge::Bridge::play(),
+                "Cribbage" => cribbage_game::Cribbage::play(),
+                "Gin Rummy" => gin_rummy::GinRummy::play(),
+                "Crazy Eights" => crazy_eights::CrazyEights::play(),
+                "War" => war::War::play(),
+                "Egyptian Ratscrew" => ratscrew::Ratscrew::play(),
+                "Go Fish" => go_fish::GoFish::play(),
+                "Old Maid" => old_maid::OldMaid::play(),
+                "Durak" => durak::Durak::play(),
+                "President" => president::President::play(),
+                "Oh Hell" => oh_hell::OhHell::play(),
+                "Pinochle" => pinochle::Pinochle::play(),
                 _ => {
                     println!("Invalid selection");
-                    select_game();
+                    select_game()?;
                 }
             }
         }
@@ -1,8 +1,21 @@
-use cards;
 use std::io;
+use std::env;
+use std::process;
 
 fn main() -> io::Result<()> {
-    cards::select_game()?;
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(|a| a.as_str()) {
+        Some("equity") => if let Err(err) = cards::equity::run(&args[1..]) {
+            eprintln!("{}", err);
+            process::exit(1);
+        },
+        Some("war") => if let Err(err) = cards::war_benchmark(&args[1..]) {
+            eprintln!("{}", err);
+            process::exit(1);
+        },
+        _ => cards::select_game()?
+    }
 
     Ok(())
 }
@@ -0,0 +1,443 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bet {
+    Fold,
+    Check,
+    Call,
+    // Raise the bet to this many chips in front of the player for the street
+    Raise(u32),
+    AllIn
+}
+
+impl fmt::Display for Bet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bet::Fold => write!(f, "folds"),
+            Bet::Check => write!(f, "checks"),
+            Bet::Call => write!(f, "calls"),
+            Bet::Raise(to) => write!(f, "raises to {}", to),
+            Bet::AllIn => write!(f, "goes all in")
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Seat {
+    pub chips: u32,
+    // Chips put in on the current street, and over the whole hand
+    pub street: u32,
+    pub contributed: u32,
+    pub folded: bool,
+}
+
+impl Seat {
+    pub fn all_in(&self) -> bool {
+        !self.folded && self.chips == 0
+    }
+
+    fn can_act(&self) -> bool {
+        !self.folded && self.chips > 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pot {
+    pub amount: u32,
+    pub eligible: Vec<usize>,
+}
+
+// No limit betting for a single hand of poker. Seats that start the hand without
+// chips sit it out. Blinds and bring-ins are posted with `post` and antes with
+// `ante`, then each street is opened with the first seat to act and bets are taken
+// from `to_act` until `round_over`.
+pub struct Betting {
+    pub seats: Vec<Seat>,
+    pub current_bet: u32,
+    pub min_raise: u32,
+    pub to_act: usize,
+    big_blind: u32,
+    pending: Vec<bool>,
+    // Seats that already acted when a short all in came in may only call or fold
+    may_raise: Vec<bool>,
+}
+
+impl Betting {
+    pub fn new(chips: &[u32], big_blind: u32) -> Betting {
+        let seats = chips.iter().map(|c| Seat { chips: *c, street: 0, contributed: 0, folded: *c == 0 }).collect();
+
+        Betting { seats, current_bet: 0, min_raise: big_blind, to_act: 0, big_blind, pending: vec![false; chips.len()], may_raise: vec![true; chips.len()] }
+    }
+
+    // Forced bets don't count as acting, so the seat still gets its turn
+    pub fn post(&mut self, seat: usize, amount: u32) -> u32 {
+        let seat = &mut self.seats[seat];
+        let paid = amount.min(seat.chips);
+
+        seat.chips -= paid;
+        seat.street += paid;
+        seat.contributed += paid;
+        self.current_bet = self.current_bet.max(seat.street);
+
+        paid
+    }
+
+    // Antes go in the pot without counting toward anyone's bet on the street
+    pub fn ante(&mut self, amount: u32) {
+        for seat in self.seats.iter_mut().filter(|s| !s.folded) {
+            let paid = amount.min(seat.chips);
+            seat.chips -= paid;
+            seat.contributed += paid;
+        }
+    }
+
+    pub fn open(&mut self, first: usize) {
+        let can_act = self.seats.iter().filter(|s| s.can_act()).count();
+        let current_bet = self.current_bet;
+
+        // A lone player who can still bet only has to act if they are behind
+        self.pending = self.seats.iter().map(|s| s.can_act() && (can_act > 1 || s.street < current_bet)).collect();
+        self.may_raise = vec![true; self.seats.len()];
+        self.to_act = first % self.seats.len();
+        if !self.pending[self.to_act] {
+            self.advance();
+        }
+    }
+
+    pub fn next_street(&mut self, first: usize) {
+        for seat in self.seats.iter_mut() {
+            seat.street = 0;
+        }
+        self.current_bet = 0;
+        self.min_raise = self.big_blind;
+
+        self.open(first);
+    }
+
+    fn advance(&mut self) {
+        let n = self.seats.len();
+        if let Some(next) = (1..=n).map(|i| (self.to_act + i) % n).find(|i| self.pending[*i]) {
+            self.to_act = next;
+        }
+    }
+
+    pub fn pot(&self) -> u32 {
+        self.seats.iter().map(|s| s.contributed).sum()
+    }
+
+    pub fn in_hand(&self) -> Vec<usize> {
+        (0..self.seats.len()).filter(|i| !self.seats[*i].folded).collect()
+    }
+
+    pub fn hand_over(&self) -> bool {
+        self.in_hand().len() <= 1
+    }
+
+    pub fn round_over(&self) -> bool {
+        self.hand_over() || !self.pending.iter().any(|p| *p)
+    }
+
+    pub fn to_call(&self) -> u32 {
+        let seat = &self.seats[self.to_act];
+
+        (self.current_bet - seat.street).min(seat.chips)
+    }
+
+    pub fn min_raise_to(&self) -> u32 {
+        self.current_bet + self.min_raise
+    }
+
+    pub fn max_raise_to(&self) -> u32 {
+        let seat = &self.seats[self.to_act];
+
+        seat.street + seat.chips
+    }
+
+    pub fn legal(&self) -> Vec<Bet> {
+        let seat = &self.seats[self.to_act];
+        let can_raise = self.may_raise[self.to_act] && self.seats.iter().enumerate().any(|(i, s)| i != self.to_act && s.can_act());
+
+        let mut bets = vec![Bet::Fold];
+        if self.to_call() == 0 { bets.push(Bet::Check) } else { bets.push(Bet::Call) }
+        if can_raise && self.max_raise_to() >= self.min_raise_to() { bets.push(Bet::Raise(self.min_raise_to())) }
+        if seat.chips > 0 && (can_raise || self.max_raise_to() <= self.current_bet) { bets.push(Bet::AllIn) }
+
+        bets
+    }
+
+    pub fn act(&mut self, bet: Bet) -> Result<(), String> {
+        let i = self.to_act;
+        if !self.pending[i] {
+            return Err(String::from("It isn't that player's turn"))
+        }
+
+        let to_call = self.current_bet - self.seats[i].street;
+        let max_to = self.max_raise_to();
+        let bet = match bet {
+            // Going all in is a call or a raise depending on the stack behind it
+            Bet::AllIn => if max_to > self.current_bet { Bet::Raise(max_to) } else { Bet::Call },
+            _ => bet
+        };
+
+        match bet {
+            Bet::Fold => self.seats[i].folded = true,
+            Bet::Check => {
+                if to_call > 0 { return Err(format!("There are {} chips to call", to_call)) }
+            },
+            Bet::Call => {
+                if to_call == 0 { return Err(String::from("There is nothing to call")) }
+                self.post(i, to_call);
+            },
+            Bet::Raise(to) => {
+                if !self.may_raise[i] { return Err(String::from("The betting wasn't reopened, you may only call or fold")) }
+                if to > max_to { return Err(format!("You only have enough to raise to {}", max_to)) }
+                if to < self.min_raise_to() && to != max_to { return Err(format!("The minimum raise is to {}", self.min_raise_to())) }
+
+                let increment = to - self.current_bet;
+                let full = increment >= self.min_raise;
+                self.post(i, to - self.seats[i].street);
+                // A short all in doesn't change the size of the next legal raise, and
+                // doesn't reopen the betting for seats that have already acted
+                self.min_raise = self.min_raise.max(increment);
+                for (j, seat) in self.seats.iter().enumerate() {
+                    if full {
+                        self.pending[j] = j != i && seat.can_act();
+                        self.may_raise[j] = true;
+                    }
+                    else if j != i && !self.pending[j] && seat.can_act() {
+                        self.pending[j] = true;
+                        self.may_raise[j] = false;
+                    }
+                }
+            },
+            Bet::AllIn => unreachable!()
+        };
+
+        self.pending[i] = false;
+        self.advance();
+
+        Ok(())
+    }
+
+    // Splits the chips into a main pot and side pots, each with the seats that can win it
+    pub fn pots(&self) -> Vec<Pot> {
+        let in_hand = self.in_hand();
+        let mut levels: Vec<u32> = in_hand.iter().map(|i| self.seats[*i].contributed).collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut pots: Vec<Pot> = vec![];
+        let mut previous = 0;
+        for level in levels {
+            let amount = self.seats.iter().map(|s| s.contributed.min(level) - s.contributed.min(previous)).sum();
+            let eligible: Vec<usize> = in_hand.iter().copied().filter(|i| self.seats[*i].contributed >= level).collect();
+            previous = level;
+
+            match pots.last_mut() {
+                Some(pot) if pot.eligible == eligible => pot.amount += amount,
+                _ => pots.push(Pot { amount, eligible })
+            }
+        }
+
+        pots
+    }
+
+    // Pays each pot to the strongest eligible hands, splitting ties with any odd chips
+    // going to the earliest seats. Returns what each seat won.
+    pub fn settle<K: Ord>(&mut self, strength: impl Fn(usize) -> K) -> Vec<u32> {
+        let mut winnings = vec![0; self.seats.len()];
+
+        for pot in self.pots() {
+            let best = pot.eligible.iter().map(|i| strength(*i)).max().unwrap();
+            let winners: Vec<usize> = pot.eligible.iter().copied().filter(|i| strength(*i) == best).collect();
+            let share = pot.amount / winners.len() as u32;
+            let odd = pot.amount as usize % winners.len();
+
+            for (n, winner) in winners.iter().enumerate() {
+                winnings[*winner] += share + if n < odd { 1 } else { 0 };
+            }
+        }
+        for (seat, won) in self.seats.iter_mut().zip(winnings.iter()) {
+            seat.chips += won;
+            seat.contributed = 0;
+            seat.street = 0;
+        }
+
+        winnings
+    }
+
+    // A simple rule based choice for a bot, from how strong it thinks its hand is
+    // on a scale from 0 (hopeless) to 1 (unbeatable)
+    pub fn bot_bet(&self, strength: f64) -> Bet {
+        let legal = self.legal();
+        let can_raise = legal.iter().any(|b| matches!(b, Bet::Raise(_)));
+        let to_call = self.to_call();
+        let pot = self.pot().max(self.big_blind);
+        let raise = |fraction: f64| {
+            let to = self.current_bet + (pot as f64 * fraction) as u32;
+            if to >= self.max_raise_to() { Bet::AllIn } else { Bet::Raise(to.max(self.min_raise_to())) }
+        };
+        let check_or = |bet: Bet| if to_call == 0 { Bet::Check } else { bet };
+
+        if strength > 0.8 && can_raise { return raise(0.75) }
+        if strength > 0.6 {
+            return if to_call == 0 && can_raise { raise(0.5) } else { check_or(Bet::Call) }
+        }
+        if strength > 0.35 && to_call * 3 <= pot {
+            return check_or(Bet::Call)
+        }
+
+        check_or(Bet::Fold)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn blinds(chips: &[u32]) -> Betting {
+        let mut betting = Betting::new(chips, 10);
+        betting.post(1, 5);
+        betting.post(2, 10);
+        betting.open(3);
+        betting
+    }
+
+    #[test]
+    fn big_blind_gets_an_option() {
+        let mut betting = blinds(&[100, 100, 100]);
+
+        assert_eq!(betting.to_act, 0);
+        betting.act(Bet::Call).unwrap();
+        betting.act(Bet::Call).unwrap();
+        assert_eq!(betting.to_act, 2);
+        assert!(!betting.round_over());
+        assert!(betting.act(Bet::Call).is_err());
+        betting.act(Bet::Check).unwrap();
+
+        assert!(betting.round_over());
+        assert_eq!(betting.pot(), 30);
+    }
+
+    #[test]
+    fn raises_reopen_action() {
+        let mut betting = blinds(&[100, 100, 100]);
+
+        assert!(betting.act(Bet::Raise(15)).is_err());
+        betting.act(Bet::Raise(30)).unwrap();
+        assert_eq!(betting.min_raise, 20);
+        betting.act(Bet::Fold).unwrap();
+        betting.act(Bet::Raise(50)).unwrap();
+        assert_eq!(betting.to_act, 0);
+        betting.act(Bet::Call).unwrap();
+
+        assert!(betting.round_over());
+        assert_eq!(betting.pot(), 105);
+
+        betting.next_street(1);
+        assert_eq!(betting.to_act, 2);
+        assert_eq!(betting.legal(), vec![Bet::Fold, Bet::Check, Bet::Raise(10), Bet::AllIn]);
+    }
+
+    #[test]
+    fn short_all_in_does_not_reopen() {
+        let mut betting = blinds(&[35, 100, 100, 100]);
+
+        betting.act(Bet::Raise(30)).unwrap();
+        betting.act(Bet::AllIn).unwrap();
+        assert_eq!(betting.min_raise, 20);
+        assert!(betting.legal().contains(&Bet::Raise(55)));
+        betting.act(Bet::Call).unwrap();
+        betting.act(Bet::Call).unwrap();
+
+        assert_eq!(betting.to_act, 3);
+        assert_eq!(betting.legal(), vec![Bet::Fold, Bet::Call]);
+        assert!(betting.act(Bet::Raise(55)).is_err());
+        betting.act(Bet::Call).unwrap();
+
+        assert!(betting.round_over());
+        assert_eq!(betting.pot(), 140);
+    }
+
+    #[test]
+    fn side_pots() {
+        let mut betting = blinds(&[20, 100, 100, 50]);
+
+        // Seat 3 shoves, seat 0 calls short all in, seat 1 folds, seat 2 calls
+        betting.act(Bet::AllIn).unwrap();
+        betting.act(Bet::AllIn).unwrap();
+        betting.act(Bet::Fold).unwrap();
+        betting.act(Bet::Call).unwrap();
+        assert!(betting.round_over());
+
+        assert_eq!(betting.pots(), vec![
+            Pot { amount: 65, eligible: vec![0, 2, 3] },
+            Pot { amount: 60, eligible: vec![2, 3] },
+        ]);
+
+        // The short stack has the best hand, seat 3 beats seat 2 for the side pot
+        let winnings = betting.settle(|i| [3, 0, 1, 2][i]);
+        assert_eq!(winnings, vec![65, 0, 0, 60]);
+        assert_eq!(betting.seats[2].chips, 50);
+    }
+
+    #[test]
+    fn uncalled_bets_come_back() {
+        let mut betting = blinds(&[100, 100, 100]);
+
+        betting.act(Bet::Raise(100)).unwrap();
+        betting.act(Bet::Fold).unwrap();
+        betting.act(Bet::Fold).unwrap();
+
+        assert!(betting.hand_over());
+        let winnings = betting.settle(|_| 0);
+        assert_eq!(winnings, vec![115, 0, 0]);
+        assert_eq!(betting.seats[0].chips, 115);
+    }
+
+    #[test]
+    fn split_pot_odd_chip() {
+        let mut betting = Betting::new(&[100, 100, 100], 10);
+        betting.post(0, 7);
+        betting.post(1, 7);
+        betting.post(2, 7);
+
+        let winnings = betting.settle(|i| if i == 1 { 0 } else { 1 });
+        assert_eq!(winnings, vec![11, 0, 10]);
+    }
+
+    #[test]
+    fn nobody_left_to_bet() {
+        let mut betting = blinds(&[100, 5, 100]);
+        betting.act(Bet::AllIn).unwrap();
+        betting.act(Bet::Call).unwrap();
+        assert!(betting.round_over());
+
+        betting.next_street(1);
+        assert!(betting.round_over());
+    }
+
+    #[test]
+    fn antes_and_bring_in() {
+        let mut betting = Betting::new(&[100, 0, 100, 3], 10);
+        betting.ante(5);
+        betting.post(2, 5);
+        betting.open(3);
+
+        assert_eq!(betting.pot(), 18);
+        assert_eq!(betting.current_bet, 5);
+        assert!(betting.seats[3].all_in());
+        assert_eq!(betting.to_act, 0);
+        assert_eq!(betting.min_raise_to(), 15);
+    }
+
+    #[test]
+    fn bots() {
+        let betting = blinds(&[100, 100, 100]);
+
+        assert!(matches!(betting.bot_bet(0.9), Bet::Raise(_)));
+        assert_eq!(betting.bot_bet(0.1), Bet::Fold);
+        assert_eq!(betting.bot_bet(0.65), Bet::Call);
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt;
+use crate::deck::Card;
+
+// Aces count one and court cards ten
+pub fn card_value(card: &Card) -> u32 {
+    match card.rank {
+        14 => 1,
+        11..=13 => 10,
+        rank => rank as u32
+    }
+}
+
+fn is_run(cards: &[Card]) -> bool {
+    let mut ranks: Vec<u8> = cards.iter().map(|c| c.low_rank()).collect();
+    ranks.sort_unstable();
+
+    ranks.windows(2).all(|pair| pair[1] == pair[0] + 1)
+}
+
+// What a hand or crib scores in the show, by the way it scores
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HandCount {
+    pub fifteens: u32,
+    pub pairs: u32,
+    pub runs: u32,
+    pub flush: u32,
+    pub nobs: u32,
+}
+
+impl HandCount {
+    pub fn total(&self) -> u32 {
+        self.fifteens + self.pairs + self.runs + self.flush + self.nobs
+    }
+}
+
+impl fmt::Display for HandCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = [("fifteens", self.fifteens), ("pairs", self.pairs), ("runs", self.runs), ("flush", self.flush), ("nobs", self.nobs)].iter()
+            .filter(|(_, points)| *points > 0)
+            .map(|(name, points)| format!("{} {}", name, points))
+            .collect();
+
+        if parts.is_empty() { write!(f, "0") } else { write!(f, "{} = {}", parts.join(", "), self.total()) }
+    }
+}
+
+// Counts `hand` together with the starter: two for every combination adding to
+// fifteen and every pair, each longest run, a flush of the whole hand (which in the
+// crib must include the starter) and one for the jack of the starter's suit
+pub fn count_hand(hand: &[Card], starter: Option<Card>, crib: bool) -> HandCount {
+    let mut cards = hand.to_vec();
+    cards.extend(starter);
+    let subsets = |size: usize| -> Vec<Vec<Card>> {
+        (0..1u32 << cards.len())
+            .filter(|mask| mask.count_ones() as usize == size)
+            .map(|mask| (0..cards.len()).filter(|i| mask & (1 << i) != 0).map(|i| cards[i]).collect())
+            .collect()
+    };
+    let mut count = HandCount::default();
+
+    for size in 2..=cards.len() {
+        count.fifteens += 2 * subsets(size).iter().filter(|s| s.iter().map(card_value).sum::<u32>() == 15).count() as u32;
+    }
+    count.pairs = 2 * subsets(2).iter().filter(|s| s[0].rank == s[1].rank).count() as u32;
+    count.runs = (3..=cards.len()).rev()
+        .map(|size| size as u32 * subsets(size).iter().filter(|s| is_run(s)).count() as u32)
+        .find(|points| *points > 0)
+        .unwrap_or(0);
+
+    let flush = |cards: &[Card]| cards.len() >= 4 && cards.iter().all(|c| c.suit == cards[0].suit);
+    if flush(&cards) {
+        count.flush = cards.len() as u32;
+    } else if !crib && flush(hand) {
+        count.flush = hand.len() as u32;
+    }
+
+    if let Some(starter) = starter {
+        count.nobs = hand.iter().filter(|c| c.rank == 11 && c.suit == starter.suit).count() as u32;
+    }
+
+    count
+}
+
+// Points for the last card played in pegging, given every card played since the
+// count was last reset: fifteen or thirty one, pairs, and the longest run at the end
+pub fn peg_points(played: &[Card]) -> u32 {
+    let last = match played.last() { Some(card) => card, None => return 0 };
+    let total: u32 = played.iter().map(card_value).sum();
+    let mut points = 0;
+
+    if total == 15 || total == 31 { points += 2 }
+
+    let same = played.iter().rev().take_while(|c| c.rank == last.rank).count();
+    points += match same { 2 => 2, 3 => 6, 4 => 12, _ => 0 };
+
+    points += (3..=played.len()).rev()
+        .find(|n| is_run(&played[played.len() - n..]))
+        .unwrap_or(0) as u32;
+
+    points
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::deck::parse_cards;
+
+    fn count(hand: &str, starter: &str, crib: bool) -> HandCount {
+        count_hand(&parse_cards(hand).unwrap(), Some(starter.parse().unwrap()), crib)
+    }
+
+    #[test]
+    fn perfect_hand() {
+        let best = count("5h5c5dJs", "5s", false);
+
+        assert_eq!(best, HandCount { fifteens: 16, pairs: 12, runs: 0, flush: 0, nobs: 1 });
+        assert_eq!(best.total(), 29);
+    }
+
+    #[test]
+    fn runs_and_fifteens() {
+        assert_eq!(count("4h5h5c6d", "Ks", false), HandCount { fifteens: 8, pairs: 2, runs: 6, flush: 0, nobs: 0 });
+        assert_eq!(count("Ah2c3dKs", "9h", false).total(), 7);
+        assert_eq!(count("Ah2c3d4s", "5h", false), HandCount { fifteens: 2, pairs: 0, runs: 5, flush: 0, nobs: 0 });
+        assert_eq!(count("2c4c8dTh", "Qs", false).total(), 0);
+    }
+
+    #[test]
+    fn flushes() {
+        assert_eq!(count("2h4h6h8h", "Ts", false).flush, 4);
+        assert_eq!(count("2h4h6h8h", "Ts", true).flush, 0);
+        assert_eq!(count("2h4h6h8h", "Th", true).flush, 5);
+        assert_eq!(count("2h4h6hJh", "Qh", false), HandCount { fifteens: 0, pairs: 0, runs: 0, flush: 5, nobs: 1 });
+    }
+
+    #[test]
+    fn pegging() {
+        assert_eq!(peg_points(&parse_cards("5hTs").unwrap()), 2);
+        assert_eq!(peg_points(&parse_cards("7h7c7d").unwrap()), 6);
+        assert_eq!(peg_points(&parse_cards("3h5c4d").unwrap()), 3);
+        assert_eq!(peg_points(&parse_cards("3h5c4d4s").unwrap()), 2);
+        assert_eq!(peg_points(&parse_cards("TsKhAs5c5d").unwrap()), 4);
+        assert_eq!(peg_points(&parse_cards("9h8c").unwrap()), 0);
+    }
+}
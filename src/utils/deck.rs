@@ -1,8 +1,31 @@
 use std::fmt;
+use std::str::FromStr;
+use std::ops::RangeInclusive;
 use rand::thread_rng;
 use rand::seq::SliceRandom;
 
 const MAX_CARD_RANK: u8 = 14;
+const MIN_CARD_RANK: u8 = 2;
+const RANKS_PER_SUIT: u8 = MAX_CARD_RANK - MIN_CARD_RANK + 1;
+
+/// Offsets `rank` by `n`, treating the ranks as a cycle so that counting up
+/// from a king goes to the ace and then the two, and counting down does the reverse.
+pub fn wrapping_rank(rank: u8, n: i8) -> u8 {
+    let offset = rank as i16 - MIN_CARD_RANK as i16 + n as i16;
+
+    offset.rem_euclid(RANKS_PER_SUIT as i16) as u8 + MIN_CARD_RANK
+}
+
+pub fn rank_name(rank: u8) -> String {
+    match rank {
+        13 => String::from(" K"),
+        12 => String::from(" Q"),
+        11 => String::from(" J"),
+        14 => String::from(" A"),
+        10 => String::from("10"),
+        _ => format!(" {}", rank)
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Hash, Eq)]
 pub enum Suit {
@@ -53,6 +76,22 @@ impl Card {
         self.rank == MAX_CARD_RANK
     }
 
+    // Rank of the card when aces are played low, as on most solitaire foundations
+    pub fn low_rank(&self) -> u8
+    {
+        if self.is_ace() { 1 } else { self.rank }
+    }
+
+    pub fn next_rank(&self) -> u8
+    {
+        wrapping_rank(self.rank, 1)
+    }
+
+    pub fn prev_rank(&self) -> u8
+    {
+        wrapping_rank(self.rank, -1)
+    }
+
     pub fn set_visible(&mut self, new_val: bool)
     {
         self.visible = new_val;
@@ -79,10 +118,7 @@ impl Card {
             Suit::Spade => true,
             Suit::Diamond => false,
             Suit::Heart => false,
-            Suit::Joker => {
-                if self.rank == 0 { return true }
-                else { return false };
-            }
+            Suit::Joker => self.rank == 0
         }
     }
 }
@@ -93,16 +129,51 @@ impl fmt::Display for Card {
         {
             return write!(f, "? ?")
         }
-        let rank_str: String = match self.rank {
-            13 => String::from(" K"),
-            12 => String::from(" Q"),
-            11 => String::from(" J"),
-            14 => String::from(" A"),
-            10 => String::from("10"),
-            _ => format!(" {}", self.rank)
+        write!(f, "{}{}", rank_name(self.rank), self.suit)
+    }
+}
+
+// Parses short card names such as "As", "Td" or "10h", rank first and then suit
+impl FromStr for Card {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Card, String> {
+        let mut cards = parse_cards(s)?;
+
+        if cards.len() != 1 { return Err(format!("Expected a single card, found {} in {}", cards.len(), s)) }
+
+        Ok(cards.remove(0))
+    }
+}
+
+/// Parses a run of short card names written together, like "AsKs" or "2h7d9c".
+pub fn parse_cards(s: &str) -> Result<Vec<Card>, String> {
+    let mut cards = vec![];
+    let mut chars = s.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let rank = match c.to_ascii_uppercase() {
+            'A' => 14,
+            'K' => 13,
+            'Q' => 12,
+            'J' => 11,
+            'T' => 10,
+            '1' if chars.peek() == Some(&'0') => { chars.next(); 10 },
+            '2'..='9' => c.to_digit(10).unwrap() as u8,
+            _ => return Err(format!("Unknown rank {} in {}", c, s))
+        };
+        let suit = match chars.next().map(|c| c.to_ascii_lowercase()) {
+            Some('s') => Suit::Spade,
+            Some('h') => Suit::Heart,
+            Some('d') => Suit::Diamond,
+            Some('c') => Suit::Club,
+            Some(c) => return Err(format!("Unknown suit {} in {}", c, s)),
+            None => return Err(format!("Missing a suit at the end of {}", s))
         };
-        write!(f, "{}{}", rank_str, self.suit)
+        cards.push(Card::new(suit, rank)?);
     }
+
+    Ok(cards)
 }
 
 pub struct Stack {
@@ -127,6 +198,27 @@ impl Stack {
         Stack { cards, top: 0 }
     }
 
+    // Every suit from `ranks.start()` up to `ranks.end()`, like the 9 to ace deck used in euchre
+    pub fn new_partial_deck(ranks: RangeInclusive<u8>) -> Stack {
+        let cards = Stack::new_deck(false).cards.into_iter().filter(|c| ranks.contains(&c.rank)).collect();
+
+        Stack { cards, top: 0 }
+    }
+
+    // `count` copies of a partial deck shuffled together, like the doubled 9 to ace deck
+    // of pinochle where every card has a twin
+    pub fn new_partial_decks(count: usize, ranks: RangeInclusive<u8>) -> Stack {
+        let cards = (0..count).flat_map(|_| Stack::new_partial_deck(ranks.clone()).cards).collect();
+
+        Stack { cards, top: 0 }
+    }
+
+    pub fn new_decks(count: usize, with_joker: bool) -> Stack {
+        let cards = (0..count).flat_map(|_| Stack::new_deck(with_joker).cards).collect();
+
+        Stack { cards, top: 0 }
+    }
+
     pub fn new_deck_reverse(with_joker: bool) -> Stack {
         let mut cards = vec![];
         for i in 0..4 {
@@ -149,6 +241,10 @@ impl Stack {
         Stack { cards, top: 0 }
     }
 
+    pub fn from_cards(cards: Vec<Card>) -> Stack {
+        Stack { cards, top: 0 }
+    }
+
     pub fn shuffle(&mut self) {
         self.cards.shuffle(&mut thread_rng());
     }
@@ -179,6 +275,21 @@ impl Stack {
     pub fn top_card(&self) -> Option<&Card> {
         self.cards.get(self.top)
     }
+
+    // The card that will be dealt last, which some games turn face up to show trumps
+    pub fn bottom_card(&self) -> Option<&Card> {
+        if self.size() == 0 { None } else { self.cards.last() }
+    }
+
+    // Adds cards under the rest, like the tricks won in war going under a player's
+    // pile. Cards already drawn are let go once they make up most of the stack.
+    pub fn put_bottom(&mut self, cards: Vec<Card>) {
+        if self.top > self.size() {
+            self.cards.drain(..self.top);
+            self.top = 0;
+        }
+        self.cards.extend(cards);
+    }
 }
 
 #[cfg(test)]
@@ -192,8 +303,9 @@ mod test {
         assert_eq!(format!("{}", card.unwrap()), " 3❤");
     }
 
+    #[test]
     fn new_hidden_card() {
-        let card = Card::new(Suit::Heart, 3);
+        let card = Card::new(Suit::Heart, 3).map(Card::hide);
         assert_eq!(format!("{}", card.unwrap()), "? ?");
     }
 
@@ -211,6 +323,56 @@ mod test {
         assert_eq!(deck.cards.len(), 54)
     }
 
+    #[test]
+    fn multiple_decks() {
+        let deck = Stack::new_decks(2, false);
+
+        assert_eq!(deck.size(), 104)
+    }
+
+    #[test]
+    fn partial_deck() {
+        let deck = Stack::new_partial_deck(9..=14);
+
+        assert_eq!(deck.size(), 24);
+        assert!(deck.cards.iter().all(|c| c.rank >= 9));
+    }
+
+    #[test]
+    fn partial_decks() {
+        let deck = Stack::new_partial_decks(2, 9..=14);
+        let ace = "As".parse::<Card>().unwrap();
+
+        assert_eq!(deck.size(), 48);
+        assert_eq!(deck.cards.iter().filter(|c| **c == ace).count(), 2);
+    }
+
+    #[test]
+    fn bottom_card() {
+        let mut pile = Stack::from_cards(parse_cards("2h3h4h").unwrap());
+        assert_eq!(pile.bottom_card(), Some(&"4h".parse().unwrap()));
+
+        pile.deal(3);
+        assert_eq!(pile.bottom_card(), None);
+    }
+
+    #[test]
+    fn put_bottom() {
+        let mut pile = Stack::from_cards(parse_cards("2h3h4h").unwrap());
+        pile.deal(2);
+        pile.put_bottom(parse_cards("5s6s").unwrap());
+
+        assert_eq!(pile.size(), 3);
+        assert_eq!(pile.cards.len(), 3);
+        assert_eq!(pile.deal(3), parse_cards("4h5s6s").unwrap());
+    }
+
+    #[test]
+    fn low_rank() {
+        assert_eq!(Card::new(Suit::Club, 14).unwrap().low_rank(), 1);
+        assert_eq!(Card::new(Suit::Club, 13).unwrap().low_rank(), 13);
+    }
+
     #[test]
     fn invalid_card() {
         let card = Card::new(Suit::Club, 15);
@@ -244,6 +406,26 @@ mod test {
         assert_eq!(size_after, 51);
     }
 
+    #[test]
+    fn wrapping_ranks() {
+        assert_eq!(wrapping_rank(13, 1), 14);
+        assert_eq!(wrapping_rank(14, 1), 2);
+        assert_eq!(wrapping_rank(2, -1), 14);
+        assert_eq!(wrapping_rank(12, 4), 3);
+
+        let king = Card::new(Suit::Spade, 13).unwrap();
+        assert_eq!(king.next_rank(), 14);
+        assert_eq!(king.prev_rank(), 12);
+    }
+
+    #[test]
+    fn from_cards() {
+        let mut stack = Stack::from_cards(vec![Card::new(Suit::Club, 4).unwrap(), Card::new(Suit::Heart, 9).unwrap()]);
+
+        assert_eq!(stack.size(), 2);
+        assert_eq!(stack.draw().rank, 4);
+    }
+
     #[test]
     fn deal() {
         let mut deck = Stack::new_deck(false);
@@ -255,5 +437,15 @@ mod test {
         assert_eq!(hand.len(), 5);
         assert_eq!(deck.size(), 47)
     }
+    #[test]
+    fn parse() {
+        assert_eq!("As".parse::<Card>(), Ok(Card { suit: Suit::Spade, rank: 14, visible: true }));
+        assert_eq!("10h".parse::<Card>(), "Th".parse::<Card>());
+        assert_eq!(parse_cards("2h7d9c").unwrap().iter().map(|c| c.rank).collect::<Vec<u8>>(), vec![2, 7, 9]);
+        assert!("AsKs".parse::<Card>().is_err());
+        assert!(parse_cards("Ax").is_err());
+        assert!(parse_cards("1s").is_err());
+        assert!(parse_cards("K").is_err());
+    }
 }
 
@@ -0,0 +1,224 @@
+use rand::thread_rng;
+use rand::seq::SliceRandom;
+use crate::deck::{Card, Suit, parse_cards};
+use crate::poker::{evaluate, HandRank};
+
+const BOARD_SIZE: usize = 5;
+const HOLE_CARDS: usize = 2;
+// Boards to enumerate before sampling them at random instead
+const EXHAUSTIVE_LIMIT: u64 = 500_000;
+pub const DEFAULT_TRIALS: usize = 100_000;
+
+// Percentage of boards a hand wins outright, and ties for the best hand
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Equity {
+    pub win: f64,
+    pub tie: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquityReport {
+    pub players: Vec<Equity>,
+    pub boards: usize,
+    pub exhaustive: bool,
+}
+
+fn full_deck() -> Vec<Card> {
+    [Suit::Spade, Suit::Heart, Suit::Diamond, Suit::Club].iter()
+        .flat_map(|suit| (2..=14).map(move |rank| Card { suit: *suit, rank, visible: true }))
+        .collect()
+}
+
+fn choose(n: usize, k: usize) -> u64 {
+    (0..k as u64).fold(1, |acc, i| acc * (n as u64 - i) / (i + 1))
+}
+
+// Calls `f` with every way of picking `k` of `cards`
+fn for_each_combination(cards: &[Card], k: usize, mut f: impl FnMut(&[Card])) {
+    let n = cards.len();
+    let mut indices: Vec<usize> = (0..k).collect();
+
+    loop {
+        let chosen: Vec<Card> = indices.iter().map(|i| cards[*i]).collect();
+        f(&chosen);
+
+        // Move the rightmost index that still has room, and reset the ones after it
+        let mut i = k;
+        loop {
+            if i == 0 { return }
+            i -= 1;
+            if indices[i] != i + n - k { break }
+        }
+        indices[i] += 1;
+        for j in i + 1..k {
+            indices[j] = indices[j - 1] + 1;
+        }
+    }
+}
+
+struct Tally {
+    wins: Vec<usize>,
+    ties: Vec<usize>,
+    boards: usize,
+}
+
+impl Tally {
+    fn score(&mut self, hands: &[Vec<Card>], board: &[Card], runout: &[Card]) {
+        let ranks: Vec<HandRank> = hands.iter()
+            .map(|hand| {
+                let cards: Vec<Card> = hand.iter().chain(board).chain(runout).copied().collect();
+                evaluate(&cards).unwrap()
+            })
+            .collect();
+        let best = *ranks.iter().max().unwrap();
+        let winners: Vec<usize> = (0..hands.len()).filter(|i| ranks[*i] == best).collect();
+
+        if winners.len() == 1 {
+            self.wins[winners[0]] += 1;
+        } else {
+            for i in winners { self.ties[i] += 1 }
+        }
+        self.boards += 1;
+    }
+}
+
+pub fn equity(hands: &[Vec<Card>], board: &[Card], dead: &[Card]) -> Result<EquityReport, String> {
+    equity_with_trials(hands, board, dead, DEFAULT_TRIALS)
+}
+
+/// Hold'em equity for each hand. Every possible runout of the board is dealt when
+/// there are few enough of them, otherwise `trials` random runouts are sampled.
+pub fn equity_with_trials(hands: &[Vec<Card>], board: &[Card], dead: &[Card], trials: usize) -> Result<EquityReport, String> {
+    if hands.len() < 2 { return Err(format!("Equity needs at least 2 hands, {} provided", hands.len())) }
+    if let Some(hand) = hands.iter().find(|h| h.len() != HOLE_CARDS) {
+        return Err(format!("Each hand needs {} cards, one has {}", HOLE_CARDS, hand.len()))
+    }
+    if board.len() > BOARD_SIZE { return Err(format!("The board has at most {} cards, {} provided", BOARD_SIZE, board.len())) }
+
+    let mut known: Vec<Card> = vec![];
+    for card in hands.iter().flatten().chain(board).chain(dead) {
+        if card.suit == Suit::Joker { return Err(String::from("Jokers aren't used in poker")) }
+        if known.iter().any(|k| k.suit == card.suit && k.rank == card.rank) {
+            return Err(format!("{} appears more than once", card.to_string().trim()))
+        }
+        known.push(*card);
+    }
+
+    let remaining: Vec<Card> = full_deck().into_iter().filter(|c| !known.iter().any(|k| k.suit == c.suit && k.rank == c.rank)).collect();
+    let needed = BOARD_SIZE - board.len();
+    if remaining.len() < needed { return Err(String::from("There aren't enough cards left to finish the board")) }
+
+    let mut tally = Tally { wins: vec![0; hands.len()], ties: vec![0; hands.len()], boards: 0 };
+    let exhaustive = choose(remaining.len(), needed) <= EXHAUSTIVE_LIMIT;
+    if exhaustive {
+        for_each_combination(&remaining, needed, |runout| tally.score(hands, board, runout));
+    } else {
+        let mut rng = thread_rng();
+        for _ in 0..trials {
+            let runout: Vec<Card> = remaining.choose_multiple(&mut rng, needed).copied().collect();
+            tally.score(hands, board, &runout);
+        }
+    }
+
+    let percent = |count: usize| if tally.boards == 0 { 0.0 } else { count as f64 * 100.0 / tally.boards as f64 };
+    let players = (0..hands.len()).map(|i| Equity { win: percent(tally.wins[i]), tie: percent(tally.ties[i]) }).collect();
+
+    Ok(EquityReport { players, boards: tally.boards, exhaustive })
+}
+
+// The `equity` command line: hands as arguments, with optional `--board`, `--dead`
+// and `--trials`, for example `cards equity AsKs QdQc --board 2h7d9c`
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut names = vec![];
+    let mut hands = vec![];
+    let mut board = vec![];
+    let mut dead = vec![];
+    let mut trials = DEFAULT_TRIALS;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--board" => board = parse_cards(value()?)?,
+            "--dead" => dead = parse_cards(value()?)?,
+            "--trials" => trials = value()?.parse().map_err(|_| String::from("--trials needs a number"))?,
+            hand => {
+                hands.push(parse_cards(hand)?);
+                names.push(hand.to_string());
+            }
+        }
+    }
+
+    let report = equity_with_trials(&hands, &board, &dead, trials)?;
+    for (name, equity) in names.iter().zip(report.players.iter()) {
+        println!("{:<8} win {:>6.2}%  tie {:>6.2}%", name, equity.win, equity.tie);
+    }
+    let method = if report.exhaustive { "every" } else { "a random sample of" };
+    println!("Dealt {} {} boards", method, report.boards);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn hand(s: &str) -> Vec<Card> {
+        parse_cards(s).unwrap()
+    }
+
+    #[test]
+    fn combinations() {
+        let deck = full_deck();
+        let mut count = 0;
+
+        for_each_combination(&deck[..10], 3, |_| count += 1);
+        assert_eq!(count, choose(10, 3) as usize);
+        assert_eq!(choose(48, 5), 1_712_304);
+    }
+
+    #[test]
+    fn complete_board() {
+        let report = equity(&[hand("AsAh"), hand("KsKh")], &hand("2c3d4h8s9c"), &[]).unwrap();
+
+        assert_eq!(report.boards, 1);
+        assert_eq!(report.players, vec![Equity { win: 100.0, tie: 0.0 }, Equity { win: 0.0, tie: 0.0 }]);
+
+        let royal = equity(&[hand("2c3d"), hand("4c5d")], &hand("AsKsQsJsTs"), &[]).unwrap();
+        assert_eq!(royal.players[0], Equity { win: 0.0, tie: 100.0 });
+    }
+
+    #[test]
+    fn counts_outs() {
+        // Nine hearts, three aces and three kings give the flush draw fifteen outs
+        let hands = [hand("AhKh"), hand("QsQc")];
+        let report = equity(&hands, &hand("2h7h9c3d"), &[]).unwrap();
+
+        assert!(report.exhaustive);
+        assert_eq!(report.boards, 44);
+        assert_eq!(report.players[0].win, 15.0 * 100.0 / 44.0);
+        assert!((report.players[0].win + report.players[1].win - 100.0).abs() < 1e-9);
+
+        let dead = equity(&hands, &hand("2h7h9c3d"), &hand("Ad")).unwrap();
+        assert_eq!(dead.players[0].win, 14.0 * 100.0 / 43.0);
+    }
+
+    #[test]
+    fn sampled_preflop() {
+        let report = equity_with_trials(&[hand("AsAh"), hand("KsKh")], &[], &[], 20_000).unwrap();
+
+        assert!(!report.exhaustive);
+        assert_eq!(report.boards, 20_000);
+        assert!((report.players[0].win - 82.0).abs() < 3.0);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(equity(&[hand("AsAh")], &[], &[]).is_err());
+        assert!(equity(&[hand("AsAh"), hand("AsKh")], &[], &[]).is_err());
+        assert!(equity(&[hand("AsAh"), hand("KsKhQs")], &[], &[]).is_err());
+        assert!(equity(&[hand("AsAh"), hand("KsKh")], &hand("2c3d4h8s9cTc"), &[]).is_err());
+        assert!(run(&["AsAh".to_string(), "--board".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,348 @@
+use std::fmt;
+use std::cmp::Ordering;
+use crate::deck::{Card, Suit, rank_name};
+
+const MIN_HAND: usize = 5;
+const MAX_HAND: usize = 7;
+// Bits for A, 5, 4, 3 and 2, the only straight where the ace plays low
+const WHEEL: u16 = 0b1_0000_0000_1111;
+const CATEGORY_SHIFT: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush
+}
+
+const CATEGORIES: [Category; 9] = [
+    Category::HighCard,
+    Category::Pair,
+    Category::TwoPair,
+    Category::ThreeOfAKind,
+    Category::Straight,
+    Category::Flush,
+    Category::FullHouse,
+    Category::FourOfAKind,
+    Category::StraightFlush
+];
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let category_str = match self {
+            Category::HighCard => "High card",
+            Category::Pair => "Pair",
+            Category::TwoPair => "Two pair",
+            Category::ThreeOfAKind => "Three of a kind",
+            Category::Straight => "Straight",
+            Category::Flush => "Flush",
+            Category::FullHouse => "Full house",
+            Category::FourOfAKind => "Four of a kind",
+            Category::StraightFlush => "Straight flush"
+        };
+        write!(f, "{}", category_str)
+    }
+}
+
+// The strength of the best five cards in a hand. The category sits in the high bits
+// and the ranks that break ties follow it four bits apiece, most significant first,
+// so two hands compare with a single integer comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandRank(u32);
+
+impl HandRank {
+    fn new(category: Category, tiebreaks: &[u8]) -> HandRank {
+        let ranks = tiebreaks.iter().enumerate().fold(0, |acc, (i, rank)| acc | (*rank as u32) << (16 - 4 * i));
+
+        HandRank((category as u32) << CATEGORY_SHIFT | ranks)
+    }
+
+    pub fn category(&self) -> Category {
+        CATEGORIES[(self.0 >> CATEGORY_SHIFT) as usize]
+    }
+
+    // The ranks that decide between two hands of the same category, in order
+    pub fn tiebreaks(&self) -> Vec<u8> {
+        let count = match self.category() {
+            Category::Straight | Category::StraightFlush => 1,
+            Category::FullHouse | Category::FourOfAKind => 2,
+            Category::TwoPair | Category::ThreeOfAKind => 3,
+            Category::Pair => 4,
+            Category::HighCard | Category::Flush => 5
+        };
+
+        (0..count).map(|i| ((self.0 >> (16 - 4 * i)) & 0xF) as u8).collect()
+    }
+}
+
+impl fmt::Display for HandRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranks: Vec<String> = self.tiebreaks().iter().map(|r| rank_name(*r).trim().to_string()).collect();
+        write!(f, "{} ({})", self.category(), ranks.join(", "))
+    }
+}
+
+// The rank of the highest card in the best straight within `ranks`, if there is one
+fn straight_high(ranks: u16) -> Option<u8> {
+    let runs = ranks & (ranks << 1) & (ranks << 2) & (ranks << 3) & (ranks << 4);
+
+    if runs != 0 { return Some(15 - runs.leading_zeros() as u8 + 2) }
+    if ranks & WHEEL == WHEEL { return Some(5) }
+
+    None
+}
+
+fn top_ranks(ranks: u16, count: usize, tiebreaks: &mut [u8; 5], from: usize) {
+    let mut remaining = ranks;
+    for slot in tiebreaks.iter_mut().skip(from).take(count) {
+        let bit = 15 - remaining.leading_zeros() as u8;
+        *slot = bit + 2;
+        remaining &= !(1 << bit);
+    }
+}
+
+// Ranks the best five card poker hand that can be made from 5, 6 or 7 cards
+pub fn evaluate(cards: &[Card]) -> Result<HandRank, String> {
+    if cards.len() < MIN_HAND || cards.len() > MAX_HAND {
+        return Err(format!("Hands are between {} and {} cards, {} provided", MIN_HAND, MAX_HAND, cards.len()))
+    }
+
+    let mut suits = [0u16; 4];
+    let mut counts = [0u8; 13];
+    for card in cards {
+        let suit = match card.suit {
+            Suit::Club => 0,
+            Suit::Diamond => 1,
+            Suit::Heart => 2,
+            Suit::Spade => 3,
+            Suit::Joker => return Err(String::from("Jokers cannot be evaluated in a poker hand"))
+        };
+        let rank = card.rank.wrapping_sub(2);
+        if rank > 12 { return Err(format!("Invalid rank {}", card.rank)) }
+
+        suits[suit] |= 1 << rank;
+        counts[rank as usize] += 1;
+    }
+
+    let mut tiebreaks = [0u8; 5];
+
+    // With seven cards or fewer a flush can never share the hand with a full house or quads
+    if let Some(flush) = suits.iter().find(|s| s.count_ones() >= 5) {
+        if let Some(high) = straight_high(*flush) {
+            return Ok(HandRank::new(Category::StraightFlush, &[high]))
+        }
+        top_ranks(*flush, 5, &mut tiebreaks, 0);
+        return Ok(HandRank::new(Category::Flush, &tiebreaks))
+    }
+
+    let ranks = suits.iter().fold(0, |acc, s| acc | s);
+    let (mut quads, mut trips, mut pairs) = (0u16, 0u16, 0u16);
+    for (rank, count) in counts.iter().enumerate() {
+        match count {
+            4 => quads |= 1 << rank,
+            3 => trips |= 1 << rank,
+            2 => pairs |= 1 << rank,
+            _ => ()
+        }
+    }
+    let highest = |mask: u16| 15 - mask.leading_zeros() as u8;
+
+    if quads != 0 {
+        let quad = highest(quads);
+        top_ranks(ranks & !(1 << quad), 1, &mut tiebreaks, 1);
+        tiebreaks[0] = quad + 2;
+        return Ok(HandRank::new(Category::FourOfAKind, &tiebreaks[..2]))
+    }
+    if trips != 0 {
+        let trip = highest(trips);
+        let rest = (trips & !(1 << trip)) | pairs;
+        if rest != 0 {
+            return Ok(HandRank::new(Category::FullHouse, &[trip + 2, highest(rest) + 2]))
+        }
+    }
+    if let Some(high) = straight_high(ranks) {
+        return Ok(HandRank::new(Category::Straight, &[high]))
+    }
+    if trips != 0 {
+        let trip = highest(trips);
+        tiebreaks[0] = trip + 2;
+        top_ranks(ranks & !(1 << trip), 2, &mut tiebreaks, 1);
+        return Ok(HandRank::new(Category::ThreeOfAKind, &tiebreaks[..3]))
+    }
+    if pairs.count_ones() >= 2 {
+        let high = highest(pairs);
+        let low = highest(pairs & !(1 << high));
+        tiebreaks[0] = high + 2;
+        tiebreaks[1] = low + 2;
+        top_ranks(ranks & !(1 << high) & !(1 << low), 1, &mut tiebreaks, 2);
+        return Ok(HandRank::new(Category::TwoPair, &tiebreaks[..3]))
+    }
+    if pairs != 0 {
+        let pair = highest(pairs);
+        tiebreaks[0] = pair + 2;
+        top_ranks(ranks & !(1 << pair), 3, &mut tiebreaks, 1);
+        return Ok(HandRank::new(Category::Pair, &tiebreaks[..4]))
+    }
+
+    top_ranks(ranks, 5, &mut tiebreaks, 0);
+    Ok(HandRank::new(Category::HighCard, &tiebreaks))
+}
+
+// Ranks up to four cards, such as the up cards in stud. Only pairs, trips and quads
+// count since there aren't enough cards for a straight or a flush.
+pub fn evaluate_partial(cards: &[Card]) -> Result<HandRank, String> {
+    if cards.is_empty() || cards.len() >= MIN_HAND {
+        return Err(format!("Partial hands are between 1 and {} cards, {} provided", MIN_HAND - 1, cards.len()))
+    }
+
+    let mut counts = [0u8; 15];
+    for card in cards {
+        if card.suit == Suit::Joker { return Err(String::from("Jokers cannot be evaluated in a poker hand")) }
+        counts[card.rank as usize] += 1;
+    }
+
+    // Bigger groups first, then higher ranks
+    let mut groups: Vec<(u8, u8)> = (2..15).filter(|r| counts[*r as usize] > 0).map(|r| (counts[r as usize], r)).collect();
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+
+    let category = match (groups[0].0, groups.get(1).map(|g| g.0)) {
+        (4, _) => Category::FourOfAKind,
+        (3, _) => Category::ThreeOfAKind,
+        (2, Some(2)) => Category::TwoPair,
+        (2, _) => Category::Pair,
+        _ => Category::HighCard
+    };
+    let tiebreaks: Vec<u8> = groups.iter().map(|g| g.1).collect();
+
+    Ok(HandRank::new(category, &tiebreaks))
+}
+
+pub fn compare(a: &[Card], b: &[Card]) -> Result<Ordering, String> {
+    Ok(evaluate(a)?.cmp(&evaluate(b)?))
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::deck::Stack;
+    use Suit::{Club as C, Diamond as D, Heart as H, Spade as S};
+
+    fn hand(cards: &[(u8, Suit)]) -> Vec<Card> {
+        cards.iter().map(|(rank, suit)| Card::new(*suit, *rank).unwrap()).collect()
+    }
+
+    fn rank(cards: &[(u8, Suit)]) -> HandRank {
+        evaluate(&hand(cards)).unwrap()
+    }
+
+    #[test]
+    fn categories() {
+        assert_eq!(rank(&[(2, C), (5, D), (9, H), (11, S), (13, C)]).category(), Category::HighCard);
+        assert_eq!(rank(&[(2, C), (2, D), (9, H), (11, S), (13, C)]).category(), Category::Pair);
+        assert_eq!(rank(&[(2, C), (2, D), (9, H), (9, S), (13, C)]).category(), Category::TwoPair);
+        assert_eq!(rank(&[(2, C), (2, D), (2, H), (9, S), (13, C)]).category(), Category::ThreeOfAKind);
+        assert_eq!(rank(&[(10, C), (11, D), (12, H), (13, S), (14, C)]).category(), Category::Straight);
+        assert_eq!(rank(&[(2, C), (5, C), (9, C), (11, C), (13, C)]).category(), Category::Flush);
+        assert_eq!(rank(&[(2, C), (2, D), (2, H), (9, S), (9, C)]).category(), Category::FullHouse);
+        assert_eq!(rank(&[(2, C), (2, D), (2, H), (2, S), (9, C)]).category(), Category::FourOfAKind);
+        assert_eq!(rank(&[(14, H), (2, H), (3, H), (4, H), (5, H)]).category(), Category::StraightFlush);
+    }
+
+    #[test]
+    fn ace_low_straight() {
+        let wheel = rank(&[(14, C), (2, D), (3, H), (4, S), (5, C)]);
+        let six_high = rank(&[(6, C), (2, D), (3, H), (4, S), (5, C)]);
+
+        assert_eq!(wheel.category(), Category::Straight);
+        assert_eq!(wheel.tiebreaks(), vec![5]);
+        assert!(six_high > wheel);
+        // An ace can't wrap round the middle of a straight
+        assert_eq!(rank(&[(12, C), (13, D), (14, H), (2, S), (3, C)]).category(), Category::HighCard);
+    }
+
+    #[test]
+    fn tiebreaks() {
+        let kings_up = rank(&[(13, C), (13, D), (4, H), (4, S), (9, C)]);
+        let kings_up_better_kicker = rank(&[(13, H), (13, S), (4, C), (4, D), (10, C)]);
+        let queens_up = rank(&[(12, C), (12, D), (11, H), (11, S), (14, C)]);
+
+        assert!(kings_up_better_kicker > kings_up);
+        assert!(kings_up > queens_up);
+        assert_eq!(kings_up.tiebreaks(), vec![13, 4, 9]);
+        assert_eq!(format!("{}", kings_up), "Two pair (K, 4, 9)");
+
+        assert_eq!(
+            compare(&hand(&[(5, C), (5, D), (14, H), (8, S), (3, C)]), &hand(&[(5, H), (5, S), (14, C), (8, D), (3, D)])),
+            Ok(Ordering::Equal)
+        );
+    }
+
+    #[test]
+    fn best_of_seven() {
+        // Two sets of trips make a full house with the lower set as the pair
+        let full = rank(&[(9, C), (9, D), (9, H), (4, S), (4, C), (4, D), (14, C)]);
+        assert_eq!(full.category(), Category::FullHouse);
+        assert_eq!(full.tiebreaks(), vec![9, 4]);
+
+        // Three pairs only play the top two, with the best remaining kicker
+        let two_pair = rank(&[(9, C), (9, D), (7, H), (7, S), (4, C), (4, D), (2, C)]);
+        assert_eq!(two_pair.tiebreaks(), vec![9, 7, 4]);
+
+        // The flush beats the straight also in the hand
+        let flush = rank(&[(5, H), (6, H), (7, C), (8, H), (9, D), (2, H), (13, H)]);
+        assert_eq!(flush.category(), Category::Flush);
+        assert_eq!(flush.tiebreaks(), vec![13, 8, 6, 5, 2]);
+
+        let six_cards = rank(&[(14, S), (14, D), (14, C), (13, S), (13, D), (14, H)]);
+        assert_eq!(six_cards.category(), Category::FourOfAKind);
+        assert_eq!(six_cards.tiebreaks(), vec![14, 13]);
+    }
+
+    #[test]
+    fn partial_hands() {
+        let partial = |cards: &[(u8, Suit)]| evaluate_partial(&hand(cards)).unwrap();
+
+        assert_eq!(partial(&[(9, C), (9, D), (13, H)]).category(), Category::Pair);
+        assert_eq!(partial(&[(9, C), (9, D), (13, H)]).tiebreaks(), vec![9, 13, 0, 0]);
+        assert_eq!(partial(&[(9, C), (9, D), (4, H), (4, S)]).category(), Category::TwoPair);
+        assert!(partial(&[(3, C), (3, D), (3, H)]) > partial(&[(14, C), (14, D), (13, H), (13, S)]));
+        assert!(partial(&[(14, C)]) > partial(&[(13, C), (12, D)]));
+        assert!(evaluate_partial(&[]).is_err());
+        assert!(evaluate_partial(&hand(&[(2, C), (5, D), (9, H), (11, S), (13, C)])).is_err());
+    }
+
+    #[test]
+    fn invalid_hands() {
+        assert!(evaluate(&hand(&[(2, C), (5, D), (9, H), (11, S)])).is_err());
+        assert!(evaluate(&Stack::new_deck(false).deal(8)).is_err());
+        assert!(evaluate(&[Card::new(Suit::Joker, 1).unwrap(), Card::new(C, 2).unwrap(), Card::new(C, 3).unwrap(), Card::new(C, 4).unwrap(), Card::new(C, 5).unwrap()]).is_err());
+    }
+
+    // Every five card hand from a deck, checked against the well known category counts
+    #[test]
+    fn all_five_card_hands() {
+        let deck = Stack::new_deck(false).deal(52);
+        let mut counts = [0usize; 9];
+
+        for a in 0..48 {
+            for b in a + 1..49 {
+                for c in b + 1..50 {
+                    for d in c + 1..51 {
+                        for e in d + 1..52 {
+                            let five = [deck[a], deck[b], deck[c], deck[d], deck[e]];
+                            counts[evaluate(&five).unwrap().category() as usize] += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(counts, [1302540, 1098240, 123552, 54912, 10200, 5108, 3744, 624, 40]);
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt;
+use crate::deck::Card;
+
+// Deadwood counts cards the way cribbage does, aces one and court cards ten
+pub use crate::cribbage::card_value as deadwood_value;
+
+fn is_set(meld: &[Card]) -> bool {
+    meld.iter().all(|c| c.rank == meld[0].rank)
+}
+
+// Sets of three or four of a rank, and runs of three or more in a suit with aces
+// low, as bitmasks over the cards in `hand`
+fn candidate_melds(hand: &[Card]) -> Vec<u32> {
+    let mut melds = vec![];
+
+    for rank in 2..=14 {
+        let same: Vec<usize> = (0..hand.len()).filter(|i| hand[*i].rank == rank).collect();
+        if same.len() < 3 { continue }
+        for left_out in 0..=same.len() {
+            let mask = same.iter().enumerate().filter(|(n, _)| *n != left_out).fold(0u32, |mask, (_, i)| mask | 1 << *i);
+            if mask.count_ones() >= 3 { melds.push(mask) }
+        }
+    }
+
+    let mut by_rank: Vec<usize> = (0..hand.len()).collect();
+    by_rank.sort_by_key(|i| hand[*i].low_rank());
+    for start in 0..by_rank.len() {
+        let first = hand[by_rank[start]];
+        let mut run = vec![by_rank[start]];
+        for i in by_rank.iter().skip(start + 1) {
+            let last = hand[*run.last().unwrap()];
+            if hand[*i].suit == first.suit && hand[*i].low_rank() == last.low_rank() + 1 {
+                run.push(*i);
+                if run.len() >= 3 { melds.push(run.iter().fold(0u32, |mask, i| mask | 1 << *i)) }
+            }
+        }
+    }
+
+    melds.sort_unstable();
+    melds.dedup();
+    melds
+}
+
+// The lowest deadwood for the cards not in `used`, and the melds that get it. Each
+// card in turn either goes to deadwood or into one of the melds it could be part of.
+fn arrange(hand: &[Card], candidates: &[u32], used: u32) -> (u32, Vec<u32>) {
+    let next = match (0..hand.len()).find(|i| used & 1 << i == 0) { Some(i) => i, None => return (0, vec![]) };
+
+    let (deadwood, melds) = arrange(hand, candidates, used | 1 << next);
+    let mut best = (deadwood + deadwood_value(&hand[next]), melds);
+
+    for meld in candidates.iter().filter(|m| *m & 1 << next != 0 && *m & used == 0) {
+        let (deadwood, mut melds) = arrange(hand, candidates, used | meld);
+        if deadwood < best.0 {
+            melds.insert(0, *meld);
+            best = (deadwood, melds);
+        }
+    }
+
+    best
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Melds {
+    pub melds: Vec<Vec<Card>>,
+    pub deadwood: Vec<Card>,
+}
+
+impl Melds {
+    pub fn deadwood_points(&self) -> u32 {
+        self.deadwood.iter().map(deadwood_value).sum()
+    }
+}
+
+impl fmt::Display for Melds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards = |cards: &[Card]| cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ");
+        let melds: Vec<String> = self.melds.iter().map(|m| format!("[{}]", cards(m))).collect();
+
+        write!(f, "{} deadwood: {} ({})", melds.join(" "), cards(&self.deadwood), self.deadwood_points())
+    }
+}
+
+// Arranges any hand into the melds that leave the least deadwood
+pub fn best_melds(hand: &[Card]) -> Melds {
+    let (_, masks) = arrange(hand, &candidate_melds(hand), 0);
+    let in_meld = masks.iter().fold(0, |all, m| all | m);
+
+    let melds = masks.iter().map(|mask| {
+        let mut meld: Vec<Card> = (0..hand.len()).filter(|i| mask & 1 << i != 0).map(|i| hand[i]).collect();
+        meld.sort_by_key(|c| c.low_rank());
+        meld
+    }).collect();
+    let deadwood = (0..hand.len()).filter(|i| in_meld & 1 << i == 0).map(|i| hand[i]).collect();
+
+    Melds { melds, deadwood }
+}
+
+fn extends(meld: &[Card], card: &Card) -> bool {
+    if is_set(meld) {
+        return meld.len() < 4 && card.rank == meld[0].rank
+    }
+
+    let low = meld.first().unwrap().low_rank();
+    let high = meld.last().unwrap().low_rank();
+    card.suit == meld[0].suit && (card.low_rank() + 1 == low || card.low_rank() == high + 1)
+}
+
+// Lays `cards` off onto `melds` wherever they fit, extending the melds as it goes so a
+// run can keep growing, and returns whatever is left over
+pub fn lay_off(melds: &mut [Vec<Card>], cards: &[Card]) -> Vec<Card> {
+    let mut left = cards.to_vec();
+
+    while let Some((i, meld)) = left.iter().enumerate().find_map(|(i, card)| melds.iter().position(|m| extends(m, card)).map(|m| (i, m))) {
+        let card = left.remove(i);
+        melds[meld].push(card);
+        melds[meld].sort_by_key(|c| c.low_rank());
+    }
+
+    left
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::deck::parse_cards;
+
+    fn points(hand: &str) -> u32 {
+        best_melds(&parse_cards(hand).unwrap()).deadwood_points()
+    }
+
+    #[test]
+    fn arranging() {
+        // The six of spades does more in the set than at the end of the run
+        let hand = parse_cards("3s4s5s6s6h6dKcQd2h9c").unwrap();
+        let melds = best_melds(&hand);
+        assert_eq!(melds.deadwood_points(), 31);
+        assert_eq!(melds.melds, vec![parse_cards("3s4s5s").unwrap(), parse_cards("6s6h6d").unwrap()]);
+
+        assert_eq!(points("AsAhAd2c3c4c5c9d9s9h"), 0);
+        assert_eq!(points("Ac2c3cQcKc"), 20);
+        assert_eq!(points("QcKcAc"), 21);
+        assert_eq!(points("7h7s7d7c8h9h"), 0);
+    }
+
+    #[test]
+    fn sets_of_four() {
+        // Only a set of three leaves the seven of hearts for the run
+        assert_eq!(points("7h7s7d7c8h9h2s"), 2);
+        assert_eq!(points("7h7s7d7c8h2s"), 10);
+        assert_eq!(best_melds(&[]).deadwood_points(), 0);
+    }
+
+    #[test]
+    fn laying_off() {
+        let mut melds = vec![parse_cards("5h6h7h").unwrap(), parse_cards("QsQdQc").unwrap()];
+        let left = lay_off(&mut melds, &parse_cards("9hQh8h4d").unwrap());
+
+        assert_eq!(left, parse_cards("4d").unwrap());
+        assert_eq!(melds[0], parse_cards("5h6h7h8h9h").unwrap());
+        assert_eq!(melds[1].len(), 4);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::deck::{Stack, Card, Suit};
+
+// What playing a card of a given rank does besides matching the discard
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Effect {
+    // The next player misses their turn
+    Skip,
+    // Play changes direction, which with two players is the same as a skip
+    Reverse,
+    // The next player draws two cards and misses their turn
+    DrawTwo,
+    // Plays on anything and names the suit to follow
+    Wild,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheddingRules {
+    pub effects: Vec<(u8, Effect)>,
+}
+
+impl SheddingRules {
+    // Eights are wild and everything else only has to match
+    pub fn crazy_eights() -> SheddingRules {
+        SheddingRules { effects: vec![(8, Effect::Wild)] }
+    }
+
+    // Crazy eights with action cards in the style of Uno: queens skip, aces reverse
+    // and twos make the next player draw
+    pub fn action_cards() -> SheddingRules {
+        SheddingRules { effects: vec![(8, Effect::Wild), (12, Effect::Skip), (14, Effect::Reverse), (2, Effect::DrawTwo)] }
+    }
+
+    pub fn effect(&self, card: &Card) -> Option<Effect> {
+        self.effects.iter().find(|(rank, _)| *rank == card.rank).map(|(_, effect)| *effect)
+    }
+}
+
+// A game where players take turns matching the top discard by suit or rank, and
+// the first to get rid of their cards wins
+pub struct Shedding {
+    pub rules: SheddingRules,
+    pub hands: Vec<Vec<Card>>,
+    pub stock: Stack,
+    pub discards: Vec<Card>,
+    // The suit to follow, which a wild card can change from the top discard's
+    pub suit: Suit,
+    pub to_play: usize,
+    pub clockwise: bool,
+}
+
+impl Shedding {
+    // Seven cards each with two players, five with more, then cards are turned
+    // until the discard pile starts with one that has no effect
+    pub fn new(players: usize, rules: SheddingRules) -> Result<Shedding, String> {
+        if !(2..=6).contains(&players) {
+            return Err(format!("Shedding games are played by 2 to 6 players, {} provided", players))
+        }
+
+        let mut stock = Stack::new_deck(false);
+        stock.shuffle();
+        let hand_size = if players == 2 { 7 } else { 5 };
+        let hands = (0..players).map(|_| stock.deal(hand_size)).collect();
+
+        let mut discards = vec![stock.draw()];
+        while rules.effect(discards.last().unwrap()).is_some() {
+            discards.push(stock.draw());
+        }
+        let suit = discards.last().unwrap().suit;
+
+        Ok(Shedding { rules, hands, stock, discards, suit, to_play: 0, clockwise: true })
+    }
+
+    pub fn players(&self) -> usize {
+        self.hands.len()
+    }
+
+    pub fn top(&self) -> Card {
+        *self.discards.last().unwrap()
+    }
+
+    // The seat `steps` turns on from the player to play
+    pub fn next_seat(&self, steps: usize) -> usize {
+        let n = self.players();
+        let steps = steps % n;
+
+        if self.clockwise { (self.to_play + steps) % n } else { (self.to_play + n - steps) % n }
+    }
+
+    pub fn can_play(&self, card: &Card) -> bool {
+        self.rules.effect(card) == Some(Effect::Wild) || card.suit == self.suit || card.rank == self.top().rank
+    }
+
+    pub fn playable(&self, seat: usize) -> Vec<usize> {
+        (0..self.hands[seat].len()).filter(|i| self.can_play(&self.hands[seat][*i])).collect()
+    }
+
+    // Turns the discards, all but the top card, into a new stock once it runs out
+    fn refill(&mut self) {
+        if self.stock.size() > 0 || self.discards.len() < 2 { return }
+
+        let top = self.discards.pop().unwrap();
+        self.stock = Stack::from_cards(std::mem::replace(&mut self.discards, vec![top]));
+        self.stock.shuffle();
+    }
+
+    // Draws a card for `seat`, or nothing if every card is already in someone's hand
+    pub fn draw(&mut self, seat: usize) -> Option<Card> {
+        self.refill();
+        if self.stock.size() == 0 { return None }
+
+        let card = self.stock.draw();
+        self.hands[seat].push(card);
+        Some(card)
+    }
+
+    // Ends the turn of a player who couldn't or wouldn't play
+    pub fn pass(&mut self) {
+        self.to_play = self.next_seat(1);
+    }
+
+    // Plays a card from the hand of the player to play, naming the suit to follow
+    // when it's wild, and passes the turn on according to its effect
+    pub fn play(&mut self, index: usize, named: Option<Suit>) -> Result<Card, String> {
+        let seat = self.to_play;
+        let card = *self.hands[seat].get(index).ok_or_else(|| String::from("Unable to play that card"))?;
+        if !self.can_play(&card) {
+            return Err(format!("{} doesn't match {} or {}", card, self.top(), self.suit))
+        }
+
+        let effect = self.rules.effect(&card);
+        self.suit = match (effect, named) {
+            (Some(Effect::Wild), Some(suit)) => suit,
+            (Some(Effect::Wild), None) => return Err(format!("Name a suit to follow {}", card)),
+            _ => card.suit
+        };
+        self.hands[seat].remove(index);
+        self.discards.push(card);
+
+        let steps = match effect {
+            Some(Effect::Skip) => 2,
+            Some(Effect::Reverse) => {
+                self.clockwise = !self.clockwise;
+                if self.players() == 2 { 2 } else { 1 }
+            },
+            Some(Effect::DrawTwo) => {
+                let victim = self.next_seat(1);
+                for _ in 0..2 { self.draw(victim); }
+                2
+            },
+            _ => 1
+        };
+        self.to_play = self.next_seat(steps);
+
+        Ok(card)
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        self.hands.iter().position(|hand| hand.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::deck::parse_cards;
+
+    fn game(players: usize, rules: SheddingRules, hands: &[&str], top: &str) -> Shedding {
+        let mut game = Shedding::new(players, rules).unwrap();
+        game.hands = hands.iter().map(|h| parse_cards(h).unwrap()).collect();
+        game.discards = parse_cards(top).unwrap();
+        game.suit = game.top().suit;
+        game
+    }
+
+    #[test]
+    fn dealing() {
+        let two = Shedding::new(2, SheddingRules::action_cards()).unwrap();
+        assert!(two.hands.iter().all(|h| h.len() == 7));
+        assert_eq!(two.rules.effect(&two.top()), None);
+
+        assert_eq!(Shedding::new(6, SheddingRules::crazy_eights()).unwrap().hands[5].len(), 5);
+        assert!(Shedding::new(7, SheddingRules::crazy_eights()).is_err());
+    }
+
+    #[test]
+    fn matching() {
+        let mut game = game(3, SheddingRules::crazy_eights(), &["5h5c9d8s", "3c", "4c"], "5s");
+
+        assert_eq!(game.playable(0), vec![0, 1, 3]);
+        assert!(game.play(2, None).is_err());
+        assert!(game.play(3, None).is_err());
+        game.play(3, Some(Suit::Diamond)).unwrap();
+        assert_eq!(game.suit, Suit::Diamond);
+        assert_eq!(game.to_play, 1);
+        assert!(game.playable(1).is_empty());
+    }
+
+    #[test]
+    fn actions() {
+        let mut game = game(4, SheddingRules::action_cards(), &["QhAh2h", "3c", "4c", "5c6c"], "9h");
+
+        game.play(0, None).unwrap();
+        assert_eq!(game.to_play, 2);
+
+        game.to_play = 0;
+        game.play(0, None).unwrap();
+        assert!(!game.clockwise);
+        assert_eq!(game.to_play, 3);
+
+        game.to_play = 0;
+        game.play(0, None).unwrap();
+        assert_eq!(game.hands[3].len(), 4);
+        assert_eq!(game.to_play, 2);
+        assert_eq!(game.winner(), Some(0));
+    }
+
+    #[test]
+    fn reshuffling() {
+        let mut game = game(2, SheddingRules::crazy_eights(), &["", ""], "5s6s7s");
+        game.stock.deal(game.stock.size());
+
+        assert_eq!(game.draw(0).map(|c| c.rank == 5 || c.rank == 6), Some(true));
+        assert_eq!(game.discards, parse_cards("7s").unwrap());
+        game.draw(0);
+        assert_eq!(game.draw(1), None);
+    }
+}
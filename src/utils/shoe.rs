@@ -0,0 +1,92 @@
+use crate::deck::{Stack, Card};
+
+pub const MAX_DECKS: usize = 8;
+
+// A dealing shoe holding several shuffled decks. A cut card is placed part way
+// through, and once it has been dealt the shoe should be reshuffled before the
+// next round.
+pub struct Shoe {
+    decks: usize,
+    stack: Stack,
+    // Number of cards left in the shoe when the cut card comes out
+    cut_card: usize,
+}
+
+impl Shoe {
+    pub fn new(decks: usize, penetration: f64) -> Result<Shoe, String> {
+        if decks == 0 || decks > MAX_DECKS { return Err(format!("A shoe holds between 1 and {} decks, {} provided", MAX_DECKS, decks)) }
+        if !(0.0..=1.0).contains(&penetration) { return Err(format!("Penetration must be between 0 and 1, {} provided", penetration)) }
+
+        let total = decks * 52;
+        let cut_card = total - (total as f64 * penetration) as usize;
+        let mut shoe = Shoe { decks, stack: Stack::new_decks(decks, false), cut_card };
+
+        shoe.stack.shuffle();
+
+        Ok(shoe)
+    }
+
+    pub fn decks(&self) -> usize {
+        self.decks
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.stack.size()
+    }
+
+    pub fn cut_card_reached(&self) -> bool {
+        self.stack.size() <= self.cut_card
+    }
+
+    pub fn reshuffle(&mut self) {
+        self.stack = Stack::new_decks(self.decks, false);
+        self.stack.shuffle();
+    }
+
+    // Deals the next card. A shoe that runs dry part way through a round is
+    // reshuffled on the spot rather than ending the round.
+    pub fn draw(&mut self) -> Card {
+        if self.stack.size() == 0 {
+            self.reshuffle();
+        }
+
+        self.stack.draw()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn deck_limits() {
+        assert!(Shoe::new(0, 0.75).is_err());
+        assert!(Shoe::new(9, 0.75).is_err());
+        assert!(Shoe::new(6, 1.5).is_err());
+        assert_eq!(Shoe::new(8, 0.75).unwrap().remaining(), 416);
+    }
+
+    #[test]
+    fn cut_card() {
+        let mut shoe = Shoe::new(1, 0.5).unwrap();
+
+        for _ in 0..25 { shoe.draw(); }
+        assert!(!shoe.cut_card_reached());
+
+        shoe.draw();
+        assert!(shoe.cut_card_reached());
+
+        shoe.reshuffle();
+        assert_eq!(shoe.remaining(), 52);
+    }
+
+    #[test]
+    fn never_runs_dry() {
+        let mut shoe = Shoe::new(1, 1.0).unwrap();
+
+        for _ in 0..60 { shoe.draw(); }
+
+        assert_eq!(shoe.remaining(), 44);
+    }
+}
@@ -0,0 +1,325 @@
+use crate::deck::{Card, Suit};
+
+// What sets one trick-taking game apart from another when it comes to playing a
+// card. The defaults are plain suits with aces high, where any card may be discarded.
+pub trait TrickRules {
+    fn trump(&self) -> Option<Suit>;
+
+    fn effective_suit(&self, card: &Card) -> Suit {
+        card.suit
+    }
+
+    fn card_order(&self, card: &Card) -> u8 {
+        card.rank
+    }
+
+    fn must_trump(&self) -> bool {
+        false
+    }
+
+    fn must_head(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StandardRules {
+    pub trump: Option<Suit>,
+}
+
+impl TrickRules for StandardRules {
+    fn trump(&self) -> Option<Suit> {
+        self.trump
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trick {
+    pub leader: usize,
+    // Each seat with the card it played, in the order they were played
+    pub plays: Vec<(usize, Card)>,
+}
+
+impl Trick {
+    pub fn new(leader: usize) -> Trick {
+        Trick { leader, plays: vec![] }
+    }
+
+    pub fn led_suit(&self, rules: &impl TrickRules) -> Option<Suit> {
+        self.plays.first().map(|(_, card)| rules.effective_suit(card))
+    }
+
+    pub fn cards(&self) -> Vec<Card> {
+        self.plays.iter().map(|(_, card)| *card).collect()
+    }
+
+    // The seat taking the trick so far, with the highest trump or else the highest
+    // card of the suit led. When two identical cards are played the first one wins.
+    pub fn winner(&self, rules: &impl TrickRules) -> Option<usize> {
+        let led = self.led_suit(rules)?;
+        let key = |card: &Card| {
+            let suit = rules.effective_suit(card);
+            let class = if Some(suit) == rules.trump() { 2 } else if suit == led { 1 } else { 0 };
+            (class, rules.card_order(card))
+        };
+
+        let mut best = self.plays[0];
+        for play in self.plays.iter().skip(1) {
+            if key(&play.1) > key(&best.1) { best = *play }
+        }
+
+        Some(best.0)
+    }
+
+    // Whether `card` from `seat` would be taking the trick once played
+    pub fn would_win(&self, seat: usize, card: Card, rules: &impl TrickRules) -> bool {
+        let mut trick = self.clone();
+        trick.plays.push((seat, card));
+
+        trick.winner(rules) == Some(seat)
+    }
+}
+
+// The cards in `hand` that may be played to `trick`, following the suit led when possible,
+// and otherwise trumping and beating the winning card if the rules demand it
+pub fn legal_plays(hand: &[Card], trick: &Trick, rules: &impl TrickRules) -> Vec<usize> {
+    let all: Vec<usize> = (0..hand.len()).collect();
+    let led = match trick.led_suit(rules) { Some(suit) => suit, None => return all };
+
+    let following: Vec<usize> = all.iter().copied().filter(|i| rules.effective_suit(&hand[*i]) == led).collect();
+    let playable = if !following.is_empty() {
+        following
+    }
+    else if rules.must_trump() {
+        prefer(hand, all, |c| Some(rules.effective_suit(c)) == rules.trump())
+    }
+    else {
+        return all
+    };
+
+    // The seat is only there to tell the card apart from those already played
+    if rules.must_head() { prefer(hand, playable, |c| trick.would_win(usize::MAX, *c, rules)) } else { playable }
+}
+
+// Narrows `legal` to the cards that pass `keep`, unless that would leave nothing to play.
+// This covers house rules such as not leading hearts until they are broken.
+pub fn prefer(hand: &[Card], legal: Vec<usize>, keep: impl Fn(&Card) -> bool) -> Vec<usize> {
+    let kept: Vec<usize> = legal.iter().copied().filter(|i| keep(&hand[*i])).collect();
+
+    if kept.is_empty() { legal } else { kept }
+}
+
+// The card a bot plays from `legal` when it only cares whether it takes the trick:
+// the cheapest card that takes it, or the highest that doesn't, saving trumps
+// either way. With no such card it plays its lowest.
+pub fn bot_card(hand: &[Card], legal: &[usize], trick: &Trick, seat: usize, rules: &impl TrickRules, take: bool) -> usize {
+    let wins = |i: &&usize| trick.would_win(seat, hand[**i], rules);
+    let cheapest = |i: &&usize| (Some(rules.effective_suit(&hand[**i])) == rules.trump(), rules.card_order(&hand[**i]));
+    let lowest = *legal.iter().min_by_key(cheapest).unwrap();
+
+    let card = if take { legal.iter().filter(wins).min_by_key(cheapest) } else { legal.iter().filter(|i| !wins(i)).max_by_key(cheapest) };
+    card.copied().unwrap_or(lowest)
+}
+
+fn suit_order(suit: Suit) -> u8 {
+    match suit {
+        Suit::Spade => 0,
+        Suit::Heart => 1,
+        Suit::Club => 2,
+        Suit::Diamond => 3,
+        Suit::Joker => 4
+    }
+}
+
+// Groups a hand by suit, highest cards first, with suits alternating in colour
+pub fn sort_hand(hand: &mut [Card], rules: &impl TrickRules) {
+    hand.sort_by_key(|card| (suit_order(rules.effective_suit(card)), std::cmp::Reverse(rules.card_order(card))));
+}
+
+// A round of tricks: every seat plays a card in turn, and whoever takes a trick
+// collects its cards and leads the next one. Seats sitting out, like the partner of
+// a lone hand in euchre, are skipped.
+pub struct TrickRound {
+    pub hands: Vec<Vec<Card>>,
+    pub trick: Trick,
+    pub last_trick: Option<Trick>,
+    pub taken: Vec<Vec<Card>>,
+    pub tricks_won: Vec<usize>,
+    pub sitting_out: Vec<bool>,
+}
+
+impl TrickRound {
+    pub fn new(hands: Vec<Vec<Card>>, leader: usize) -> TrickRound {
+        let players = hands.len();
+
+        TrickRound {
+            hands,
+            trick: Trick::new(leader),
+            last_trick: None,
+            taken: vec![vec![]; players],
+            tricks_won: vec![0; players],
+            sitting_out: vec![false; players]
+        }
+    }
+
+    pub fn players(&self) -> usize {
+        self.hands.len()
+    }
+
+    // Takes a seat out of the round, setting its cards aside
+    pub fn sit_out(&mut self, seat: usize) {
+        self.sitting_out[seat] = true;
+        self.hands[seat].clear();
+    }
+
+    fn active(&self) -> usize {
+        self.sitting_out.iter().filter(|out| !**out).count()
+    }
+
+    // The next seat from the leader round that hasn't played yet. A leader sitting
+    // out passes the lead to the next seat along.
+    pub fn to_play(&self) -> usize {
+        let n = self.players();
+
+        (0..n).map(|i| (self.trick.leader + i) % n)
+            .filter(|seat| !self.sitting_out[*seat])
+            .nth(self.trick.plays.len())
+            .unwrap()
+    }
+
+    pub fn tricks_played(&self) -> usize {
+        self.tricks_won.iter().sum()
+    }
+
+    pub fn legal(&self, rules: &impl TrickRules) -> Vec<usize> {
+        legal_plays(&self.hands[self.to_play()], &self.trick, rules)
+    }
+
+    // Plays a card from the hand of the seat to play. Returns the winner once the
+    // trick is complete.
+    pub fn play(&mut self, index: usize, rules: &impl TrickRules) -> Result<Option<usize>, String> {
+        if !self.legal(rules).contains(&index) {
+            return Err(String::from("Unable to play that card"))
+        }
+
+        let seat = self.to_play();
+        let card = self.hands[seat].remove(index);
+        self.trick.plays.push((seat, card));
+        if self.trick.plays.len() < self.active() {
+            return Ok(None)
+        }
+
+        let winner = self.trick.winner(rules).unwrap();
+        let trick = std::mem::replace(&mut self.trick, Trick::new(winner));
+        self.taken[winner].extend(trick.cards());
+        self.tricks_won[winner] += 1;
+        self.last_trick = Some(trick);
+
+        Ok(Some(winner))
+    }
+
+    pub fn finished(&self) -> bool {
+        self.trick.plays.is_empty() && self.hands.iter().all(|h| h.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::deck::parse_cards;
+
+    const NO_TRUMP: StandardRules = StandardRules { trump: None };
+    const SPADES: StandardRules = StandardRules { trump: Some(Suit::Spade) };
+
+    fn trick(leader: usize, cards: &str) -> Trick {
+        let cards = parse_cards(cards).unwrap();
+        let plays = cards.into_iter().enumerate().map(|(i, c)| ((leader + i) % 4, c)).collect();
+
+        Trick { leader, plays }
+    }
+
+    #[test]
+    fn winners() {
+        assert_eq!(trick(1, "5hKhAd2h").winner(&NO_TRUMP), Some(2));
+        assert_eq!(trick(1, "5hKh2sAh").winner(&NO_TRUMP), Some(0));
+        assert_eq!(trick(1, "5hKh2sAh").winner(&SPADES), Some(3));
+        assert_eq!(trick(3, "9c9cTd").winner(&NO_TRUMP), Some(3));
+        assert_eq!(Trick::new(0).winner(&NO_TRUMP), None);
+    }
+
+    #[test]
+    fn follow_suit() {
+        let hand = parse_cards("2h9sKhAc").unwrap();
+
+        assert_eq!(legal_plays(&hand, &Trick::new(0), &NO_TRUMP), vec![0, 1, 2, 3]);
+        assert_eq!(legal_plays(&hand, &trick(0, "5h"), &NO_TRUMP), vec![0, 2]);
+        assert_eq!(legal_plays(&hand, &trick(0, "5d"), &NO_TRUMP), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn preferring() {
+        let hand = parse_cards("2h9sKh").unwrap();
+
+        assert_eq!(prefer(&hand, vec![0, 1, 2], |c| c.suit != Suit::Heart), vec![1]);
+        assert_eq!(prefer(&hand, vec![0, 2], |c| c.suit != Suit::Heart), vec![0, 2]);
+        assert!(trick(0, "5h").would_win(1, hand[2], &NO_TRUMP));
+        assert!(!trick(0, "5h").would_win(1, hand[1], &NO_TRUMP));
+        assert!(trick(0, "5h").would_win(1, hand[1], &SPADES));
+    }
+
+    #[test]
+    fn bot_cards() {
+        let rules = StandardRules { trump: Some(Suit::Spade) };
+        let hand = parse_cards("2s9hKh3h").unwrap();
+        let mut trick = Trick::new(1);
+        trick.plays.push((1, "Th".parse().unwrap()));
+
+        assert_eq!(bot_card(&hand, &[1, 2, 3], &trick, 0, &rules, true), 2);
+        assert_eq!(bot_card(&hand, &[1, 2, 3], &trick, 0, &rules, false), 1);
+        assert_eq!(bot_card(&hand, &[0], &trick, 0, &rules, false), 0);
+        assert_eq!(bot_card(&hand, &[0, 1], &trick, 0, &rules, true), 0);
+    }
+
+    #[test]
+    fn sorting() {
+        let mut hand = parse_cards("2h9sKhAc3s").unwrap();
+        sort_hand(&mut hand, &NO_TRUMP);
+
+        assert_eq!(hand, parse_cards("9s3sKh2hAc").unwrap());
+    }
+
+    #[test]
+    fn round() {
+        let hands = ["Ah2c", "Kh3c", "2h4c"].iter().map(|h| parse_cards(h).unwrap()).collect();
+        let mut round = TrickRound::new(hands, 1);
+
+        assert_eq!(round.to_play(), 1);
+        assert_eq!(round.play(0, &NO_TRUMP), Ok(None));
+        assert!(round.play(1, &NO_TRUMP).is_err());
+        assert_eq!(round.play(0, &NO_TRUMP), Ok(None));
+        assert_eq!(round.play(0, &NO_TRUMP), Ok(Some(0)));
+
+        assert_eq!(round.to_play(), 0);
+        assert_eq!(round.taken[0].len(), 3);
+        round.play(0, &NO_TRUMP).unwrap();
+        round.play(0, &NO_TRUMP).unwrap();
+        assert_eq!(round.play(0, &NO_TRUMP), Ok(Some(2)));
+        assert!(round.finished());
+        assert_eq!(round.tricks_won, vec![1, 0, 1]);
+    }
+
+    #[test]
+    fn sitting_out() {
+        let hands = ["Ah", "Kh", "2h", "3h"].iter().map(|h| parse_cards(h).unwrap()).collect();
+        let mut round = TrickRound::new(hands, 2);
+        round.sit_out(2);
+
+        assert_eq!(round.to_play(), 3);
+        round.play(0, &NO_TRUMP).unwrap();
+        round.play(0, &NO_TRUMP).unwrap();
+        assert_eq!(round.play(0, &NO_TRUMP), Ok(Some(0)));
+        assert!(round.finished());
+        assert_eq!(round.taken[0].len(), 3);
+    }
+}